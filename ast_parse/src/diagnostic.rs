@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Diagnostic of a source file's code from parsing or transpiling a function's sources.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnDiagnostic {
+    /// Identifier of the diagnostic's error or lint reported by the parser.
+    pub code: Option<String>,
+    pub message: String,
+    /// Absolute path of the diagnostic's source file.
+    pub path: PathBuf,
+    pub severity: FnDiagnosticSeverity,
+    /// Range of the diagnostic's code, absent when a diagnostic does not point to code.
+    pub span: Option<FnDiagnosticSpan>,
+}
+
+impl fmt::Display for FnDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.to_string_lossy())?;
+        if let Some(span) = &self.span {
+            write!(f, ":{}:{}", span.start.line, span.start.column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum FnDiagnosticSeverity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnDiagnosticSpan {
+    pub start: FnSourcePosition,
+    pub end: FnSourcePosition,
+}
+
+/// Position in a source file with a 1-based line and column of chars and a byte offset.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnSourcePosition {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl FnSourcePosition {
+    /// Position of a byte offset in a source file's content.
+    pub fn from_offset(content: &str, offset: usize) -> Self {
+        let offset = offset.min(content.len());
+        let preceding = &content[..content.floor_char_boundary(offset)];
+        let line_start = preceding.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Self {
+            line: preceding.matches('\n').count() + 1,
+            column: preceding[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
+
+/// Formats diagnostics for error messages, one diagnostic per line.
+pub fn format_diagnostics(diagnostics: &[FnDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
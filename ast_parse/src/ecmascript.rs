@@ -1,30 +1,628 @@
-use std::path::Path;
-use swc_ecma_visit::FoldWith;
+use crate::swc::{ParsedSource, SourceType, SwcParser};
+use crate::{AstParseResult, FnHandler, FnHandlerDefinition, FnHandlerSignature};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use swc_common::comments::{CommentKind, Comments};
+use swc_common::{BytePos, Spanned};
+use swc_ecma_ast::{
+    ArrowExpr, AssignExpr, AssignOp, AssignTarget, CallExpr, Callee, Decl, DefaultDecl,
+    ExportSpecifier, Expr, Function, ImportSpecifier, Lit, MemberExpr, MemberProp, Module,
+    ModuleDecl, ModuleExportName, ModuleItem, Pat, Program, Prop, PropName, PropOrSpread,
+    SimpleAssignTarget, Stmt,
+};
 
-use crate::swc::visitors::CollectExportedFnsVisitor;
-use crate::swc::{parse_program_from_fs, CompileError};
-use crate::{AstParseError, AstParseResult};
+/// Collects the handlers of a JS or TS source. A source without `import` or `export`
+/// declarations is a CommonJS script. Handlers re-exported from other sources are not collected,
+/// as resolving their sources requires the function's import resolution, which is provided to
+/// `collect_exported_fns_with_re_exports`.
+pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<FnHandler>> {
+    let source = parse_source(path)?;
+    Ok(match &source.program {
+        Program::Module(module) => collect_module_exports(&source, module)
+            .into_iter()
+            .filter_map(|module_export| match module_export {
+                ModuleExport::Handler(handler) => Some(handler),
+                _ => None,
+            })
+            .collect(),
+        Program::Script(script) => collect_commonjs_handlers(&source, &script.body),
+    })
+}
+
+/// Collects the handlers of a JS or TS source and the handlers it re-exports from the sources
+/// that `resolve` resolves a re-export's specifier to from the re-exporting source, such as
+/// `export { GET } from './impl.js'`. Paths are relative to `project_dir`. Sources already
+/// visited by a circular re-export do not export handlers.
+pub fn collect_exported_fns_with_re_exports(
+    project_dir: &Path,
+    path: &Path,
+    resolve: &mut dyn FnMut(&Path, &str) -> Option<PathBuf>,
+) -> AstParseResult<Vec<FnHandler>> {
+    collect_source_handlers(project_dir, path, resolve, &mut Vec::new())
+}
+
+fn collect_source_handlers(
+    project_dir: &Path,
+    path: &Path,
+    resolve: &mut dyn FnMut(&Path, &str) -> Option<PathBuf>,
+    visited: &mut Vec<PathBuf>,
+) -> AstParseResult<Vec<FnHandler>> {
+    if visited.iter().any(|visited| visited == path) {
+        return Ok(Vec::new());
+    }
+    visited.push(path.to_path_buf());
+    let source = parse_source(&project_dir.join(path))?;
+    let module = match &source.program {
+        Program::Module(module) => module,
+        Program::Script(script) => return Ok(collect_commonjs_handlers(&source, &script.body)),
+    };
+    let mut handlers: Vec<FnHandler> = Vec::new();
+    for module_export in collect_module_exports(&source, module) {
+        match module_export {
+            ModuleExport::Handler(handler) => handlers.push(handler),
+            ModuleExport::ReExport {
+                name,
+                specifier,
+                imported,
+            } => {
+                let Some(re_exported_path) = resolve(path, &specifier) else {
+                    continue;
+                };
+                let re_exported =
+                    collect_source_handlers(project_dir, &re_exported_path, resolve, visited)?;
+                if let Some(handler) = re_exported
+                    .into_iter()
+                    .find(|handler| handler.name == imported)
+                {
+                    handlers.push(FnHandler {
+                        definition: FnHandlerDefinition::ReExport {
+                            name: imported,
+                            path: re_exported_path,
+                        },
+                        doc: handler.doc,
+                        name,
+                        signature: handler.signature,
+                    });
+                }
+            }
+            ModuleExport::ReExportAll { specifier } => {
+                let Some(re_exported_path) = resolve(path, &specifier) else {
+                    continue;
+                };
+                // export * does not re-export a module's default export
+                for handler in
+                    collect_source_handlers(project_dir, &re_exported_path, resolve, visited)?
+                {
+                    if handler.name != "default" {
+                        handlers.push(FnHandler {
+                            definition: FnHandlerDefinition::ReExport {
+                                name: handler.name.clone(),
+                                path: re_exported_path.clone(),
+                            },
+                            doc: handler.doc,
+                            name: handler.name,
+                            signature: handler.signature,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(handlers)
+}
+
+/// Parses a JS or TS source as a CommonJS or ES module by its `.cjs` or `.mjs` extension or else
+/// by detecting `import` and `export` declarations.
+fn parse_source(path: &Path) -> AstParseResult<ParsedSource> {
+    let source_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("cjs") => SourceType::CommonJs,
+        Some("mjs") => SourceType::EsModule,
+        _ => SourceType::Detect,
+    };
+    Ok(SwcParser::new().parse_source_from_fs(path, source_type)?)
+}
+
+/// Export of an ES module that could be a handler fn.
+#[derive(Debug, PartialEq)]
+pub enum ModuleExport {
+    /// Handler fn defined by the module.
+    Handler(FnHandler),
+    /// Binding imported or re-exported from another module and exported as `name`.
+    ReExport {
+        name: String,
+        specifier: String,
+        imported: String,
+    },
+    /// `export * from` re-exporting another module's named exports.
+    ReExportAll { specifier: String },
+}
+
+/// Binding of a module's top-level scope that could be exported as a handler.
+#[derive(Clone)]
+enum Local {
+    Fn(LocalFn),
+    Import { specifier: String, imported: String },
+}
+
+/// Fn of a module's top-level scope with the doc comment of its declaration.
+#[derive(Clone)]
+struct LocalFn {
+    definition: FnHandlerDefinition,
+    doc: Option<String>,
+    signature: Option<FnHandlerSignature>,
+}
+
+impl LocalFn {
+    fn handler(&self, name: String) -> FnHandler {
+        FnHandler {
+            definition: self.definition.clone(),
+            doc: self.doc.clone(),
+            name,
+            signature: self.signature.clone(),
+        }
+    }
+
+    /// Alias of the fn bound as `local`, documented by the alias's doc comment if it has one.
+    fn alias(&self, local: &str, doc: Option<String>) -> Self {
+        Self {
+            definition: FnHandlerDefinition::Alias {
+                local: local.to_string(),
+            },
+            doc: doc.or_else(|| self.doc.clone()),
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+/// Collects exports of an ES module that are fns declared by the module, fns wrapped with
+/// middleware, aliases of either and bindings re-exported from other modules.
+pub fn collect_module_exports(source: &ParsedSource, module: &Module) -> Vec<ModuleExport> {
+    let mut locals: HashMap<String, Local> = HashMap::new();
+    // fn declarations and imports are hoisted and may be referenced before they are declared
+    for module_item in &module.body {
+        match module_item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => {
+                for specifier in &import.specifiers {
+                    let (local, imported) = match specifier {
+                        ImportSpecifier::Named(named) if !named.is_type_only => (
+                            &named.local,
+                            named
+                                .imported
+                                .as_ref()
+                                .map(|imported| imported.atom().to_string())
+                                .unwrap_or_else(|| named.local.sym.to_string()),
+                        ),
+                        ImportSpecifier::Default(default) => {
+                            (&default.local, String::from("default"))
+                        }
+                        _ => continue,
+                    };
+                    locals.insert(
+                        local.sym.to_string(),
+                        Local::Import {
+                            specifier: import.src.value.to_string(),
+                            imported,
+                        },
+                    );
+                }
+            }
+            _ => {
+                if let Some(Decl::Fn(fn_decl)) = module_item_decl(module_item) {
+                    locals.insert(
+                        fn_decl.ident.sym.to_string(),
+                        Local::Fn(LocalFn {
+                            definition: FnHandlerDefinition::Function,
+                            doc: doc_comment(source, module_item.span_lo()),
+                            signature: Some(fn_signature(source, &fn_decl.function)),
+                        }),
+                    );
+                }
+            }
+        }
+    }
+    for module_item in &module.body {
+        if let Some(Decl::Var(var_decl)) = module_item_decl(module_item) {
+            for var_declarator in &var_decl.decls {
+                if let (Some(ident), Some(init)) =
+                    (var_declarator.name.as_ident(), &var_declarator.init)
+                {
+                    let doc = doc_comment(source, module_item.span_lo());
+                    if let Some(local) = expr_local(source, init, doc, &locals) {
+                        locals.insert(ident.sym.to_string(), local);
+                    }
+                }
+            }
+        }
+    }
+    let mut exports = Vec::new();
+    for module_item in &module.body {
+        let ModuleItem::ModuleDecl(module_decl) = module_item else {
+            continue;
+        };
+        match module_decl {
+            ModuleDecl::ExportDecl(export_decl) => {
+                let idents = match &export_decl.decl {
+                    Decl::Fn(fn_decl) => vec![&fn_decl.ident],
+                    Decl::Var(var_decl) => var_decl
+                        .decls
+                        .iter()
+                        .filter_map(|var_declarator| var_declarator.name.as_ident())
+                        .map(|binding| &binding.id)
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                for ident in idents {
+                    if let Some(local) = locals.get(ident.sym.as_str()) {
+                        exports.push(local_export(ident.sym.to_string(), local));
+                    }
+                }
+            }
+            ModuleDecl::ExportDefaultDecl(export_default) => {
+                if let DefaultDecl::Fn(fn_expr) = &export_default.decl {
+                    exports.push(ModuleExport::Handler(FnHandler {
+                        definition: FnHandlerDefinition::Function,
+                        doc: doc_comment(source, export_default.span.lo),
+                        name: String::from("default"),
+                        signature: Some(fn_signature(source, &fn_expr.function)),
+                    }));
+                }
+            }
+            ModuleDecl::ExportDefaultExpr(export_default) => {
+                let doc = doc_comment(source, export_default.span.lo);
+                if let Some(local) = expr_local(source, &export_default.expr, doc, &locals) {
+                    exports.push(local_export(String::from("default"), &local));
+                }
+            }
+            ModuleDecl::ExportNamed(named_export) if !named_export.type_only => {
+                for specifier in &named_export.specifiers {
+                    let ExportSpecifier::Named(named) = specifier else {
+                        continue;
+                    };
+                    if named.is_type_only {
+                        continue;
+                    }
+                    let orig = named.orig.atom().to_string();
+                    let name = named
+                        .exported
+                        .as_ref()
+                        .map(|exported| exported.atom().to_string())
+                        .unwrap_or_else(|| orig.clone());
+                    match &named_export.src {
+                        Some(src) => exports.push(ModuleExport::ReExport {
+                            name,
+                            specifier: src.value.to_string(),
+                            imported: orig,
+                        }),
+                        None => {
+                            let ModuleExportName::Ident(_) = named.orig else {
+                                continue;
+                            };
+                            let Some(local) = locals.get(&orig) else {
+                                continue;
+                            };
+                            match local {
+                                Local::Fn(local_fn) if name != orig => {
+                                    exports.push(ModuleExport::Handler(
+                                        local_fn.alias(&orig, None).handler(name),
+                                    ))
+                                }
+                                _ => exports.push(local_export(name, local)),
+                            }
+                        }
+                    }
+                }
+            }
+            ModuleDecl::ExportAll(export_all) if !export_all.type_only => {
+                exports.push(ModuleExport::ReExportAll {
+                    specifier: export_all.src.value.to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    exports
+}
+
+fn local_export(name: String, local: &Local) -> ModuleExport {
+    match local {
+        Local::Fn(local_fn) => ModuleExport::Handler(local_fn.handler(name)),
+        Local::Import {
+            specifier,
+            imported,
+        } => ModuleExport::ReExport {
+            name,
+            specifier: specifier.clone(),
+            imported: imported.clone(),
+        },
+    }
+}
+
+fn module_item_decl(module_item: &ModuleItem) -> Option<&Decl> {
+    match module_item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => Some(decl),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => Some(&export_decl.decl),
+        _ => None,
+    }
+}
+
+/// Binding of an expression initializing a variable or exported as a module's default export.
+fn expr_local(
+    source: &ParsedSource,
+    expr: &Expr,
+    doc: Option<String>,
+    locals: &HashMap<String, Local>,
+) -> Option<Local> {
+    match unwrap_expr(expr) {
+        Expr::Ident(ident) => match locals.get(ident.sym.as_str())? {
+            Local::Fn(local_fn) => Some(Local::Fn(local_fn.alias(ident.sym.as_str(), doc))),
+            import => Some(import.clone()),
+        },
+        expr => expr_fn(source, expr, doc, &|ident| {
+            matches!(locals.get(ident), Some(Local::Fn(_)))
+        })
+        .map(Local::Fn),
+    }
+}
+
+/// Fn of a fn expression or of a call wrapping a fn with middleware.
+fn expr_fn(
+    source: &ParsedSource,
+    expr: &Expr,
+    doc: Option<String>,
+    is_local: &dyn Fn(&str) -> bool,
+) -> Option<LocalFn> {
+    let (definition, signature) = match unwrap_expr(expr) {
+        Expr::Arrow(arrow) => (
+            FnHandlerDefinition::FunctionExpression,
+            Some(arrow_signature(source, arrow)),
+        ),
+        Expr::Fn(fn_expr) => (
+            FnHandlerDefinition::FunctionExpression,
+            Some(fn_signature(source, &fn_expr.function)),
+        ),
+        Expr::Call(call) if is_wrapped_fn(call, is_local) => (FnHandlerDefinition::Wrapped, None),
+        _ => return None,
+    };
+    Some(LocalFn {
+        definition,
+        doc,
+        signature,
+    })
+}
+
+fn fn_signature(source: &ParsedSource, function: &Function) -> FnHandlerSignature {
+    FnHandlerSignature {
+        event_type: function
+            .params
+            .first()
+            .and_then(|param| pat_type(source, &param.pat)),
+        is_async: function.is_async,
+        param_count: function.params.len(),
+    }
+}
+
+fn arrow_signature(source: &ParsedSource, arrow: &ArrowExpr) -> FnHandlerSignature {
+    FnHandlerSignature {
+        event_type: arrow.params.first().and_then(|pat| pat_type(source, pat)),
+        is_async: arrow.is_async,
+        param_count: arrow.params.len(),
+    }
+}
+
+/// TypeScript type annotation of a fn param as it is written in the source.
+fn pat_type(source: &ParsedSource, pat: &Pat) -> Option<String> {
+    let type_ann = match pat {
+        Pat::Ident(binding) => binding.type_ann.as_ref(),
+        Pat::Array(array) => array.type_ann.as_ref(),
+        Pat::Object(object) => object.type_ann.as_ref(),
+        Pat::Rest(rest) => rest.type_ann.as_ref(),
+        Pat::Assign(assign) => return pat_type(source, &assign.left),
+        _ => None,
+    }?;
+    Some(source.snippet(type_ann.type_ann.span()).to_string())
+}
 
-pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<String>> {
-    let program = parse_program_from_fs(path, None)?;
-    let mut collecting = CollectExportedFnsVisitor::new();
-    program.fold_with(&mut collecting);
-    Ok(collecting.result())
+/// Text of the JSDoc comment leading the code at a position without its `*` line prefixes.
+fn doc_comment(source: &ParsedSource, pos: BytePos) -> Option<String> {
+    let comments = source.comments.get_leading(pos)?;
+    let comment = comments
+        .iter()
+        .rev()
+        .find(|comment| comment.kind == CommentKind::Block && comment.text.starts_with('*'))?;
+    let doc = comment.text[1..]
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    (!doc.is_empty()).then_some(doc)
 }
 
-impl From<CompileError> for AstParseError {
-    fn from(err: CompileError) -> Self {
-        match err {
-            CompileError::ReadError(err) => AstParseError::IO(err),
-            CompileError::CompilerDiagnostics(diagnostics) => {
-                dbg!(diagnostics);
-                panic!();
-                // AstParseError::Syntax
+/// Unwraps parentheses and TS type assertions of an expression.
+fn unwrap_expr(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_expr(&paren.expr),
+        Expr::TsAs(ts_as) => unwrap_expr(&ts_as.expr),
+        Expr::TsNonNull(ts_non_null) => unwrap_expr(&ts_non_null.expr),
+        Expr::TsSatisfies(ts_satisfies) => unwrap_expr(&ts_satisfies.expr),
+        _ => expr,
+    }
+}
+
+/// Whether a call wraps a handler fn with middleware by passing a handler fn expression or a fn
+/// declared by the module to the call or a call chained from it, such as
+/// `middy(fn).use(middleware)` or `middy().handler(async () => {})`.
+///
+/// Calls of imported values, such as `createClient(config)`, and of sync fn expressions without
+/// an event param, such as `cache(() => load())`, are not handlers.
+fn is_wrapped_fn(call: &CallExpr, is_local: &dyn Fn(&str) -> bool) -> bool {
+    let wraps_fn = call.args.iter().any(|arg| {
+        arg.spread.is_none()
+            && match unwrap_expr(&arg.expr) {
+                Expr::Arrow(arrow) => arrow.is_async || !arrow.params.is_empty(),
+                Expr::Fn(fn_expr) => {
+                    fn_expr.function.is_async || !fn_expr.function.params.is_empty()
+                }
+                Expr::Ident(ident) => is_local(ident.sym.as_str()),
+                _ => false,
             }
-            CompileError::OperationError(err) => {
-                dbg!(err);
-                panic!("swc operation error");
+    });
+    wraps_fn
+        || match &call.callee {
+            Callee::Expr(callee) => match &**callee {
+                Expr::Member(member) => match &*member.obj {
+                    Expr::Call(call) => is_wrapped_fn(call, is_local),
+                    _ => false,
+                },
+                _ => false,
+            },
+            _ => false,
+        }
+}
+
+/// Collects handlers of a CommonJS script assigned as `exports.X` or `module.exports.X`, or as
+/// properties of an object literal assigned to `module.exports`.
+pub fn collect_commonjs_handlers(source: &ParsedSource, stmts: &[Stmt]) -> Vec<FnHandler> {
+    // fn declarations are hoisted and may be exported before they are declared
+    let mut fns: HashMap<&str, LocalFn> = HashMap::new();
+    for stmt in stmts {
+        if let Stmt::Decl(Decl::Fn(fn_decl)) = stmt {
+            fns.insert(
+                fn_decl.ident.sym.as_str(),
+                LocalFn {
+                    definition: FnHandlerDefinition::Function,
+                    doc: doc_comment(source, stmt.span_lo()),
+                    signature: Some(fn_signature(source, &fn_decl.function)),
+                },
+            );
+        }
+    }
+    for stmt in stmts {
+        if let Stmt::Decl(Decl::Var(var_decl)) = stmt {
+            for var_declarator in &var_decl.decls {
+                if let (Some(ident), Some(init)) =
+                    (var_declarator.name.as_ident(), &var_declarator.init)
+                {
+                    let doc = doc_comment(source, stmt.span_lo());
+                    if let Some(local_fn) = commonjs_fn(source, init, doc, &fns) {
+                        fns.insert(ident.sym.as_str(), local_fn);
+                    }
+                }
             }
         }
     }
+    let local_fn = |name: &str, expr: &Expr, doc: Option<String>| match unwrap_expr(expr) {
+        Expr::Ident(ident) if ident.sym == name => fns.get(name).cloned(),
+        expr => commonjs_fn(source, expr, doc, &fns),
+    };
+    let mut handlers: Vec<FnHandler> = Vec::new();
+    let mut push_handler = |name: Option<String>, local_fn: Option<LocalFn>| {
+        if let (Some(name), Some(local_fn)) = (name, local_fn) {
+            handlers.push(local_fn.handler(name));
+        }
+    };
+    for stmt in stmts {
+        let Some(AssignExpr {
+            op: AssignOp::Assign,
+            left: AssignTarget::Simple(SimpleAssignTarget::Member(member)),
+            right,
+            ..
+        }) = stmt
+            .as_expr()
+            .and_then(|expr_stmt| expr_stmt.expr.as_assign())
+        else {
+            continue;
+        };
+        if is_module_exports(member) {
+            if let Expr::Object(object) = &**right {
+                for prop in &object.props {
+                    let PropOrSpread::Prop(prop) = prop else {
+                        continue;
+                    };
+                    match &**prop {
+                        Prop::Shorthand(ident) => push_handler(
+                            Some(ident.sym.to_string()),
+                            fns.get(ident.sym.as_str()).cloned(),
+                        ),
+                        Prop::KeyValue(key_value) => {
+                            let name = prop_name(&key_value.key);
+                            let doc = doc_comment(source, prop.span_lo());
+                            let local_fn = name
+                                .as_deref()
+                                .and_then(|name| local_fn(name, &key_value.value, doc));
+                            push_handler(name, local_fn)
+                        }
+                        Prop::Method(method) => push_handler(
+                            prop_name(&method.key),
+                            Some(LocalFn {
+                                definition: FnHandlerDefinition::Function,
+                                doc: doc_comment(source, prop.span_lo()),
+                                signature: Some(fn_signature(source, &method.function)),
+                            }),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+        } else if is_exports(&member.obj) {
+            let name = member_prop_name(&member.prop);
+            let doc = doc_comment(source, stmt.span_lo());
+            let local_fn = name.as_deref().and_then(|name| local_fn(name, right, doc));
+            push_handler(name, local_fn);
+        }
+    }
+    handlers
+}
+
+/// Fn of a fn expression, wrapped fn or alias of a fn declared by a CommonJS script.
+fn commonjs_fn(
+    source: &ParsedSource,
+    expr: &Expr,
+    doc: Option<String>,
+    fns: &HashMap<&str, LocalFn>,
+) -> Option<LocalFn> {
+    match unwrap_expr(expr) {
+        Expr::Ident(ident) => fns
+            .get(ident.sym.as_str())
+            .map(|local_fn| local_fn.alias(ident.sym.as_str(), doc)),
+        expr => expr_fn(source, expr, doc, &|ident| fns.contains_key(ident)),
+    }
+}
+
+/// Whether an expression is `exports` or `module.exports`.
+fn is_exports(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(ident) => ident.sym == "exports",
+        Expr::Member(member) => is_module_exports(member),
+        _ => false,
+    }
+}
+
+fn is_module_exports(member: &MemberExpr) -> bool {
+    matches!(&*member.obj, Expr::Ident(ident) if ident.sym == "module")
+        && member_prop_name(&member.prop).as_deref() == Some("exports")
+}
+
+fn member_prop_name(prop: &MemberProp) -> Option<String> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(computed) => match &*computed.expr {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+fn prop_name(prop_name: &PropName) -> Option<String> {
+    match prop_name {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(s) => Some(s.value.to_string()),
+        _ => None,
+    }
 }
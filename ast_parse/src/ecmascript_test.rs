@@ -1,21 +1,369 @@
+use crate::ecmascript::{
+    collect_commonjs_handlers, collect_exported_fns_with_re_exports, collect_module_exports,
+    ModuleExport,
+};
+use crate::swc::{ParsedSource, SourceType, SwcParser};
+use crate::{collect_exported_fns, FnHandler, FnHandlerDefinition, FnHandlerSignature};
 use std::fs;
-
+use std::path::{Path, PathBuf};
+use swc_ecma_ast::Program;
 use temp_dir::TempDir;
 
-use crate::collect_exported_fns;
+fn parse_source(filename: &str, js: &str, source_type: SourceType) -> ParsedSource {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.child(filename);
+    fs::write(&path, js).unwrap();
+    SwcParser::new()
+        .parse_source_from_fs(&path, source_type)
+        .unwrap()
+}
+
+fn module_handlers(filename: &str, js: &str) -> Vec<FnHandler> {
+    let source = parse_source(filename, js, SourceType::EsModule);
+    match &source.program {
+        Program::Module(module) => collect_module_exports(&source, module)
+            .into_iter()
+            .filter_map(|module_export| match module_export {
+                ModuleExport::Handler(handler) => Some(handler),
+                _ => None,
+            })
+            .collect(),
+        Program::Script(_) => panic!(),
+    }
+}
+
+/// Module exports without the docs and signatures of handlers.
+fn module_exports(js: &str) -> Vec<ModuleExport> {
+    let source = parse_source("lambda.js", js, SourceType::EsModule);
+    match &source.program {
+        Program::Module(module) => collect_module_exports(&source, module)
+            .into_iter()
+            .map(|module_export| match module_export {
+                ModuleExport::Handler(handler) => self::handler(&handler.name, handler.definition),
+                module_export => module_export,
+            })
+            .collect(),
+        Program::Script(_) => panic!(),
+    }
+}
+
+fn handler(name: &str, definition: FnHandlerDefinition) -> ModuleExport {
+    ModuleExport::Handler(FnHandler {
+        definition,
+        doc: None,
+        name: name.into(),
+        signature: None,
+    })
+}
+
+fn signature(is_async: bool, param_count: usize, event_type: Option<&str>) -> FnHandlerSignature {
+    FnHandlerSignature {
+        event_type: event_type.map(String::from),
+        is_async,
+        param_count,
+    }
+}
+
+fn exported_fn_names(filename: &str, js: &str) -> Vec<String> {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.child(filename);
+    fs::write(&path, js).unwrap();
+    collect_exported_fns(&path)
+        .unwrap()
+        .into_iter()
+        .map(|handler| handler.name)
+        .collect()
+}
 
 #[test]
 fn test_collect_exported_fns_for_export_function() {
-    let temp_dir = TempDir::new().unwrap();
-    let path = temp_dir.child("lambda.js");
-    fs::write(&path, "export function get() {}").unwrap();
-    assert_eq!(collect_exported_fns(&path).unwrap(), vec!["get"]);
+    assert_eq!(
+        exported_fn_names("lambda.js", "export function get() {}"),
+        vec!["get"]
+    );
 }
 
 #[test]
 fn test_collect_exported_fns_for_export_const_arrow_lambda() {
+    assert_eq!(
+        exported_fn_names("lambda.js", "export const get = () => {}"),
+        vec!["get"]
+    );
+}
+
+#[test]
+fn test_collect_exported_fns_for_commonjs_script() {
+    assert_eq!(
+        exported_fn_names("lambda.cjs", "exports.get = async () => {}"),
+        vec!["get"]
+    );
+}
+
+#[test]
+fn test_collect_exported_fns_errors_with_diagnostics_of_syntax_error() {
     let temp_dir = TempDir::new().unwrap();
     let path = temp_dir.child("lambda.js");
-    fs::write(&path, "export const get = () => {}").unwrap();
-    assert_eq!(collect_exported_fns(&path).unwrap(), vec!["get"]);
+    fs::write(&path, "export const get = () => {\n    return 1 +;\n}\n").unwrap();
+    let err = collect_exported_fns(&path).unwrap_err();
+    let start = &err
+        .diagnostics()
+        .first()
+        .unwrap()
+        .span
+        .as_ref()
+        .unwrap()
+        .start;
+    assert_eq!((start.line, start.column), (2, 15));
+}
+
+#[test]
+fn test_collect_module_exports_of_declared_fns() {
+    assert_eq!(
+        module_exports(
+            "export function GET() {}\nexport const POST = async () => {}\nexport const PUT = function () {}\nexport const data = []"
+        ),
+        vec![
+            handler("GET", FnHandlerDefinition::Function),
+            handler("POST", FnHandlerDefinition::FunctionExpression),
+            handler("PUT", FnHandlerDefinition::FunctionExpression),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_aliased_fns() {
+    assert_eq!(
+        module_exports(
+            "function handler() {}\nconst process = () => {}\nexport { handler as GET, process }\nexport const DELETE = handler"
+        ),
+        vec![
+            handler(
+                "GET",
+                FnHandlerDefinition::Alias {
+                    local: "handler".into()
+                }
+            ),
+            handler("process", FnHandlerDefinition::FunctionExpression),
+            handler(
+                "DELETE",
+                FnHandlerDefinition::Alias {
+                    local: "handler".into()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_default_exports() {
+    assert_eq!(
+        module_exports("export default async (event) => {}"),
+        vec![handler("default", FnHandlerDefinition::FunctionExpression)]
+    );
+    assert_eq!(
+        module_exports("export default function (event) {}"),
+        vec![handler("default", FnHandlerDefinition::Function)]
+    );
+    assert_eq!(
+        module_exports("const handler = () => {}\nexport default handler"),
+        vec![handler(
+            "default",
+            FnHandlerDefinition::Alias {
+                local: "handler".into()
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_wrapped_fns() {
+    assert_eq!(
+        module_exports(
+            "import middy from '@middy/core'\nconst process = async () => {}\nexport const handler = middy(process).use(cors())\nexport const GET = middy().handler(async () => {})\nexport const client = createClient({ region })"
+        ),
+        vec![
+            handler("handler", FnHandlerDefinition::Wrapped),
+            handler("GET", FnHandlerDefinition::Wrapped),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_calls_not_wrapping_fns() {
+    assert_eq!(
+        module_exports(
+            "import { config } from './config.js'\nimport { load } from './data.js'\nexport const client = createClient(config)\nexport const users = cache(() => load())\nexport const orders = memoize(function () { return load() })"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_re_exports() {
+    assert_eq!(
+        module_exports(
+            "import { process } from './process.js'\nexport { GET, handler as POST } from './impl.js'\nexport * from './routes.js'\nexport { process as PUT }"
+        ),
+        vec![
+            ModuleExport::ReExport {
+                name: "GET".into(),
+                specifier: "./impl.js".into(),
+                imported: "GET".into(),
+            },
+            ModuleExport::ReExport {
+                name: "POST".into(),
+                specifier: "./impl.js".into(),
+                imported: "handler".into(),
+            },
+            ModuleExport::ReExportAll {
+                specifier: "./routes.js".into(),
+            },
+            ModuleExport::ReExport {
+                name: "PUT".into(),
+                specifier: "./process.js".into(),
+                imported: "process".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_collect_module_exports_of_handler_signatures_and_docs() {
+    assert_eq!(
+        module_handlers(
+            "lambda.ts",
+            "import type { SQSEvent } from 'aws-lambda'\n\n/**\n * Processes orders.\n *\n * Retries failed orders.\n */\nexport async function handler(event: SQSEvent, context) {}\n\n// not a doc comment\nexport const GET = ({ body }: APIGatewayProxyEventV2 = defaultEvent) => {}\n\n/** Deletes orders. */\nexport const DELETE = handler\nexport const PUT = middy(handler)"
+        ),
+        vec![
+            FnHandler {
+                definition: FnHandlerDefinition::Function,
+                doc: Some("Processes orders.\n\nRetries failed orders.".into()),
+                name: "handler".into(),
+                signature: Some(signature(true, 2, Some("SQSEvent"))),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::FunctionExpression,
+                doc: None,
+                name: "GET".into(),
+                signature: Some(signature(false, 1, Some("APIGatewayProxyEventV2"))),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::Alias {
+                    local: "handler".into()
+                },
+                doc: Some("Deletes orders.".into()),
+                name: "DELETE".into(),
+                signature: Some(signature(true, 2, Some("SQSEvent"))),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::Wrapped,
+                doc: None,
+                name: "PUT".into(),
+                signature: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_collect_commonjs_handlers() {
+    let source = parse_source(
+        "lambda.js",
+        "/** Gets data. */\nfunction handler(event) {}\nexports.GET = handler\nexports.handler = handler\nexports.POST = async () => {}\nexports.PUT = middy(handler)",
+        SourceType::CommonJs,
+    );
+    let Program::Script(script) = &source.program else {
+        panic!();
+    };
+    assert_eq!(
+        collect_commonjs_handlers(&source, &script.body),
+        vec![
+            FnHandler {
+                definition: FnHandlerDefinition::Alias {
+                    local: "handler".into()
+                },
+                doc: Some("Gets data.".into()),
+                name: "GET".into(),
+                signature: Some(signature(false, 1, None)),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::Function,
+                doc: Some("Gets data.".into()),
+                name: "handler".into(),
+                signature: Some(signature(false, 1, None)),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::FunctionExpression,
+                doc: None,
+                name: "POST".into(),
+                signature: Some(signature(true, 0, None)),
+            },
+            FnHandler {
+                definition: FnHandlerDefinition::Wrapped,
+                doc: None,
+                name: "PUT".into(),
+                signature: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_collect_exported_fns_with_re_exports() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.child("routes/data")).unwrap();
+    fs::create_dir_all(temp_dir.child("lib")).unwrap();
+    fs::write(
+        temp_dir.child("routes/data/lambda.js"),
+        "export { DELETE } from '../../lib/impl.js'\nexport * from '../../lib/routes.js'\nexport { GET as PUT } from 'data-lib'",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("lib/impl.js"),
+        "export const DELETE = async () => {}\nexport const POST = async () => {}",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("lib/routes.js"),
+        "export * from '../routes/data/lambda.js'\nexport function GET() {}\nexport default () => {}",
+    )
+    .unwrap();
+    let mut resolved: Vec<(PathBuf, String)> = Vec::new();
+    let handlers = collect_exported_fns_with_re_exports(
+        temp_dir.path(),
+        Path::new("routes/data/lambda.js"),
+        &mut |from, specifier| {
+            resolved.push((from.to_path_buf(), specifier.to_string()));
+            match specifier {
+                "../../lib/impl.js" => Some(PathBuf::from("lib/impl.js")),
+                "../../lib/routes.js" => Some(PathBuf::from("lib/routes.js")),
+                "../routes/data/lambda.js" => Some(PathBuf::from("routes/data/lambda.js")),
+                _ => None,
+            }
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        handlers
+            .into_iter()
+            .map(|handler| (handler.name, handler.definition))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "DELETE".to_string(),
+                FnHandlerDefinition::ReExport {
+                    name: "DELETE".into(),
+                    path: PathBuf::from("lib/impl.js"),
+                }
+            ),
+            (
+                "GET".to_string(),
+                FnHandlerDefinition::ReExport {
+                    name: "GET".into(),
+                    path: PathBuf::from("lib/routes.js"),
+                }
+            ),
+        ]
+    );
+    assert_eq!(resolved.len(), 4);
 }
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A function exported by an entrypoint, named as it is exported. A default export is named
+/// `default`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnHandler {
+    pub definition: FnHandlerDefinition,
+    /// Leading JSDoc comment or Python docstring of the handler.
+    pub doc: Option<String>,
+    pub name: String,
+    /// Signature of the handler fn, unknown for a fn wrapped with middleware.
+    pub signature: Option<FnHandlerSignature>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnHandlerSignature {
+    /// Type annotation of the handler's first param, such as `SQSEvent` or
+    /// `APIGatewayProxyEventV2` of a TypeScript handler.
+    pub event_type: Option<String>,
+    pub is_async: bool,
+    pub param_count: usize,
+}
+
+/// How an exported handler fn is defined by its module.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FnHandlerDefinition {
+    /// Function declaration, such as `export function GET() {}` or a Python `def`.
+    Function,
+    /// Arrow fn or fn expression, such as `export const GET = () => {}`.
+    FunctionExpression,
+    /// Call of middleware wrapping a handler fn, such as `export const handler = middy(fn)`.
+    Wrapped,
+    /// Fn declared by the module and exported with another name, such as
+    /// `export { handler as GET }`.
+    Alias { local: String },
+    /// Fn exported by another source of the function, such as `export { GET } from './impl.js'`
+    /// or an imported fn exported by the module.
+    ReExport { name: String, path: PathBuf },
+}
@@ -1,5 +1,7 @@
-mod ecmascript;
-mod python;
+mod diagnostic;
+pub mod ecmascript;
+mod handler;
+pub mod python;
 pub mod swc;
 
 #[cfg(test)]
@@ -7,29 +9,56 @@ mod ecmascript_test;
 #[cfg(test)]
 mod python_test;
 
-use std::{io, path::Path};
+use std::{io, path::Path, path::PathBuf};
+
+use crate::swc::CompileError;
+
+pub use crate::diagnostic::*;
+pub use crate::handler::*;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AstParseError {
-    #[error("unsupported file type")]
-    InvalidFileType,
-    #[error("io error")]
-    IO(#[from] io::Error),
-    #[error("syntax error")]
-    Syntax,
+    #[error("unsupported file type {0}")]
+    InvalidFileType(PathBuf),
+    #[error("{0}")]
+    IoError(#[from] io::Error),
+    #[error("compiler error: {0}")]
+    OperationError(String),
+    #[error("syntax error:\n{}", format_diagnostics(.0))]
+    SyntaxError(Vec<FnDiagnostic>),
+}
+
+impl AstParseError {
+    /// Diagnostics of source code that failed to parse.
+    pub fn diagnostics(&self) -> &[FnDiagnostic] {
+        match self {
+            AstParseError::SyntaxError(diagnostics) => diagnostics,
+            _ => &[],
+        }
+    }
+}
+
+impl From<CompileError> for AstParseError {
+    fn from(err: CompileError) -> Self {
+        match err {
+            CompileError::CompilerDiagnostics(diagnostics) => {
+                AstParseError::SyntaxError(diagnostics)
+            }
+            CompileError::OperationError(err) => AstParseError::OperationError(err),
+            CompileError::ReadError(err) => AstParseError::IoError(err),
+        }
+    }
 }
 
 pub type AstParseResult<T> = Result<T, AstParseError>;
 
-pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<String>> {
+/// Collects the handlers of a JS, TS or Python source.
+pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<FnHandler>> {
     debug_assert!(path.is_absolute());
     debug_assert!(path.is_file());
     match path.extension().and_then(|ext| ext.to_str()) {
-        None => Err(AstParseError::InvalidFileType),
-        Some(ext) => match ext {
-            "js" | "mjs" | "ts" => Ok(ecmascript::collect_exported_fns(path)?),
-            "py" => Ok(python::collect_exported_fns(path)?),
-            _ => Err(AstParseError::InvalidFileType),
-        },
+        Some("js" | "mjs" | "cjs" | "ts" | "tsx") => ecmascript::collect_exported_fns(path),
+        Some("py") => python::collect_exported_fns(path),
+        _ => Err(AstParseError::InvalidFileType(path.to_path_buf())),
     }
 }
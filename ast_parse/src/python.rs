@@ -1,27 +1,108 @@
-use rustpython_parser::ast::Stmt;
+use rustpython_parser::ast::{Ranged, Stmt};
 use rustpython_parser::{ast, Parse, ParseError};
-use std::{fs, path::Path};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::{AstParseError, AstParseResult};
+use crate::{
+    AstParseError, AstParseResult, FnDiagnostic, FnDiagnosticSeverity, FnDiagnosticSpan, FnHandler,
+    FnHandlerDefinition, FnHandlerSignature, FnSourcePosition,
+};
 
-pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<String>> {
+pub fn collect_exported_fns(path: &Path) -> AstParseResult<Vec<FnHandler>> {
+    let (python_code, ast) = parse_python_source(path)?;
+    Ok(collect_python_handlers(&python_code, &ast))
+}
+
+/// Parses a Python source to its code and ast.
+pub fn parse_python_source(path: &Path) -> AstParseResult<(String, Vec<Stmt>)> {
     let python_code = fs::read_to_string(path)?;
-    let ast = ast::Suite::parse(&python_code, &path.to_string_lossy())?;
-    let mut handlers: Vec<String> = Vec::new();
+    let ast = ast::Suite::parse(&python_code, &path.to_string_lossy())
+        .map_err(|err| syntax_error(err, &python_code))?;
+    Ok((python_code, ast))
+}
+
+/// Collects the top-level fns of a Python module's ast parsed from its code.
+pub fn collect_python_handlers(python_code: &str, ast: &[Stmt]) -> Vec<FnHandler> {
+    let mut handlers: Vec<FnHandler> = Vec::new();
     for stmt in ast {
-        match stmt {
-            Stmt::FunctionDef(function) => handlers.push(function.name.to_string()),
-            Stmt::AsyncFunctionDef(_) => todo!("to support python async functions as handlers, build_python_fn will have to generate code for a non async handler that launches the async python function with the python async runtime"),
-            _ => {}
-        }
+        let (name, args, body, is_async) = match stmt {
+            Stmt::FunctionDef(function) => (&function.name, &function.args, &function.body, false),
+            Stmt::AsyncFunctionDef(function) => {
+                (&function.name, &function.args, &function.body, true)
+            }
+            _ => continue,
+        };
+        let params: Vec<&ast::Arg> = args
+            .posonlyargs
+            .iter()
+            .chain(args.args.iter())
+            .map(|arg| &arg.def)
+            .collect();
+        handlers.push(FnHandler {
+            definition: FnHandlerDefinition::Function,
+            doc: docstring(body),
+            name: name.to_string(),
+            signature: Some(FnHandlerSignature {
+                event_type: params
+                    .first()
+                    .and_then(|param| param.annotation.as_ref())
+                    .map(|annotation| {
+                        let range = annotation.range();
+                        python_code[range.start().to_usize()..range.end().to_usize()].to_string()
+                    }),
+                is_async,
+                param_count: params.len(),
+            }),
+        });
     }
-    Ok(handlers)
+    handlers
 }
 
-impl From<ParseError> for AstParseError {
-    fn from(err: ParseError) -> Self {
-        // todo map rustpython_parser::ParseError diagnostics to a public API type
-        dbg!(err);
-        AstParseError::Syntax
-    }
+/// Maps a ParseError to a syntax error with a diagnostic located at the error's offset in the
+/// parsed Python code.
+fn syntax_error(err: ParseError, python_code: &str) -> AstParseError {
+    let position = FnSourcePosition::from_offset(python_code, err.offset.to_usize());
+    AstParseError::SyntaxError(Vec::from([FnDiagnostic {
+        code: None,
+        message: err.error.to_string(),
+        path: PathBuf::from(err.source_path),
+        severity: FnDiagnosticSeverity::Error,
+        span: Some(FnDiagnosticSpan {
+            start: position.clone(),
+            end: position,
+        }),
+    }]))
+}
+
+/// Docstring of a fn's body with its indentation removed as `inspect.cleandoc` would.
+fn docstring(body: &[Stmt]) -> Option<String> {
+    let Some(Stmt::Expr(ast::StmtExpr { value, .. })) = body.first() else {
+        return None;
+    };
+    let ast::Expr::Constant(ast::ExprConstant {
+        value: ast::Constant::Str(docstring),
+        ..
+    }) = &**value
+    else {
+        return None;
+    };
+    let mut lines = docstring.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let doc = std::iter::once(first)
+        .chain(
+            rest.iter()
+                .map(|line| line.get(indent..).unwrap_or_default().trim_end()),
+        )
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    (!doc.is_empty()).then_some(doc)
 }
@@ -3,10 +3,43 @@ use temp_dir::TempDir;
 
 use crate::collect_exported_fns;
 
+fn exported_fn_names(python: &str) -> Vec<String> {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.child("lambda.py");
+    fs::write(&path, python).unwrap();
+    collect_exported_fns(&path)
+        .unwrap()
+        .into_iter()
+        .map(|handler| handler.name)
+        .collect()
+}
+
 #[test]
 fn test_collect_exported_fns() {
+    assert_eq!(
+        exported_fn_names("def get():\n    return 'data'\n"),
+        vec!["get"]
+    );
+}
+
+#[test]
+fn test_collect_exported_fns_includes_async_fns() {
+    assert_eq!(
+        exported_fn_names(
+            "async def get_data():\n    return 'data'\n\nasync def get():\n    return await get_data()\n"
+        ),
+        vec!["get_data", "get"]
+    );
+}
+
+#[test]
+fn test_collect_exported_fns_errors_with_diagnostics_of_syntax_error() {
     let temp_dir = TempDir::new().unwrap();
     let path = temp_dir.child("lambda.py");
-    fs::write(&path, "def get():\n    return 'data'\n").unwrap();
-    assert_eq!(collect_exported_fns(&path).unwrap(), vec!["get"]);
+    fs::write(&path, "def get(event, context):\n    return (\n").unwrap();
+    let err = collect_exported_fns(&path).unwrap_err();
+    let diagnostic = err.diagnostics().first().unwrap();
+    assert_eq!(diagnostic.path, path);
+    let start = &diagnostic.span.as_ref().unwrap().start;
+    assert_eq!((start.line, start.column), (3, 1));
 }
@@ -4,118 +4,87 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use swc::config::{Config, IsModule, JscConfig, Options};
-use swc::{BoolConfig, Compiler};
-use swc_common::errors::{Diagnostic, DiagnosticBuilder, Emitter, Handler, HANDLER};
-use swc_common::{SourceFile, SourceMap, GLOBALS};
+use swc::Compiler;
+use swc_common::comments::SingleThreadedComments;
+use swc_common::errors::{
+    Diagnostic, DiagnosticBuilder, DiagnosticId, Emitter, Handler, Level, HANDLER,
+};
+use swc_common::{BytePos, FileName, SourceFile, SourceMap, Span, GLOBALS};
 use swc_ecma_ast::{EsVersion, Program};
 use swc_ecma_parser::{EsSyntax, Syntax, TsSyntax};
 
-pub type CompileResult<R> = Result<R, CompileError>;
+use crate::{FnDiagnostic, FnDiagnosticSeverity, FnDiagnosticSpan, FnSourcePosition};
 
-#[derive(thiserror::Error, Debug)]
-pub enum CompileError {
-    #[error("compiler produced diagnostic info")]
-    CompilerDiagnostics(Vec<Diagnostic>),
-    #[error("compiler operation produced error: {0}")]
-    OperationError(String),
-    #[error("reading source io error: {0}")]
-    ReadError(#[from] io::Error),
+#[derive(Clone)]
+struct CapturingEmitter {
+    errors: Arc<Mutex<Vec<Diagnostic>>>,
 }
 
-pub fn parse_program_from_fs(path: &Path, compiler: Option<SwcCompiler>) -> CompileResult<Program> {
-    let program = compiler.unwrap_or_default().fs_source_with_compiler(
-        path,
-        |compiler, handler, source_file| {
-            compiler.parse_js(
-                source_file,
-                handler,
-                es_target(),
-                es_or_ts_syntax(path),
-                IsModule::Bool(true),
-                None,
-            )
-        },
-    )?;
-    if let Program::Script(_) = program {
-        panic!("cjs unsupported");
+impl CapturingEmitter {
+    pub fn new(errors: Arc<Mutex<Vec<Diagnostic>>>) -> Self {
+        Self { errors }
     }
-    Ok(program)
-}
-
-pub fn parse_program_to_string(
-    program: Program,
-    compiler: Option<SwcCompiler>,
-) -> CompileResult<String> {
-    compiler
-        .unwrap_or_default()
-        .with_compiler(|compiler, handler| {
-            Ok(compiler
-                .process_js(handler, program, &process_opts(false, ts_syntax()))?
-                .code)
-        })
 }
 
-pub fn process_opts(minify: bool, syntax: Syntax) -> Options {
-    Options {
-        config: Config {
-            jsc: JscConfig {
-                syntax: Some(syntax),
-                target: Some(es_target()),
-                ..Default::default()
-            },
-            is_module: Some(IsModule::Bool(true)),
-            minify: BoolConfig::new(Some(minify)),
-            ..Default::default()
-        },
-        ..Default::default()
+impl Emitter for CapturingEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        self.errors.lock().unwrap().push((**db).clone());
     }
 }
 
-pub fn es_or_ts_syntax(p: &Path) -> Syntax {
-    p.extension()
-        .map(|ext| match ext.to_str().unwrap() {
-            "ts" => ts_syntax(),
-            _ => es_syntax(),
-        })
-        .unwrap()
-}
-
-pub fn es_syntax() -> Syntax {
-    Syntax::Es(EsSyntax::default())
+#[derive(thiserror::Error, Debug)]
+pub enum CompileError {
+    #[error("compiler produced diagnostic info")]
+    CompilerDiagnostics(Vec<FnDiagnostic>),
+    #[error("compiler operation produced error: {0}")]
+    OperationError(String),
+    #[error("reading source io error: {0}")]
+    ReadError(#[from] io::Error),
 }
 
-pub fn ts_syntax() -> Syntax {
-    Syntax::Typescript(TsSyntax {
-        decorators: false,
-        disallow_ambiguous_jsx_like: true,
-        tsx: false,
-        dts: false,
-        no_early_errors: false,
-    })
-}
+pub type CompileResult<R> = Result<R, CompileError>;
 
-pub fn es_target() -> EsVersion {
-    EsVersion::EsNext
+/// Program parsed from a source file with the source's comments.
+pub struct ParsedSource {
+    pub comments: SingleThreadedComments,
+    pub program: Program,
+    pub source_file: Arc<SourceFile>,
 }
 
-struct CapturingEmitter {
-    errors: Arc<Mutex<Vec<Diagnostic>>>,
+impl ParsedSource {
+    /// Code of a span of the source.
+    pub fn snippet(&self, span: Span) -> &str {
+        let start = (span.lo - self.source_file.start_pos).0 as usize;
+        let end = (span.hi - self.source_file.start_pos).0 as usize;
+        &self.source_file.src[start..end]
+    }
 }
 
-impl CapturingEmitter {
-    pub fn new(errors: Arc<Mutex<Vec<Diagnostic>>>) -> Self {
-        Self { errors }
-    }
+/// Module system of a source that determines whether it is parsed as a module or a script.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SourceType {
+    /// A CommonJS script that may `require` modules and assign `module.exports`.
+    CommonJs,
+    EsModule,
+    /// Parsed as an ES module if the source has `import` or `export` declarations and otherwise
+    /// as a script, like Node.js's syntax detection of sources without an explicit module system.
+    Detect,
 }
 
-impl Emitter for CapturingEmitter {
-    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
-        self.errors.lock().unwrap().push((**db).clone());
+impl From<SourceType> for IsModule {
+    fn from(source_type: SourceType) -> Self {
+        match source_type {
+            SourceType::CommonJs => IsModule::CommonJS,
+            SourceType::EsModule => IsModule::Bool(true),
+            SourceType::Detect => IsModule::Unknown,
+        }
     }
 }
 
+/// Parses JS and TS sources with an SWC compiler whose operations report FnDiagnostics. Builds
+/// run their transforms of parsed sources with the same compiler and source map.
 #[derive(Clone)]
-pub struct SwcCompiler {
+pub struct SwcParser {
     /**
      * swc::Compiler APIs
      *
@@ -130,13 +99,13 @@ pub struct SwcCompiler {
     pub source_map: Arc<SourceMap>,
 }
 
-impl Default for SwcCompiler {
+impl Default for SwcParser {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl SwcCompiler {
+impl SwcParser {
     pub fn new() -> Self {
         let source_map = Arc::<SourceMap>::default();
         let compiler = Arc::new(Compiler::new(source_map.clone()));
@@ -146,23 +115,92 @@ impl SwcCompiler {
         }
     }
 
+    // from file of ts or js, parse to ast of a module or, for CommonJS, a script
+    pub fn parse_program_from_fs(
+        self,
+        path: &Path,
+        source_type: SourceType,
+    ) -> CompileResult<Program> {
+        debug_assert!(path.is_absolute());
+        self.fs_source_with_compiler(path, |compiler, handler, source_file| {
+            compiler.parse_js(
+                source_file,
+                handler,
+                es_target(),
+                es_or_ts_syntax(path),
+                source_type.into(),
+                None,
+            )
+        })
+    }
+
+    // from file of ts or js, parse to ast with the source's comments
+    pub fn parse_source_from_fs(
+        self,
+        path: &Path,
+        source_type: SourceType,
+    ) -> CompileResult<ParsedSource> {
+        debug_assert!(path.is_absolute());
+        let comments = SingleThreadedComments::default();
+        let (program, source_file) =
+            self.fs_source_with_compiler(path, |compiler, handler, source_file| {
+                let program = compiler.parse_js(
+                    source_file.clone(),
+                    handler,
+                    es_target(),
+                    es_or_ts_syntax(path),
+                    source_type.into(),
+                    Some(&comments),
+                )?;
+                Ok((program, source_file))
+            })?;
+        Ok(ParsedSource {
+            comments,
+            program,
+            source_file,
+        })
+    }
+
+    // from ast of ts or js, print to js
+    pub fn program_to_string(self, path: &Path, program: Program) -> CompileResult<String> {
+        let opts = Options {
+            config: Config {
+                jsc: JscConfig {
+                    syntax: Some(es_or_ts_syntax(path)),
+                    target: Some(es_target()),
+                    ..Default::default()
+                },
+                is_module: Some(IsModule::Bool(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        self.with_compiler(path, |compiler, handler| {
+            Ok(compiler.process_js(handler, program, &opts)?.code)
+        })
+    }
+
     pub fn fs_source_with_compiler<F, R>(self, p: &Path, f: F) -> CompileResult<R>
     where
         F: FnOnce(&Compiler, &Handler, Arc<SourceFile>) -> Result<R, anyhow::Error>,
     {
         let source_file = self.source_map.load_file(p)?;
-        self.with_compiler(|compiler, handler| f(compiler, handler, source_file))
+        self.with_compiler(p, |compiler, handler| f(compiler, handler, source_file))
     }
 
     pub fn string_source_with_compiler<F, R>(self, p: PathBuf, js: String, f: F) -> CompileResult<R>
     where
         F: FnOnce(&Compiler, &Handler, Arc<SourceFile>) -> Result<R, anyhow::Error>,
     {
-        let source_file = self.source_map.new_source_file(Arc::new(p.into()), js);
-        self.with_compiler(|compiler, handler| f(compiler, handler, source_file))
+        let source_file = self
+            .source_map
+            .new_source_file(Arc::new(p.clone().into()), js);
+        self.with_compiler(&p, |compiler, handler| f(compiler, handler, source_file))
     }
 
-    pub fn with_compiler<F, R>(&self, f: F) -> CompileResult<R>
+    /// Runs a compiler operation, collecting its diagnostics as FnDiagnostics located in their
+    /// source files or, for diagnostics without a span, the operation's source path.
+    pub fn with_compiler<F, R>(self, p: &Path, f: F) -> CompileResult<R>
     where
         F: FnOnce(&Compiler, &Handler) -> Result<R, anyhow::Error>,
     {
@@ -183,8 +221,87 @@ impl SwcCompiler {
                     Err(err) => Err(CompileError::OperationError(err.to_string())),
                 }
             } else {
-                Err(CompileError::CompilerDiagnostics(diagnostics))
+                Err(CompileError::CompilerDiagnostics(
+                    diagnostics
+                        .iter()
+                        .map(|diagnostic| self.fn_diagnostic(p, diagnostic))
+                        .collect(),
+                ))
             }
         })
     }
+
+    fn fn_diagnostic(&self, p: &Path, diagnostic: &Diagnostic) -> FnDiagnostic {
+        let span = diagnostic
+            .span
+            .primary_span()
+            .filter(|span| !span.is_dummy());
+        let path = span
+            .and_then(|span| self.source_map.try_lookup_char_pos(span.lo).ok())
+            .and_then(|loc| match loc.file.name.as_ref() {
+                FileName::Real(path) => Some(path.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| p.to_path_buf());
+        FnDiagnostic {
+            code: diagnostic.code.as_ref().map(|code| match code {
+                DiagnosticId::Error(code) | DiagnosticId::Lint(code) => code.clone(),
+            }),
+            message: diagnostic.message(),
+            path,
+            severity: match diagnostic.level {
+                Level::Warning => FnDiagnosticSeverity::Warning,
+                Level::Note => FnDiagnosticSeverity::Note,
+                Level::Help => FnDiagnosticSeverity::Help,
+                _ => FnDiagnosticSeverity::Error,
+            },
+            span: span.and_then(|span| {
+                Some(FnDiagnosticSpan {
+                    start: self.source_position(span.lo)?,
+                    end: self.source_position(span.hi)?,
+                })
+            }),
+        }
+    }
+
+    fn source_position(&self, pos: BytePos) -> Option<FnSourcePosition> {
+        let loc = self.source_map.try_lookup_char_pos(pos).ok()?;
+        Some(FnSourcePosition {
+            line: loc.line,
+            column: loc.col.0 + 1,
+            offset: (pos - loc.file.start_pos).0 as usize,
+        })
+    }
+}
+
+pub fn es_or_ts_syntax(p: &Path) -> Syntax {
+    p.extension()
+        .map(|ext| match ext.to_str().unwrap() {
+            "ts" => ts_syntax(false),
+            "tsx" => ts_syntax(true),
+            _ => es_syntax(),
+        })
+        .unwrap()
+}
+
+pub fn es_syntax() -> Syntax {
+    Syntax::Es(EsSyntax::default())
+}
+
+pub fn is_tsx(p: &Path) -> bool {
+    p.extension().is_some_and(|ext| ext == "tsx")
+}
+
+pub fn ts_syntax(tsx: bool) -> Syntax {
+    Syntax::Typescript(TsSyntax {
+        decorators: true,
+        disallow_ambiguous_jsx_like: !tsx,
+        tsx,
+        dts: false,
+        no_early_errors: false,
+    })
+}
+
+pub fn es_target() -> EsVersion {
+    EsVersion::EsNext
 }
@@ -1,44 +1,83 @@
-use swc_ecma_ast::{CallExpr, Callee, ImportDecl, Str};
+use swc_ecma_ast::{CallExpr, Callee, ExportAll, Expr, ImportDecl, Lit, NamedExport};
 use swc_ecma_visit::{Fold, FoldWith};
 
+/// Syntax of an import, which selects the package.json "exports" and "imports" conditions its
+/// specifier resolves with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImportKind {
+    /// Import declarations and `export from` declarations.
+    Static,
+    /// `import()` calls.
+    Dynamic,
+    /// CommonJS `require()` calls.
+    Require,
+}
+
+/// Specifier of an import collected from a module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectedImport {
+    pub kind: ImportKind,
+    pub specifier: String,
+}
+
+/// Collects specifiers of import declarations, `export from` declarations, `import()` calls and
+/// CommonJS `require()` calls.
 #[derive(Default)]
 pub struct CollectImportsVisitor {
-    folding_import_call: bool,
-    imports: Vec<String>,
+    imports: Vec<CollectedImport>,
 }
 
 impl CollectImportsVisitor {
     pub fn new() -> Self {
         Self {
-            folding_import_call: false,
             imports: Vec::new(),
         }
     }
 
-    pub fn result(self) -> Vec<String> {
+    pub fn result(self) -> Vec<CollectedImport> {
         self.imports
     }
+
+    fn push(&mut self, kind: ImportKind, specifier: &str) {
+        self.imports.push(CollectedImport {
+            kind,
+            specifier: specifier.to_string(),
+        });
+    }
 }
 
 impl Fold for CollectImportsVisitor {
     fn fold_call_expr(&mut self, node: CallExpr) -> CallExpr {
-        if let Callee::Import(_) = node.callee {
-            self.folding_import_call = true;
-            node.fold_children_with(self)
-        } else {
-            node
+        let kind = match &node.callee {
+            Callee::Import(_) => Some(ImportKind::Dynamic),
+            Callee::Expr(expr) => matches!(&**expr, Expr::Ident(ident) if ident.sym == "require")
+                .then_some(ImportKind::Require),
+            Callee::Super(_) => None,
+        };
+        if let Some(kind) = kind {
+            // only string literal specifiers can be resolved
+            if let Some(arg) = node.args.first().filter(|arg| arg.spread.is_none()) {
+                if let Expr::Lit(Lit::Str(specifier)) = &*arg.expr {
+                    self.push(kind, &specifier.value);
+                }
+            }
         }
+        node.fold_children_with(self)
+    }
+
+    fn fold_export_all(&mut self, node: ExportAll) -> ExportAll {
+        self.push(ImportKind::Static, &node.src.value);
+        node
     }
 
     fn fold_import_decl(&mut self, node: ImportDecl) -> ImportDecl {
-        self.imports.push(node.src.value.to_string());
+        self.push(ImportKind::Static, &node.src.value);
         node
     }
 
-    fn fold_str(&mut self, node: Str) -> Str {
-        if self.folding_import_call {
-            self.imports.push(node.value.to_string());
-            self.folding_import_call = false
+    fn fold_named_export(&mut self, node: NamedExport) -> NamedExport {
+        if let Some(src) = &node.src {
+            self.push(ImportKind::Static, &src.value);
         }
         node
     }
@@ -1,15 +1,27 @@
-use crate::swc::{parse_program_from_fs, visitors::CollectImportsVisitor};
+use crate::swc::visitors::{CollectImportsVisitor, CollectedImport, ImportKind};
+use crate::swc::{SourceType, SwcParser};
 use std::fs;
 use swc_ecma_visit::FoldWith;
 use temp_dir::TempDir;
 
 fn parse_imports(js: &str) -> Vec<String> {
+    collect_imports(js)
+        .into_iter()
+        .map(|import| import.specifier)
+        .collect()
+}
+
+fn collect_imports(js: &str) -> Vec<CollectedImport> {
     let temp_dir = TempDir::new().unwrap();
     let path = temp_dir.child("index.js");
     fs::write(&path, js).unwrap();
-    let module = parse_program_from_fs(&path, None).unwrap();
+    let compiler = SwcParser::new();
+    let program = compiler
+        .clone()
+        .parse_program_from_fs(&path, SourceType::Detect)
+        .unwrap();
     let mut visitor = CollectImportsVisitor::new();
-    module.fold_with(&mut visitor);
+    program.fold_with(&mut visitor);
     visitor.result()
 }
 
@@ -28,3 +40,52 @@ fn test_collect_imports_visitor_collects_import_fn() {
     assert_eq!(1, imports.len());
     assert_eq!("./data.js", imports.first().unwrap());
 }
+
+#[test]
+fn test_collect_imports_visitor_collects_require() {
+    let imports = parse_imports(
+        "const {getData} = require('./data.js')\nexports.GET = () => require('data-lib').format(getData())",
+    );
+    assert_eq!(imports, vec!["./data.js", "data-lib"]);
+}
+
+#[test]
+fn test_collect_imports_visitor_collects_nested_import_fn() {
+    let imports = parse_imports(
+        "export async function GET() {\nconsole.log(await import('./data.js'), `${'str'}`)\n}",
+    );
+    assert_eq!(imports, vec!["./data.js"]);
+}
+
+#[test]
+fn test_collect_imports_visitor_skips_non_literal_specifiers() {
+    let imports = parse_imports("const lib = 'data-lib'\nrequire(lib)\nimport(lib)\nuse('str')");
+    assert!(imports.is_empty());
+}
+
+#[test]
+fn test_collect_imports_visitor_collects_export_from() {
+    let imports = parse_imports(
+        "export { GET } from './get.js'\nexport * from './routes.js'\nexport { data }",
+    );
+    assert_eq!(imports, vec!["./get.js", "./routes.js"]);
+}
+
+#[test]
+fn test_collect_imports_visitor_collects_import_kinds() {
+    let imports = collect_imports(
+        "import { getData } from './data.js'\nexport * from './routes.js'\nconst lib = require('data-lib')\nawait import('./lazy.js')",
+    );
+    assert_eq!(
+        imports
+            .iter()
+            .map(|import| (import.kind, import.specifier.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (ImportKind::Static, "./data.js"),
+            (ImportKind::Static, "./routes.js"),
+            (ImportKind::Require, "data-lib"),
+            (ImportKind::Dynamic, "./lazy.js"),
+        ]
+    );
+}
@@ -1,14 +1,9 @@
-pub use collect_exported_fns::*;
 pub use collect_imports::*;
 pub use rewrite_ts_imports::*;
 
-mod collect_exported_fns;
 mod collect_imports;
 mod rewrite_ts_imports;
 
-#[cfg(test)]
-mod collect_exported_fns_test;
-
 #[cfg(test)]
 mod collect_imports_test;
 
@@ -1,50 +1,83 @@
-use swc_ecma_ast::{CallExpr, Callee, ImportDecl, Str};
+use std::sync::Arc;
+use swc_ecma_ast::{CallExpr, Callee, ExportAll, Expr, ImportDecl, Lit, NamedExport, Str};
 use swc_ecma_visit::{Fold, FoldWith};
 
-#[derive(Default)]
+/// Rewrites a bare import specifier, such as a tsconfig.json path alias, to a relative specifier.
+pub type RewriteSpecifier = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Rewrites of import specifiers in the JS emitted from a TS source.
+#[derive(Clone, Default)]
+pub struct TsImportRewrites {
+    /// Rewrites the `.ts` extension of import specifiers to `.js`.
+    pub ts_extensions: bool,
+    pub specifiers: Option<RewriteSpecifier>,
+}
+
+impl TsImportRewrites {
+    pub fn is_empty(&self) -> bool {
+        !self.ts_extensions && self.specifiers.is_none()
+    }
+
+    fn rewrite(&self, specifier: &str) -> Option<String> {
+        if let Some(rewritten) = self
+            .specifiers
+            .as_ref()
+            .and_then(|rewrite_specifier| rewrite_specifier(specifier))
+        {
+            return Some(rewritten);
+        }
+        if self.ts_extensions {
+            if let Some(without_ext) = specifier.strip_suffix(".ts") {
+                return Some(format!("{without_ext}.js"));
+            }
+        }
+        None
+    }
+
+    fn rewrite_str(&self, specifier: &mut Box<Str>) {
+        if let Some(rewritten) = self.rewrite(&specifier.value) {
+            *specifier = Box::from(Str::from(rewritten));
+        }
+    }
+}
+
 pub struct RewriteTsImportsVisitor {
-    folding_import_call: bool,
+    rewrites: TsImportRewrites,
 }
 
 impl RewriteTsImportsVisitor {
-    pub fn new() -> Self {
-        Self {
-            folding_import_call: false,
-        }
+    pub fn new(rewrites: TsImportRewrites) -> Self {
+        Self { rewrites }
     }
 }
 
 impl Fold for RewriteTsImportsVisitor {
-    fn fold_call_expr(&mut self, node: CallExpr) -> CallExpr {
+    fn fold_call_expr(&mut self, mut node: CallExpr) -> CallExpr {
         if let Callee::Import(_) = node.callee {
-            self.folding_import_call = true;
-            node.fold_children_with(self)
-        } else {
-            node
+            if let Some(arg) = node.args.first_mut() {
+                if let Expr::Lit(Lit::Str(specifier)) = &*arg.expr {
+                    if let Some(rewritten) = self.rewrites.rewrite(&specifier.value) {
+                        *arg.expr = Expr::Lit(Lit::Str(Str::from(rewritten)));
+                    }
+                }
+            }
         }
+        node.fold_children_with(self)
+    }
+
+    fn fold_export_all(&mut self, mut node: ExportAll) -> ExportAll {
+        self.rewrites.rewrite_str(&mut node.src);
+        node
     }
 
     fn fold_import_decl(&mut self, mut node: ImportDecl) -> ImportDecl {
-        if node.src.value.ends_with(".ts") {
-            node.src = Box::from(Str::from(format!(
-                "{}{}",
-                node.src.value.strip_suffix(".ts").unwrap(),
-                ".js"
-            )));
-        }
+        self.rewrites.rewrite_str(&mut node.src);
         node
     }
 
-    fn fold_str(&mut self, node: Str) -> Str {
-        if self.folding_import_call {
-            self.folding_import_call = false;
-            if node.value.ends_with(".ts") {
-                return Str::from(format!(
-                    "{}{}",
-                    node.value.strip_suffix(".ts").unwrap(),
-                    ".js"
-                ));
-            }
+    fn fold_named_export(&mut self, mut node: NamedExport) -> NamedExport {
+        if let Some(src) = &mut node.src {
+            self.rewrites.rewrite_str(src);
         }
         node
     }
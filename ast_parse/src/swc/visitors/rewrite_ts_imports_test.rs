@@ -1,5 +1,5 @@
-use crate::swc::visitors::RewriteTsImportsVisitor;
-use crate::swc::{parse_program_from_fs, parse_program_to_string, SwcCompiler};
+use crate::swc::visitors::{RewriteTsImportsVisitor, TsImportRewrites};
+use crate::swc::{SourceType, SwcParser};
 use std::fs;
 use swc_ecma_visit::FoldWith;
 use temp_dir::TempDir;
@@ -8,13 +8,20 @@ fn rewrite_imports(js: &str) -> String {
     let temp_dir = TempDir::new().unwrap();
     let path = temp_dir.child("index.ts");
     fs::write(&path, js).unwrap();
-    let compiler = Some(SwcCompiler::new());
-    let program = parse_program_from_fs(&path, compiler.clone()).unwrap();
-    parse_program_to_string(
-        program.fold_with(&mut RewriteTsImportsVisitor::new()),
-        compiler,
-    )
-    .unwrap()
+    let compiler = SwcParser::new();
+    let program = compiler
+        .clone()
+        .parse_program_from_fs(&path, SourceType::EsModule)
+        .unwrap();
+    compiler
+        .program_to_string(
+            &path,
+            program.fold_with(&mut RewriteTsImportsVisitor::new(TsImportRewrites {
+                ts_extensions: true,
+                specifiers: None,
+            })),
+        )
+        .unwrap()
 }
 
 #[test]
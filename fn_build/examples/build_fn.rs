@@ -16,6 +16,8 @@ async fn main() {
     let out_dir = TempDir::new().unwrap();
     let node_config = NodeConfig::read_configs(&project_dir).unwrap();
     let fn_build = build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: PathBuf::from("routes/data/lambda.js"),
         handler_fn_name: "GET".to_string(),
         mode: BuildMode::Debug,
@@ -25,8 +27,10 @@ async fn main() {
             dirname: "my-sweet-lambda".to_string(),
             use_build_mode: true,
         },
+        previous_build: None,
         project_dir: Arc::new(env::current_dir().unwrap().join(&project_dir)),
         runtime: Runtime::Node(Some(Arc::new(node_config))),
+        source_maps: None,
     })
     .await
     .unwrap();
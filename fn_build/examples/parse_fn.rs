@@ -14,6 +14,7 @@ async fn main() {
     );
     let node_config = NodeConfig::read_configs(&project_dir).unwrap();
     let fn_manifest = parse_fn(FnParseSpec {
+        conditions: Vec::new(),
         entrypoint: PathBuf::from("routes/data/lambda.js"),
         project_dir: Arc::new(env::current_dir().unwrap().join(&project_dir)),
         runtime: Runtime::Node(Some(Arc::new(node_config))),
@@ -0,0 +1,5 @@
+const { getData } = require('../../lib/data.js')
+
+exports.GET = () => {
+    console.log('got', getData())
+}
@@ -0,0 +1 @@
+exports.getData = () => []
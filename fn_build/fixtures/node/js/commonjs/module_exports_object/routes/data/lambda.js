@@ -0,0 +1,12 @@
+const data = require('../../lib/data.cjs')
+
+function get() {
+    console.log('got', data.getData())
+}
+
+module.exports = {
+    GET: get,
+    POST() {},
+    DELETE: async () => {},
+    version: 1,
+}
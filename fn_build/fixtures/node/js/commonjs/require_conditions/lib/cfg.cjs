@@ -0,0 +1,3 @@
+exports.getConfig = function () {
+    return {region: 'us-east-1'}
+}
@@ -0,0 +1,3 @@
+export function getConfig() {
+    return {region: 'us-east-1'}
+}
@@ -0,0 +1,6 @@
+const {getConfig} = require('#cfg')
+const {format} = require('dual-lib')
+
+exports.GET = () => {
+    console.log(format(getConfig()))
+}
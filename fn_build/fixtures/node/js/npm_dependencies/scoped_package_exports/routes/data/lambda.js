@@ -0,0 +1,6 @@
+import {getData} from '@l3/data-lib'
+import {format} from '@l3/data-lib/utils/format'
+
+export const GET = () => {
+    console.log(format(getData()))
+}
@@ -0,0 +1,5 @@
+const empty = []
+
+export function getData() {
+    return empty
+}
@@ -0,0 +1,5 @@
+import {getData as readData} from './data.js'
+
+export const getData = () => {
+    console.log('got', readData())
+}
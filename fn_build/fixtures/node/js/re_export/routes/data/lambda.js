@@ -0,0 +1 @@
+export { getData as GET } from '../../lib/handlers.js'
@@ -0,0 +1,5 @@
+import {getData} from '@lib/data.js'
+
+export const GET = () => {
+    console.log('got', getData())
+}
@@ -0,0 +1,8 @@
+import asyncio
+
+async def get_data():
+    await asyncio.sleep(0)
+    return 'data'
+
+async def get():
+    return await get_data()
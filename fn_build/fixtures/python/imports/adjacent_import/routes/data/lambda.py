@@ -1,4 +1,4 @@
-import lib
+import routes.data.lib
 
 def get():
-    return lib.get_data()
+    return routes.data.lib.get_data()
@@ -1,4 +1,4 @@
-import fn_build.fixtures.python.imports.lib_import.lib.data as data
+import lib.data as data
 
 def get():
     return data.get_data()
@@ -0,0 +1,2 @@
+def get_data():
+    return 'data'
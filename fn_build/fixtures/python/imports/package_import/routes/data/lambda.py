@@ -0,0 +1,4 @@
+import lib.data, lib.format
+
+def get():
+    return lib.format.format_data(lib.data.get_data())
@@ -1,4 +1,4 @@
-from data import get_data
+from routes.data.data import get_data
 
 def get():
     return get_data()
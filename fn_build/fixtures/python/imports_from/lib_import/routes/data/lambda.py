@@ -1,4 +1,4 @@
-from fn_build.fixtures.python.imports.lib_import.lib.data import get_data
+from lib.data import get_data
 
 def get():
     return get_data()
@@ -1,4 +1,4 @@
-from fn_build.fixtures.python.imports.lib_import.lib.data import get_data as get_that_data_yo
+from lib.data import get_data as get_that_data_yo
 
 def get():
     return get_that_data_yo()
@@ -0,0 +1 @@
+DATA_VERSION = 1
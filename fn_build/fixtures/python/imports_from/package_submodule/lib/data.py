@@ -0,0 +1,2 @@
+def get_data(version):
+    return f'data v{version}'
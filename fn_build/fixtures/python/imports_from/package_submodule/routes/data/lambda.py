@@ -0,0 +1,4 @@
+from lib import data, DATA_VERSION
+
+def get():
+    return data.get_data(DATA_VERSION)
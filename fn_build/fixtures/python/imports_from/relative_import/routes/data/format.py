@@ -0,0 +1,2 @@
+def format_record(record):
+    return record.data
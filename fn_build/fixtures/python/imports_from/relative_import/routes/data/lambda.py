@@ -0,0 +1,6 @@
+from .models.record import Record
+from . import format
+
+
+def get():
+    return format.format_record(Record('data'))
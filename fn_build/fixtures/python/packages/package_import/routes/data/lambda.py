@@ -0,0 +1,5 @@
+import requests
+from boto3.session import Session
+
+def get():
+    return requests.get(Session().region_name).text
@@ -0,0 +1,4 @@
+from format_lib import format_data
+
+def get_data():
+    return format_data('data')
@@ -0,0 +1,2 @@
+def format_data(data):
+    return data.upper()
@@ -0,0 +1,4 @@
+import data_lib
+
+def get():
+    return data_lib.get_data()
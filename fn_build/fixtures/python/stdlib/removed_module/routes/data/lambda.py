@@ -0,0 +1,4 @@
+import cgi
+
+def get():
+    return cgi.escape('data')
@@ -0,0 +1,5 @@
+import json, os.path
+from datetime import date
+
+def get():
+    return json.dumps({'date': date(2024, 1, 1).isoformat(), 'file': os.path.basename(__file__)})
@@ -1,6 +1,6 @@
 use crate::checksum::Checksum;
 use crate::runtime::Runtime;
-use crate::{FnDependencies, FnParseError, FnParseSpec, FnSource};
+use crate::{FnDependencies, FnDiagnostic, FnParseError, FnParseSpec, FnSource};
 use l3_fn_config::Language;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -49,13 +49,51 @@ pub struct FnOutputConfig {
     pub use_build_mode: bool,
 }
 
+/// Config for bundling a function's sources into a single ES module with its entrypoint's
+/// exports as the bundle's exports.
+#[derive(Clone, Debug)]
+pub struct FnBundleConfig {
+    /// Inlines npm packages distributed as ES modules into the bundle. CommonJS packages and
+    /// their dependencies are always copied to the build's node_modules.
+    pub inline_packages: bool,
+}
+
+/// Where source maps of a function build's transformed outputs are written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FnSourceMaps {
+    /// Source maps are written to a `.map` file next to each output.
+    External,
+    /// Source maps are appended to each output as a base64 data URL.
+    Inline,
+}
+
+/// Config for writing source maps of a function build's transformed outputs.
+#[derive(Clone, Debug)]
+pub struct FnSourceMapConfig {
+    pub source_maps: FnSourceMaps,
+    /// Adds `NODE_OPTIONS=--enable-source-maps` to FnBuildManifest's env vars so Node.js maps
+    /// the stack traces of the deployed function with the build's source maps.
+    pub enable_node_source_maps: bool,
+}
+
 pub struct FnBuildSpec {
+    /// Bundles sources of the function's source tree into one output of the entrypoint instead
+    /// of building each source. Supported by Node.js functions.
+    pub bundle: Option<FnBundleConfig>,
+    /// Custom conditions matched by package.json "imports" and "exports" in addition to the
+    /// conditions of Node.js, like the `--conditions` flag of the Node.js CLI. Supported by
+    /// Node.js functions.
+    pub conditions: Vec<String>,
     pub entrypoint: PathBuf,
     pub handler_fn_name: String,
     pub mode: BuildMode,
     pub output: FnOutputConfig,
+    /// Manifest of the function's previous build to reuse outputs of unchanged sources.
+    pub previous_build: Option<FnBuildManifest>,
     pub project_dir: Arc<PathBuf>,
     pub runtime: Runtime,
+    /// Writes source maps of transformed outputs. Supported by Node.js functions.
+    pub source_maps: Option<FnSourceMapConfig>,
 }
 
 impl FnBuildSpec {
@@ -66,6 +104,7 @@ impl FnBuildSpec {
 
     pub fn to_parse_spec(&self) -> FnParseSpec {
         FnParseSpec {
+            conditions: self.conditions.clone(),
             entrypoint: self.entrypoint.clone(),
             project_dir: self.project_dir.clone(),
             runtime: self.runtime.clone(),
@@ -100,14 +139,33 @@ pub struct FnBuildOutput {
     pub build_dir: PathBuf,
     // Map of source paths to their original or rewritten name as they.
     pub paths: HashMap<PathBuf, PathBuf>,
+    /// Map of source paths to the external source maps of their outputs.
+    #[serde(default)]
+    pub source_maps: HashMap<PathBuf, PathBuf>,
+}
+
+/// Handler fn deployed from a function's build output.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnBuildHandler {
+    /// Handler fn name exported by the handler's module.
+    pub fn_name: String,
+    /// Build dir relative path of the handler's module, which is the entrypoint's output path
+    /// unless the build generates a module wrapping the entrypoint's handler fn.
+    pub path: PathBuf,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct FnBuildManifest {
+    /// Checksum of the build mode and transform options that produced the build's outputs.
+    pub build_options: Checksum,
     /// Checksums of the original sources of the function's build.
     pub checksums: HashMap<PathBuf, Checksum>,
     pub dependencies: FnDependencies,
     pub entrypoint: PathBuf,
+    /// Env vars the build's outputs require of the deployed function's configuration.
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>,
+    pub handler: FnBuildHandler,
     pub output: FnBuildOutput,
     pub sources: Vec<FnSource>,
 }
@@ -120,6 +178,20 @@ pub enum FnBuildError {
     ParseError(#[from] FnParseError),
     #[error("build task error: {0}")]
     KnownError(#[from] anyhow::Error),
+    #[error("error bundling function: {0}")]
+    BundleError(String),
+    #[error("imported package {0} is not installed to the project's .venv or venv virtualenv")]
+    UninstalledPackage(String),
+}
+
+impl FnBuildError {
+    /// Diagnostics of a function's source code that failed to parse or transpile.
+    pub fn diagnostics(&self) -> &[FnDiagnostic] {
+        match self {
+            FnBuildError::ParseError(err) => err.diagnostics(),
+            _ => &[],
+        }
+    }
 }
 
 pub type FnBuildResult<T> = Result<T, FnBuildError>;
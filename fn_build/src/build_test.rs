@@ -2,9 +2,12 @@ use crate::runtime::node::{build_node_fn, NodeConfig};
 use crate::runtime::Runtime;
 use crate::testing::unzip;
 use crate::{build_fn, FnOutputConfig};
-use crate::{BuildMode, FnBuildSpec};
+use crate::{
+    BuildMode, FnBuildHandler, FnBuildSpec, FnBundleConfig, FnSourceMapConfig, FnSourceMaps,
+};
 use crate::{FnBuildError, FnParseError};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::Arc;
 use std::{env, fs};
 use temp_dir::TempDir;
@@ -15,6 +18,8 @@ async fn build_fn_errors_for_invalid_extension() {
     for entrypoint in &["README", "README.md"] {
         fs::write(build_dir.child(entrypoint), "").unwrap();
         let build_spec = FnBuildSpec {
+            bundle: None,
+            conditions: Vec::new(),
             entrypoint: PathBuf::from(entrypoint),
             handler_fn_name: "GET".to_string(),
             mode: BuildMode::Debug,
@@ -24,8 +29,10 @@ async fn build_fn_errors_for_invalid_extension() {
                 dirname: "build-test".into(),
                 use_build_mode: true,
             },
+            previous_build: None,
             project_dir: Arc::new(build_dir.path().to_path_buf()),
             runtime: Runtime::Node(Default::default()),
+            source_maps: None,
         };
         match build_fn(build_spec).await {
             Err(FnBuildError::ParseError(FnParseError::InvalidFileType)) => {}
@@ -34,12 +41,46 @@ async fn build_fn_errors_for_invalid_extension() {
     }
 }
 
+#[tokio::test]
+async fn build_fn_errors_for_missing_handler() {
+    let fixture_path = "fixtures/node/js/npm_dependencies/with_subpath";
+    let build_root_temp = TempDir::new().unwrap();
+    let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
+    let build_result = build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.js"),
+        handler_fn_name: "DELET".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: build_root_temp.path().to_path_buf(),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir,
+        runtime: Runtime::Node(None),
+        source_maps: None,
+    })
+    .await;
+    match build_result {
+        Err(FnBuildError::ParseError(FnParseError::MissingHandler(entrypoint, handler))) => {
+            assert_eq!(entrypoint, PathBuf::from("routes/data/lambda.js"));
+            assert_eq!(handler, "DELET");
+        }
+        _ => panic!(),
+    };
+}
+
 #[tokio::test]
 async fn build_fn_produces_archive() {
     let fixture_path = "fixtures/node/js/npm_dependencies/with_subpath";
     let build_root_temp = TempDir::new().unwrap();
     let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
     let build_manifest = build_node_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: PathBuf::from("routes/data/lambda.js"),
         handler_fn_name: "DELETE".to_string(),
         mode: BuildMode::Debug,
@@ -49,10 +90,12 @@ async fn build_fn_produces_archive() {
             dirname: "build-test".into(),
             use_build_mode: true,
         },
+        previous_build: None,
         project_dir: project_dir.clone(),
         runtime: Runtime::Node(Some(Arc::new(
             NodeConfig::read_configs(&project_dir).unwrap(),
         ))),
+        source_maps: None,
     })
     .await
     .unwrap();
@@ -90,6 +133,8 @@ async fn build_fn_produces_checksums() {
     let build_root_temp = TempDir::new().unwrap();
     let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
     let build_manifest = build_node_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: PathBuf::from("routes/data/lambda.js"),
         handler_fn_name: "DELETE".to_string(),
         mode: BuildMode::Debug,
@@ -99,10 +144,12 @@ async fn build_fn_produces_checksums() {
             dirname: "build-test".into(),
             use_build_mode: true,
         },
+        previous_build: None,
         project_dir: project_dir.clone(),
         runtime: Runtime::Node(Some(Arc::new(
             NodeConfig::read_configs(&project_dir).unwrap(),
         ))),
+        source_maps: None,
     })
     .await
     .unwrap();
@@ -115,3 +162,337 @@ async fn build_fn_produces_checksums() {
         .checksums
         .contains_key(&PathBuf::from("routes/data/lambda.js")));
 }
+
+#[tokio::test]
+async fn build_fn_writes_source_maps_and_enables_node_source_maps() {
+    let fixture_path = "fixtures/node/ts/import_uses_js";
+    let build_root_temp = TempDir::new().unwrap();
+    let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
+    let build_manifest = build_node_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.ts"),
+        handler_fn_name: "GET".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: build_root_temp.path().to_path_buf(),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir: project_dir.clone(),
+        runtime: Runtime::Node(Some(Arc::new(
+            NodeConfig::read_configs(&project_dir).unwrap(),
+        ))),
+        source_maps: Some(FnSourceMapConfig {
+            source_maps: FnSourceMaps::External,
+            enable_node_source_maps: true,
+        }),
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        build_manifest.env_vars.get("NODE_OPTIONS"),
+        Some(&"--enable-source-maps".to_string())
+    );
+    let build_dir = &build_manifest.output.build_dir;
+    for (source_path, source_map_path) in [
+        ("routes/data/lambda.ts", "routes/data/lambda.js.map"),
+        ("lib/data.ts", "lib/data.js.map"),
+    ] {
+        assert_eq!(
+            build_manifest
+                .output
+                .source_maps
+                .get(Path::new(source_path)),
+            Some(&PathBuf::from(source_map_path))
+        );
+        assert!(build_dir.join(source_map_path).is_file());
+    }
+    assert!(fs::read_to_string(build_dir.join("routes/data/lambda.js"))
+        .unwrap()
+        .ends_with("//# sourceMappingURL=lambda.js.map\n"));
+}
+
+#[tokio::test]
+async fn build_fn_bundles_sources_and_es_module_packages() {
+    let project_temp = TempDir::new().unwrap();
+    let write = |path: &str, content: &str| {
+        let path = project_temp.child(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write(
+        "package.json",
+        r#"{"type":"module","dependencies":{"cjs-lib":"1.0.0","es-lib":"1.0.0"}}"#,
+    );
+    write(
+        "routes/data/lambda.js",
+        "import { getData } from './data.js'\nexport const DELETE = () => getData()",
+    );
+    write(
+        "routes/data/data.js",
+        "import cjs from 'cjs-lib'\nimport { format } from 'es-lib'\nexport const getData = () => format(cjs)",
+    );
+    write(
+        "node_modules/cjs-lib/package.json",
+        r#"{"main":"index.js"}"#,
+    );
+    write("node_modules/cjs-lib/index.js", "module.exports = 'data'");
+    write(
+        "node_modules/es-lib/package.json",
+        r#"{"type":"module","version":"1.0.0"}"#,
+    );
+    write(
+        "node_modules/es-lib/index.js",
+        "export const format = (data) => `${data}!`",
+    );
+    let project_dir = Arc::new(project_temp.path().to_path_buf());
+    let build_manifest = build_node_fn(FnBuildSpec {
+        bundle: Some(FnBundleConfig {
+            inline_packages: true,
+        }),
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.js"),
+        handler_fn_name: "DELETE".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: project_temp.child(".l3"),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir: project_dir.clone(),
+        runtime: Runtime::Node(None),
+        source_maps: None,
+    })
+    .await
+    .unwrap();
+    let bundle_path = PathBuf::from("routes/data/lambda.mjs");
+    for source_path in ["routes/data/lambda.js", "routes/data/data.js"] {
+        assert_eq!(
+            build_manifest.output.paths.get(&PathBuf::from(source_path)),
+            Some(&bundle_path)
+        );
+    }
+    let build_dir = &build_manifest.output.build_dir;
+    assert_eq!(
+        fs::read_to_string(build_dir.join(&bundle_path)).unwrap(),
+        "import cjs from 'cjs-lib';\nconst format = (data)=>`${data}!`;\nconst getData = ()=>format(cjs);\nconst DELETE = ()=>getData();\nexport { DELETE as DELETE };\n"
+    );
+    assert!(!build_dir.join("routes/data/data.js").exists());
+    assert!(build_dir.join("package.json").is_file());
+    assert!(build_dir.join("node_modules/cjs-lib/index.js").is_file());
+    assert!(!build_dir.join("node_modules/es-lib").exists());
+}
+
+#[tokio::test]
+async fn build_fn_rebuilds_bundle_when_tsconfig_changes() {
+    let project_temp = TempDir::new().unwrap();
+    fs::write(project_temp.child("package.json"), r#"{"type":"module"}"#).unwrap();
+    fs::write(
+        project_temp.child("lambda.ts"),
+        "export const GET = (data?: string) => data ?? 'data'",
+    )
+    .unwrap();
+    let build_spec = |previous_build| FnBuildSpec {
+        bundle: Some(FnBundleConfig {
+            inline_packages: false,
+        }),
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("lambda.ts"),
+        handler_fn_name: "GET".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: project_temp.child(".l3"),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build,
+        project_dir: Arc::new(project_temp.path().to_path_buf()),
+        runtime: Runtime::Node(None),
+        source_maps: None,
+    };
+    let mut bundles = Vec::new();
+    let mut previous_build = None;
+    for target in ["es2022", "es2015"] {
+        fs::write(
+            project_temp.child("tsconfig.json"),
+            format!(r#"{{"compilerOptions":{{"target":"{target}"}}}}"#),
+        )
+        .unwrap();
+        let build_manifest = build_node_fn(build_spec(previous_build)).await.unwrap();
+        bundles
+            .push(fs::read_to_string(build_manifest.output.build_dir.join("lambda.mjs")).unwrap());
+        previous_build = Some(build_manifest);
+    }
+    assert!(bundles[0].contains("??"));
+    assert!(!bundles[1].contains("??"));
+}
+
+#[tokio::test]
+async fn build_fn_inlines_helpers_of_ts_with_import_helpers() {
+    let project_temp = TempDir::new().unwrap();
+    fs::write(project_temp.child("package.json"), r#"{"type":"module"}"#).unwrap();
+    fs::write(
+        project_temp.child("tsconfig.json"),
+        r#"{"compilerOptions":{"importHelpers":true,"target":"es2015"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        project_temp.child("lambda.ts"),
+        "export const GET = async (data: object) => ({ ...data, status: 'ok' })",
+    )
+    .unwrap();
+    let project_dir = Arc::new(project_temp.path().to_path_buf());
+    let build_manifest = build_node_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("lambda.ts"),
+        handler_fn_name: "GET".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: project_temp.child(".l3"),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir: project_dir.clone(),
+        runtime: Runtime::Node(Some(Arc::new(
+            NodeConfig::read_configs(&project_dir).unwrap(),
+        ))),
+        source_maps: None,
+    })
+    .await
+    .unwrap();
+    let build_dir = &build_manifest.output.build_dir;
+    assert!(!fs::read_to_string(build_dir.join("lambda.js"))
+        .unwrap()
+        .contains("@swc/helpers"));
+    let output = Command::new("node")
+        .args([
+            "--input-type=module",
+            "-e",
+            "import { GET } from './lambda.js'; console.log(JSON.stringify(await GET({ id: 1 })))",
+        ])
+        .current_dir(build_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "{\"id\":1,\"status\":\"ok\"}\n"
+    );
+}
+
+#[tokio::test]
+async fn build_fn_errors_for_bundling_commonjs_sources() {
+    let fixture_path = "fixtures/node/js/commonjs/exports_fn";
+    let build_root_temp = TempDir::new().unwrap();
+    let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
+    let build_result = build_fn(FnBuildSpec {
+        bundle: Some(FnBundleConfig {
+            inline_packages: false,
+        }),
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.js"),
+        handler_fn_name: "GET".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: build_root_temp.path().to_path_buf(),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir: project_dir.clone(),
+        runtime: Runtime::Node(Some(Arc::new(
+            NodeConfig::read_configs(&project_dir).unwrap(),
+        ))),
+        source_maps: None,
+    })
+    .await;
+    match build_result {
+        Err(FnBuildError::BundleError(err)) => assert_eq!(
+            err,
+            "routes/data/lambda.js is a CommonJS module and cannot be bundled"
+        ),
+        _ => panic!(),
+    }
+}
+
+#[tokio::test]
+async fn build_fn_errors_for_uninstalled_python_package() {
+    let fixture_path = "fixtures/python/packages/package_import";
+    let build_root_temp = TempDir::new().unwrap();
+    let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
+    let build_result = build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.py"),
+        handler_fn_name: "get".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: build_root_temp.path().to_path_buf(),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir,
+        runtime: Runtime::Python(None),
+        source_maps: None,
+    })
+    .await;
+    match build_result {
+        Err(FnBuildError::UninstalledPackage(package)) => assert_eq!(package, "requests"),
+        _ => panic!(),
+    }
+}
+
+#[tokio::test]
+async fn build_fn_wraps_async_python_handler() {
+    let fixture_path = "fixtures/python/http_routes/async_get_fn";
+    let build_root_temp = TempDir::new().unwrap();
+    let project_dir = Arc::new(env::current_dir().unwrap().join(fixture_path));
+    let build_manifest = build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.py"),
+        handler_fn_name: "get".to_string(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: build_root_temp.path().to_path_buf(),
+            create_archive: false,
+            dirname: "build-test".into(),
+            use_build_mode: true,
+        },
+        previous_build: None,
+        project_dir,
+        runtime: Runtime::Python(None),
+        source_maps: None,
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        build_manifest.handler,
+        FnBuildHandler {
+            fn_name: "get".into(),
+            path: PathBuf::from("routes/data/lambda_l3_async.py"),
+        }
+    );
+    let output = Command::new("python3")
+        .args([
+            "-c",
+            "import importlib; print(importlib.import_module('routes.data.lambda_l3_async').get())",
+        ])
+        .current_dir(&build_manifest.output.build_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "data\n");
+}
@@ -6,7 +6,7 @@ use std::path::Path;
 use std::{fs, io};
 
 /// Sha256 checksum.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Checksum(String);
 
 impl Checksum {
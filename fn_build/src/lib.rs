@@ -25,7 +25,12 @@ mod testing;
 use l3_fn_config::Language;
 
 pub use crate::build::*;
+pub use crate::checksum::Checksum;
 pub use crate::parse::*;
+pub use l3_ast_parse::{
+    FnDiagnostic, FnDiagnosticSeverity, FnDiagnosticSpan, FnHandler, FnHandlerDefinition,
+    FnHandlerSignature, FnSourcePosition,
+};
 
 /// Builds a lambda function.
 pub async fn build_fn(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildManifest> {
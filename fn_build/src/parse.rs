@@ -1,5 +1,7 @@
 use crate::runtime::node::NodeConfigError;
+use crate::runtime::python::PythonConfigError;
 use crate::runtime::Runtime;
+use l3_ast_parse::{format_diagnostics, AstParseError, FnDiagnostic, FnHandler};
 use l3_fn_config::Language;
 use serde::{Deserialize, Serialize};
 use std::io;
@@ -7,6 +9,9 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 pub struct FnParseSpec {
+    /// Custom conditions matched by package.json "imports" and "exports" in addition to the
+    /// conditions of Node.js. Supported by Node.js functions.
+    pub conditions: Vec<String>,
     pub entrypoint: PathBuf,
     pub project_dir: Arc<PathBuf>,
     pub runtime: Runtime,
@@ -18,26 +23,64 @@ impl FnParseSpec {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum FnDependencies {
-    Required,
+    /// Packages imported by the function's sources sorted by name.
+    Packages(Vec<FnPackageDependency>),
     Unused,
 }
 
+/// A package imported by a function's sources.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FnPackageDependency {
+    pub name: String,
+    /// Version requirement declared by the project, such as `^1.2.0` from package.json.
+    pub version: Option<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct FnEntrypoint {
     /// Exported functions from entrypoint source file.
-    pub handlers: Vec<String>,
+    pub handlers: Vec<FnHandler>,
     pub path: PathBuf,
 }
 
+impl FnEntrypoint {
+    pub fn handler(&self, handler_fn_name: &str) -> Option<&FnHandler> {
+        self.handlers
+            .iter()
+            .find(|handler| handler.name == handler_fn_name)
+    }
+
+    pub fn handler_names(&self) -> Vec<&str> {
+        self.handlers
+            .iter()
+            .map(|handler| handler.name.as_str())
+            .collect()
+    }
+
+    /// Errors with FnParseError::MissingHandler if the entrypoint does not export the handler fn.
+    pub fn require_handler(&self, handler_fn_name: &str) -> FnParseResult<()> {
+        match self.handler(handler_fn_name) {
+            Some(_) => Ok(()),
+            None => Err(FnParseError::MissingHandler(
+                self.path.clone(),
+                handler_fn_name.to_string(),
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ModuleImport {
     PackageDependency {
         package: String,
         subpath: Option<String>,
+        /// Project relative path of an installed npm package's module resolved by its
+        /// package.json "exports", "main" or "module", such as `node_modules/data-lib/index.js`.
+        path: Option<PathBuf>,
     },
     /// A source file import specified by relative path from the project's root.
     RelativeSource(PathBuf),
@@ -66,6 +109,8 @@ impl FnParseManifest {
 
 #[derive(thiserror::Error, Debug)]
 pub enum FnParseError {
+    #[error("compiler error: {0}")]
+    CompilerError(String),
     #[error("entrypoint file type is unsupported")]
     InvalidFileType,
     #[error("{0}")]
@@ -76,10 +121,33 @@ pub enum FnParseError {
     MissingHandler(PathBuf, String),
     #[error("error reading package.json: {0}")]
     NodeConfig(#[from] NodeConfigError),
-    #[error("syntax error")]
-    SyntaxError,
+    #[error("{0}")]
+    PythonConfig(#[from] PythonConfigError),
+    #[error("syntax error:\n{}", format_diagnostics(.0))]
+    SyntaxError(Vec<FnDiagnostic>),
     #[error("could not resolve \"{import}\" from \"{from}\"")]
     UnresolvedImport { from: PathBuf, import: String },
 }
 
+impl FnParseError {
+    /// Diagnostics of a function's source code that failed to parse.
+    pub fn diagnostics(&self) -> &[FnDiagnostic] {
+        match self {
+            FnParseError::SyntaxError(diagnostics) => diagnostics,
+            _ => &[],
+        }
+    }
+}
+
+impl From<AstParseError> for FnParseError {
+    fn from(err: AstParseError) -> Self {
+        match err {
+            AstParseError::InvalidFileType(_) => FnParseError::InvalidFileType,
+            AstParseError::IoError(err) => FnParseError::IoError(err),
+            AstParseError::OperationError(err) => FnParseError::CompilerError(err),
+            AstParseError::SyntaxError(diagnostics) => FnParseError::SyntaxError(diagnostics),
+        }
+    }
+}
+
 pub type FnParseResult<T> = Result<T, FnParseError>;
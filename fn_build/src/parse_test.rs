@@ -1,8 +1,8 @@
 use temp_dir::TempDir;
 
 use crate::runtime::Runtime;
-use crate::{parse_entrypoint, parse_fn, FnEntrypoint};
-use crate::{FnParseError, FnParseSpec};
+use crate::{parse_entrypoint, parse_fn, FnEntrypoint, FnHandler, FnHandlerDefinition};
+use crate::{FnDiagnosticSeverity, FnHandlerSignature, FnParseError, FnParseSpec};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::{env, fs};
@@ -13,6 +13,7 @@ async fn parse_fn_errors_for_invalid_extension() {
     for entrypoint in &["README", "README.md"] {
         fs::write(temp_dir.child(entrypoint), "").unwrap();
         let parse_spec = FnParseSpec {
+            conditions: Vec::new(),
             entrypoint: PathBuf::from(entrypoint),
             project_dir: Arc::new(temp_dir.path().to_path_buf()),
             runtime: Runtime::Node(Default::default()),
@@ -29,6 +30,7 @@ async fn parse_fn_errors_for_missing_entrypoint() {
     let temp_dir = TempDir::new().unwrap();
     let entrypoint = "data.ts";
     let parse_spec = FnParseSpec {
+        conditions: Vec::new(),
         entrypoint: PathBuf::from(entrypoint),
         project_dir: Arc::new(temp_dir.path().to_path_buf()),
         runtime: Runtime::Node(Default::default()),
@@ -45,6 +47,7 @@ async fn parse_entrypoint_errors_for_invalid_extension() {
     for entrypoint in &["README", "README.md"] {
         fs::write(temp_dir.child(entrypoint), "").unwrap();
         let parse_spec = FnParseSpec {
+            conditions: Vec::new(),
             entrypoint: PathBuf::from(entrypoint),
             project_dir: Arc::new(temp_dir.path().to_path_buf()),
             runtime: Runtime::Node(Default::default()),
@@ -61,6 +64,7 @@ async fn parse_entrypoint_errors_for_missing_entrypoint() {
     let temp_dir = TempDir::new().unwrap();
     let entrypoint = "data.ts";
     let parse_spec = FnParseSpec {
+        conditions: Vec::new(),
         entrypoint: PathBuf::from(entrypoint),
         project_dir: Arc::new(temp_dir.path().to_path_buf()),
         runtime: Runtime::Node(Default::default()),
@@ -75,6 +79,7 @@ async fn parse_entrypoint_errors_for_missing_entrypoint() {
 async fn parse_entrypoint_of_js_fn() {
     assert_eq!(
         parse_entrypoint(FnParseSpec {
+            conditions: Vec::new(),
             entrypoint: PathBuf::from("routes/data/lambda.js"),
             project_dir: Arc::new(
                 env::current_dir()
@@ -86,7 +91,16 @@ async fn parse_entrypoint_of_js_fn() {
         .await
         .unwrap(),
         FnEntrypoint {
-            handlers: vec!("GET".into()),
+            handlers: vec!(FnHandler {
+                definition: FnHandlerDefinition::FunctionExpression,
+                doc: None,
+                name: "GET".into(),
+                signature: Some(FnHandlerSignature {
+                    event_type: None,
+                    is_async: false,
+                    param_count: 0,
+                }),
+            }),
             path: PathBuf::from("routes/data/lambda.js")
         }
     );
@@ -96,6 +110,7 @@ async fn parse_entrypoint_of_js_fn() {
 async fn parse_entrypoint_of_python_fn() {
     assert_eq!(
         parse_entrypoint(FnParseSpec {
+            conditions: Vec::new(),
             entrypoint: PathBuf::from("routes/data/lambda.py"),
             project_dir: Arc::new(
                 env::current_dir()
@@ -107,8 +122,250 @@ async fn parse_entrypoint_of_python_fn() {
         .await
         .unwrap(),
         FnEntrypoint {
-            handlers: vec!("get".into()),
+            handlers: vec!(FnHandler {
+                definition: FnHandlerDefinition::Function,
+                doc: None,
+                name: "get".into(),
+                signature: Some(FnHandlerSignature {
+                    event_type: None,
+                    is_async: false,
+                    param_count: 0,
+                }),
+            }),
             path: PathBuf::from("routes/data/lambda.py")
         }
     );
 }
+
+#[tokio::test]
+async fn parse_fn_errors_with_diagnostics_of_js_syntax_error() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("lambda.js"),
+        "export const GET = () => {\n    return 1 +;\n}\n",
+    )
+    .unwrap();
+    let parse_spec = FnParseSpec {
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("lambda.js"),
+        project_dir: Arc::new(temp_dir.path().to_path_buf()),
+        runtime: Runtime::Node(Default::default()),
+    };
+    match parse_fn(parse_spec).await {
+        Err(FnParseError::SyntaxError(diagnostics)) => {
+            let diagnostic = diagnostics.first().unwrap();
+            assert_eq!(diagnostic.path, temp_dir.child("lambda.js"));
+            assert_eq!(diagnostic.severity, FnDiagnosticSeverity::Error);
+            let start = &diagnostic.span.as_ref().unwrap().start;
+            assert_eq!((start.line, start.column), (2, 15));
+        }
+        _ => panic!(),
+    };
+}
+
+#[tokio::test]
+async fn parse_fn_errors_with_diagnostics_of_python_syntax_error() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("lambda.py"),
+        "def handler(event, context):\n    return (\n",
+    )
+    .unwrap();
+    let parse_spec = FnParseSpec {
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("lambda.py"),
+        project_dir: Arc::new(temp_dir.path().to_path_buf()),
+        runtime: Runtime::Python(None),
+    };
+    match parse_fn(parse_spec).await {
+        Err(FnParseError::SyntaxError(diagnostics)) => {
+            let diagnostic = diagnostics.first().unwrap();
+            assert_eq!(diagnostic.path, temp_dir.child("lambda.py"));
+            assert_eq!(diagnostic.severity, FnDiagnosticSeverity::Error);
+            let start = &diagnostic.span.as_ref().unwrap().start;
+            assert_eq!((start.line, start.column), (3, 1));
+        }
+        _ => panic!(),
+    };
+}
+
+#[tokio::test]
+async fn parse_fn_errors_for_python_absolute_import_of_adjacent_module() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.child("routes/data")).unwrap();
+    fs::write(
+        temp_dir.child("routes/data/lambda.py"),
+        "import lib\n\ndef get():\n    return lib.get_data()\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("routes/data/lib.py"),
+        "def get_data():\n    return 'data'\n",
+    )
+    .unwrap();
+    let parse_spec = FnParseSpec {
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("routes/data/lambda.py"),
+        project_dir: Arc::new(temp_dir.path().to_path_buf()),
+        runtime: Runtime::Python(None),
+    };
+    match parse_fn(parse_spec).await {
+        Err(FnParseError::UnresolvedImport { from, import }) => {
+            assert_eq!(from, PathBuf::from("routes/data/lambda.py"));
+            assert_eq!(import, "lib");
+        }
+        _ => panic!(),
+    };
+}
+
+#[tokio::test]
+async fn parse_entrypoint_follows_re_exports_of_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("package.json"), r#"{"type":"module"}"#).unwrap();
+    fs::write(
+        temp_dir.child("lambda.js"),
+        "export * from './routes.js'\nexport { missing } from './routes.js'",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("routes.js"),
+        "export * from './lambda.js'\nexport const GET = () => {}\nexport default () => {}",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_entrypoint(FnParseSpec {
+            conditions: Vec::new(),
+            entrypoint: PathBuf::from("lambda.js"),
+            project_dir: Arc::new(temp_dir.path().to_path_buf()),
+            runtime: Runtime::Node(None),
+        })
+        .await
+        .unwrap()
+        .handlers,
+        vec!(FnHandler {
+            definition: FnHandlerDefinition::ReExport {
+                name: "GET".into(),
+                path: PathBuf::from("routes.js"),
+            },
+            doc: None,
+            name: "GET".into(),
+            signature: Some(FnHandlerSignature {
+                event_type: None,
+                is_async: false,
+                param_count: 0,
+            }),
+        })
+    );
+}
+
+#[tokio::test]
+async fn parse_fn_resolves_subpath_imports_with_conditions_of_spec() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("package.json"),
+        r##"{"type":"module","imports":{"#data":{"development":"./data-mock.js","default":"./data.js"}}}"##,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("lambda.js"),
+        "import data from '#data'\nexport const GET = () => data",
+    )
+    .unwrap();
+    fs::write(temp_dir.child("data.js"), "export default []").unwrap();
+    fs::write(temp_dir.child("data-mock.js"), "export default []").unwrap();
+    for (conditions, expected) in [
+        (Vec::new(), "data.js"),
+        (vec!["development".to_string()], "data-mock.js"),
+    ] {
+        let mut source_paths = parse_fn(FnParseSpec {
+            conditions,
+            entrypoint: PathBuf::from("lambda.js"),
+            project_dir: Arc::new(temp_dir.path().to_path_buf()),
+            runtime: Runtime::Node(None),
+        })
+        .await
+        .unwrap()
+        .source_paths();
+        source_paths.sort();
+        assert_eq!(
+            source_paths,
+            vec!(
+                PathBuf::from(expected),
+                PathBuf::from("lambda.js"),
+                PathBuf::from("package.json")
+            )
+        );
+    }
+}
+
+#[tokio::test]
+async fn parse_fn_resolves_required_subpath_imports_with_conditions_of_spec() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("package.json"),
+        r##"{"imports":{"#data":{"import":"./data.mjs","development":"./data-mock.cjs","require":"./data.cjs"}}}"##,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.child("lambda.js"),
+        "const data = require('#data')\nexports.GET = () => data",
+    )
+    .unwrap();
+    for path in ["data.cjs", "data.mjs", "data-mock.cjs"] {
+        fs::write(temp_dir.child(path), "").unwrap();
+    }
+    for (conditions, expected) in [
+        (Vec::new(), "data.cjs"),
+        (vec!["development".to_string()], "data-mock.cjs"),
+    ] {
+        let mut source_paths = parse_fn(FnParseSpec {
+            conditions,
+            entrypoint: PathBuf::from("lambda.js"),
+            project_dir: Arc::new(temp_dir.path().to_path_buf()),
+            runtime: Runtime::Node(None),
+        })
+        .await
+        .unwrap()
+        .source_paths();
+        source_paths.sort();
+        assert_eq!(
+            source_paths,
+            vec!(
+                PathBuf::from(expected),
+                PathBuf::from("lambda.js"),
+                PathBuf::from("package.json")
+            )
+        );
+    }
+}
+
+#[tokio::test]
+async fn parse_entrypoint_of_python_handler_signature_and_docstring() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("lambda.py"),
+        "async def handler(event: dict[str, Any], context):\n    \"\"\"Processes orders.\n\n    Retries failed orders.\n    \"\"\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        parse_entrypoint(FnParseSpec {
+            conditions: Vec::new(),
+            entrypoint: PathBuf::from("lambda.py"),
+            project_dir: Arc::new(temp_dir.path().to_path_buf()),
+            runtime: Runtime::Python(None),
+        })
+        .await
+        .unwrap()
+        .handlers,
+        vec!(FnHandler {
+            definition: FnHandlerDefinition::Function,
+            doc: Some("Processes orders.\n\nRetries failed orders.".into()),
+            name: "handler".into(),
+            signature: Some(FnHandlerSignature {
+                event_type: Some("dict[str, Any]".into()),
+                is_async: true,
+                param_count: 2,
+            }),
+        })
+    );
+}
@@ -1,4 +1,4 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 /// Joins file paths and rewrites `.` and `..` segments from result.
 pub fn join_file_paths(base: &Path, relative: &Path) -> PathBuf {
@@ -34,3 +34,41 @@ pub fn join_file_paths(base: &Path, relative: &Path) -> PathBuf {
         p
     }
 }
+
+/// Rewrites `.` and `..` segments of a path without accessing the filesystem. Leading `..`
+/// segments of a relative path are retained.
+pub fn normalize_path(p: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in p.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => _ = normalized.pop(),
+                // `..` of the root dir is the root dir
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Relative path from a dir to a path, where both are absolute or relative to the same dir.
+pub fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from_dir = normalize_path(from_dir);
+    let to = normalize_path(to);
+    let common = from_dir
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative = PathBuf::new();
+    for _ in from_dir.components().skip(common) {
+        relative.push("..");
+    }
+    for component in to.components().skip(common) {
+        relative.push(component);
+    }
+    relative
+}
@@ -1,4 +1,4 @@
-use crate::paths::join_file_paths;
+use crate::paths::{join_file_paths, normalize_path, relative_path};
 use std::path::PathBuf;
 
 #[test]
@@ -44,3 +44,31 @@ fn test_join_file_paths_joins_rel_path_with_sibling() {
     );
     assert!(result.is_file());
 }
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(&PathBuf::from("./lib/../src/./data.ts")),
+        PathBuf::from("src/data.ts")
+    );
+    assert_eq!(
+        normalize_path(&PathBuf::from("lib/../../shared/data.ts")),
+        PathBuf::from("../shared/data.ts")
+    );
+}
+
+#[test]
+fn test_relative_path() {
+    assert_eq!(
+        relative_path(&PathBuf::from("routes/data"), &PathBuf::from("lib/data.js")),
+        PathBuf::from("../../lib/data.js")
+    );
+    assert_eq!(
+        relative_path(&PathBuf::from("lib"), &PathBuf::from("lib/utils/data.js")),
+        PathBuf::from("utils/data.js")
+    );
+    assert_eq!(
+        relative_path(&PathBuf::from(""), &PathBuf::from("lib/data.js")),
+        PathBuf::from("lib/data.js")
+    );
+}
@@ -1,10 +1,16 @@
 use crate::archive::write_archive;
 use crate::checksum::Checksum;
-use crate::{FnBuildManifest, FnBuildOutput, FnBuildResult, FnBuildSpec, FnParseManifest};
-use std::collections::HashMap;
-use std::fs;
+use crate::paths::relative_path;
+use crate::{
+    FnBuildHandler, FnBuildManifest, FnBuildOutput, FnBuildResult, FnBuildSpec, FnParseManifest,
+    FnSourceMaps,
+};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::{fs, io};
 use tokio::task::JoinSet;
 
 // todo include completed BuildTask for error reporting and tracing
@@ -18,9 +24,29 @@ enum BuildTaskResult {
         source_path: PathBuf,
         /// None if source_path is used for path in build directory.
         output_path: Option<PathBuf>,
+        source_map_path: Option<PathBuf>,
+    },
+    /// Output of an unchanged source from the previous build is reused.
+    Reused {
+        checksum: Checksum,
+        source_path: PathBuf,
+        output_path: PathBuf,
+        source_map_path: Option<PathBuf>,
+    },
+    /// Sources of a source tree transformed into a single output.
+    TransformedTree {
+        checksums: HashMap<PathBuf, Checksum>,
+        output_path: PathBuf,
+        source_map_path: Option<PathBuf>,
+    },
+    /// Output of a source tree with unchanged sources from the previous build is reused.
+    ReusedTree {
+        checksums: HashMap<PathBuf, Checksum>,
+        output_path: PathBuf,
+        source_map_path: Option<PathBuf>,
     },
     /// Specifies a BuildTask completed that isn't tracked to merge into FnBuildManifest
-    Untracked,
+    Untracked { wrote_files: bool },
 }
 
 /// Compose work of a function build into model for parallel processing.
@@ -28,12 +54,24 @@ enum BuildTaskResult {
 pub enum BuildTask {
     /// Copies a directory recursively without creating checksums.
     /// This task does not track checksums becaues it is currently
-    /// used for copying node_modules.
+    /// used for copying npm packages from node_modules.
     CopyDirectoryRecursively(PathBuf),
+    /// Copies files of a directory to the root of the build dir without creating checksums.
+    /// Used for copying Python distributions from a virtualenv's site-packages.
+    CopyDirectoryFiles { dir: PathBuf, files: Vec<PathBuf> },
     /// Copy source files and get checksums.
     CopySourceFiles(Vec<PathBuf>),
+    /// Writes a file generated by the build that is not an output of a source, such as a module
+    /// wrapping the entrypoint's handler fn.
+    GenerateFile { path: PathBuf, content: String },
     /// Perform a transform function on a source file and get a checksum of source input.
     TransformSourceFile(PathBuf),
+    /// Perform a transform function on the entrypoint of a source tree to create one output for
+    /// all of the tree's sources, such as a bundle, and get checksums of the sources.
+    TransformSourceTree {
+        entrypoint: PathBuf,
+        sources: Vec<PathBuf>,
+    },
 }
 
 /// Transform functions declaratively resolve output path.
@@ -42,12 +80,87 @@ pub enum TransformResult {
     RetainPath(String),
     /// Result is written to build dir with a new extension.
     RewriteExt(String, String),
+    /// Result is written with its source map by the build's FnSourceMaps.
+    WithSourceMap(Box<TransformResult>, String),
+}
+
+impl TransformResult {
+    pub fn with_source_map(self, source_map: Option<String>) -> Self {
+        match source_map {
+            Some(source_map) => TransformResult::WithSourceMap(Box::new(self), source_map),
+            None => self,
+        }
+    }
+
+    fn split_source_map(self) -> (Self, Option<String>) {
+        match self {
+            TransformResult::WithSourceMap(result, source_map) => (*result, Some(source_map)),
+            result => (result, None),
+        }
+    }
+}
+
+/// Outputs of a previous build that are reusable for sources with unchanged checksums.
+struct CachedOutputs {
+    build_dir: Arc<PathBuf>,
+    checksums: HashMap<PathBuf, Checksum>,
+    paths: HashMap<PathBuf, PathBuf>,
+    source_maps: HashMap<PathBuf, PathBuf>,
+}
+
+impl CachedOutputs {
+    /// Previous build's outputs are only reusable when written to the same build dir with the
+    /// same build mode and transform options.
+    fn from_previous_build(
+        previous_build: &Option<FnBuildManifest>,
+        build_dir: &Arc<PathBuf>,
+        build_options: &Checksum,
+    ) -> Option<Self> {
+        let previous_build = previous_build.as_ref()?;
+        if &previous_build.build_options != build_options
+            || previous_build.output.build_dir != **build_dir
+        {
+            return None;
+        }
+        Some(Self {
+            build_dir: build_dir.clone(),
+            checksums: previous_build.checksums.clone(),
+            paths: previous_build.output.paths.clone(),
+            source_maps: previous_build.output.source_maps.clone(),
+        })
+    }
+
+    /// Output path and source map path of an unchanged source's output.
+    fn reusable_output(
+        &self,
+        source_path: &Path,
+        checksum: &Checksum,
+    ) -> Option<(PathBuf, Option<PathBuf>)> {
+        if self.checksums.get(source_path) != Some(checksum) {
+            return None;
+        }
+        let output_path = self.paths.get(source_path)?;
+        let source_map_path = self.source_maps.get(source_path);
+        if self.build_dir.join(output_path).is_file()
+            && source_map_path.is_none_or(|p| self.build_dir.join(p).is_file())
+        {
+            Some((output_path.clone(), source_map_path.cloned()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Checksum of a build's mode and the transform options a runtime uses for its sources.
+fn build_options_checksum(build_spec: &FnBuildSpec, transform_options: &str) -> Checksum {
+    Checksum::try_from(format!("{}\n{transform_options}", build_spec.mode).as_str()).unwrap()
 }
 
 pub async fn build_fn_inner<F>(
     build_spec: &FnBuildSpec,
     parse_manifest: FnParseManifest,
     build_tasks: Vec<BuildTask>,
+    transform_options: &str,
     transform: F,
 ) -> FnBuildResult<FnBuildManifest>
 where
@@ -55,7 +168,18 @@ where
 {
     let build_root = build_spec.output_build_root();
     let build_dir = Arc::new(build_root.join(&build_spec.output.dirname));
+    let build_options = build_options_checksum(build_spec, transform_options);
+    let cached =
+        CachedOutputs::from_previous_build(&build_spec.previous_build, &build_dir, &build_options)
+            .map(Arc::new);
+    if cached.is_none() {
+        _ = fs::remove_dir_all(build_dir.as_path());
+    }
     let transform = Arc::new(transform);
+    let source_maps_config = build_spec
+        .source_maps
+        .as_ref()
+        .map(|config| config.source_maps.clone());
     let mut join_set: JoinSet<FnBuildResult<BuildTaskResult>> = JoinSet::new();
     for build_task in build_tasks {
         match build_task {
@@ -67,6 +191,14 @@ where
                     path,
                 ))
             }
+            BuildTask::CopyDirectoryFiles { dir, files } => {
+                assert!(dir.is_relative());
+                _ = join_set.spawn(copy_directory_files(
+                    build_spec.project_dir.join(dir),
+                    build_dir.clone(),
+                    files,
+                ))
+            }
             BuildTask::CopySourceFiles(paths) => {
                 for path in paths {
                     assert!(path.is_relative());
@@ -74,9 +206,14 @@ where
                         build_spec.project_dir.clone(),
                         build_dir.clone(),
                         path,
+                        cached.clone(),
                     ))
                 }
             }
+            BuildTask::GenerateFile { path, content } => {
+                assert!(path.is_relative());
+                _ = join_set.spawn(generate_file(build_dir.clone(), path, content))
+            }
             BuildTask::TransformSourceFile(path) => {
                 assert!(path.is_relative());
                 _ = join_set.spawn(build_source(
@@ -84,12 +221,31 @@ where
                     build_dir.clone(),
                     path,
                     transform.clone(),
+                    cached.clone(),
+                    source_maps_config.clone(),
+                ))
+            }
+            BuildTask::TransformSourceTree {
+                entrypoint,
+                sources,
+            } => {
+                assert!(entrypoint.is_relative());
+                _ = join_set.spawn(build_source_tree(
+                    build_spec.project_dir.clone(),
+                    build_dir.clone(),
+                    entrypoint,
+                    sources,
+                    transform.clone(),
+                    cached.clone(),
+                    source_maps_config.clone(),
                 ))
             }
         }
     }
     let mut checksums = HashMap::new();
     let mut paths = HashMap::new();
+    let mut source_maps = HashMap::new();
+    let mut wrote_files = false;
     while let Some(join_result) = join_set.join_next().await {
         match join_result {
             Err(join_err) => panic!("panic in build task: {}", join_err),
@@ -98,6 +254,7 @@ where
                     checksum,
                     source_path,
                 }) => {
+                    wrote_files = true;
                     paths.insert(source_path.clone(), source_path.clone());
                     checksums.insert(source_path, checksum);
                 }
@@ -105,7 +262,12 @@ where
                     checksum,
                     source_path,
                     output_path,
+                    source_map_path,
                 }) => {
+                    wrote_files = true;
+                    if let Some(source_map_path) = source_map_path {
+                        source_maps.insert(source_path.clone(), source_map_path);
+                    }
                     match output_path {
                         Some(output_path) => {
                             debug_assert!(output_path.is_relative());
@@ -115,26 +277,97 @@ where
                     };
                     checksums.insert(source_path, checksum);
                 }
-                Ok(BuildTaskResult::Untracked) => {}
+                Ok(BuildTaskResult::Reused {
+                    checksum,
+                    source_path,
+                    output_path,
+                    source_map_path,
+                }) => {
+                    if let Some(source_map_path) = source_map_path {
+                        source_maps.insert(source_path.clone(), source_map_path);
+                    }
+                    paths.insert(source_path.clone(), output_path);
+                    checksums.insert(source_path, checksum);
+                }
+                Ok(BuildTaskResult::TransformedTree {
+                    checksums: tree_checksums,
+                    output_path,
+                    source_map_path,
+                }) => {
+                    wrote_files = true;
+                    for (source_path, checksum) in tree_checksums {
+                        if let Some(source_map_path) = &source_map_path {
+                            source_maps.insert(source_path.clone(), source_map_path.clone());
+                        }
+                        paths.insert(source_path.clone(), output_path.clone());
+                        checksums.insert(source_path, checksum);
+                    }
+                }
+                Ok(BuildTaskResult::ReusedTree {
+                    checksums: tree_checksums,
+                    output_path,
+                    source_map_path,
+                }) => {
+                    for (source_path, checksum) in tree_checksums {
+                        if let Some(source_map_path) = &source_map_path {
+                            source_maps.insert(source_path.clone(), source_map_path.clone());
+                        }
+                        paths.insert(source_path.clone(), output_path.clone());
+                        checksums.insert(source_path, checksum);
+                    }
+                }
+                Ok(BuildTaskResult::Untracked { wrote_files: wrote }) => wrote_files |= wrote,
                 Err(build_err) => return Err(build_err),
             },
         }
     }
+    if let Some(cached) = &cached {
+        // outputs of sources removed from the function's source tree
+        let output_paths: HashSet<&PathBuf> = paths.values().chain(source_maps.values()).collect();
+        for stale_output_path in cached.paths.values().chain(cached.source_maps.values()) {
+            if !output_paths.contains(stale_output_path) {
+                wrote_files = true;
+                _ = fs::remove_file(build_dir.join(stale_output_path));
+            }
+        }
+    }
+    let handler = FnBuildHandler {
+        fn_name: build_spec.handler_fn_name.clone(),
+        path: paths
+            .get(&parse_manifest.entrypoint.path)
+            .unwrap_or(&parse_manifest.entrypoint.path)
+            .clone(),
+    };
     Ok(FnBuildManifest {
+        build_options,
         checksums,
         dependencies: parse_manifest.dependencies,
         entrypoint: parse_manifest.entrypoint.path,
+        env_vars: HashMap::new(),
+        handler,
         sources: parse_manifest.sources,
         output: FnBuildOutput {
             archive_file: if build_spec.output.create_archive {
                 let archive_file = build_root.join(format!("{}.zip", &build_spec.output.dirname));
-                write_archive(&archive_file, &build_dir)?;
+                let previous_archive_file = build_spec
+                    .previous_build
+                    .as_ref()
+                    .and_then(|previous_build| previous_build.output.archive_file.as_ref());
+                if wrote_files
+                    || cached.is_none()
+                    || previous_archive_file != Some(&archive_file)
+                    || !archive_file.is_file()
+                {
+                    _ = fs::remove_file(&archive_file);
+                    write_archive(&archive_file, &build_dir)?;
+                }
                 Some(archive_file)
             } else {
                 None
             },
             build_dir: build_dir.to_path_buf(),
             paths,
+            source_maps,
         },
     })
 }
@@ -144,6 +377,8 @@ async fn build_source<F>(
     build_dir: Arc<PathBuf>,
     source_path: PathBuf,
     transform: Arc<F>,
+    cached: Option<Arc<CachedOutputs>>,
+    source_maps: Option<FnSourceMaps>,
 ) -> FnBuildResult<BuildTaskResult>
 where
     F: (Fn(&Path, String) -> FnBuildResult<TransformResult>) + Send + Sync + 'static,
@@ -151,34 +386,197 @@ where
     let abs_source_path = project_dir.join(&source_path);
     let source_content = fs::read_to_string(&abs_source_path)?;
     let checksum = Checksum::try_from(source_content.as_str())?;
-    let (output_path, content) = match transform(&abs_source_path, source_content)? {
+    if let Some((output_path, source_map_path)) =
+        cached.and_then(|c| c.reusable_output(&source_path, &checksum))
+    {
+        return Ok(BuildTaskResult::Reused {
+            checksum,
+            source_path,
+            output_path,
+            source_map_path,
+        });
+    }
+    let (transform_result, source_map) =
+        transform(&abs_source_path, source_content)?.split_source_map();
+    let (output_path, content) = match transform_result {
         TransformResult::RetainPath(content) => (None, content),
         TransformResult::RewriteExt(content, ext) => {
             (Some(source_path.with_extension(ext)), content)
         }
+        TransformResult::WithSourceMap(..) => unreachable!(),
     };
-    let dest = match &output_path {
-        Some(output_path) => build_dir.join(output_path),
-        None => build_dir.join(&source_path),
-    };
-    _ = fs::create_dir_all(dest.parent().unwrap());
-    fs::write(dest, content)?;
+    let source_map_path = write_output(
+        &project_dir,
+        &build_dir,
+        output_path.as_ref().unwrap_or(&source_path),
+        content,
+        source_map.zip(source_maps.as_ref()),
+    )?;
     Ok(BuildTaskResult::Transformed {
         checksum,
         source_path,
         output_path,
+        source_map_path,
+    })
+}
+
+async fn build_source_tree<F>(
+    project_dir: Arc<PathBuf>,
+    build_dir: Arc<PathBuf>,
+    entrypoint: PathBuf,
+    sources: Vec<PathBuf>,
+    transform: Arc<F>,
+    cached: Option<Arc<CachedOutputs>>,
+    source_maps: Option<FnSourceMaps>,
+) -> FnBuildResult<BuildTaskResult>
+where
+    F: (Fn(&Path, String) -> FnBuildResult<TransformResult>) + Send + Sync + 'static,
+{
+    let mut checksums = HashMap::new();
+    for source_path in sources {
+        let source_content = fs::read_to_string(project_dir.join(&source_path))?;
+        checksums.insert(source_path, Checksum::try_from(source_content.as_str())?);
+    }
+    // the tree's output is only reusable if every source of the tree is unchanged
+    if let Some(cached) = cached {
+        let reusable: Option<HashSet<(PathBuf, Option<PathBuf>)>> = checksums
+            .iter()
+            .map(|(source_path, checksum)| cached.reusable_output(source_path, checksum))
+            .collect();
+        if let Some(outputs) = reusable {
+            if outputs.len() == 1 {
+                let (output_path, source_map_path) = outputs.into_iter().next().unwrap();
+                return Ok(BuildTaskResult::ReusedTree {
+                    checksums,
+                    output_path,
+                    source_map_path,
+                });
+            }
+        }
+    }
+    let abs_entrypoint = project_dir.join(&entrypoint);
+    let entrypoint_content = fs::read_to_string(&abs_entrypoint)?;
+    let (transform_result, source_map) =
+        transform(&abs_entrypoint, entrypoint_content)?.split_source_map();
+    let (output_path, content) = match transform_result {
+        TransformResult::RetainPath(content) => (entrypoint, content),
+        TransformResult::RewriteExt(content, ext) => (entrypoint.with_extension(ext), content),
+        TransformResult::WithSourceMap(..) => unreachable!(),
+    };
+    let source_map_path = write_output(
+        &project_dir,
+        &build_dir,
+        &output_path,
+        content,
+        source_map.zip(source_maps.as_ref()),
+    )?;
+    Ok(BuildTaskResult::TransformedTree {
+        checksums,
+        output_path,
+        source_map_path,
     })
 }
 
+/// Writes a transformed output with its source map appended as a data URL or written to a
+/// `.map` file next to the output, returning the path of a written `.map` file.
+fn write_output(
+    project_dir: &Path,
+    build_dir: &Path,
+    output_path: &Path,
+    mut content: String,
+    source_map: Option<(String, &FnSourceMaps)>,
+) -> io::Result<Option<PathBuf>> {
+    let dest = build_dir.join(output_path);
+    _ = fs::create_dir_all(dest.parent().unwrap());
+    let Some((source_map, source_maps)) = source_map else {
+        fs::write(dest, content)?;
+        return Ok(None);
+    };
+    let source_map = rebase_source_map_sources(project_dir, output_path, &source_map)?;
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    match source_maps {
+        FnSourceMaps::External => {
+            let source_map_path = PathBuf::from(format!("{}.map", output_path.to_string_lossy()));
+            content.push_str(&format!(
+                "//# sourceMappingURL={}\n",
+                source_map_path.file_name().unwrap().to_string_lossy()
+            ));
+            fs::write(build_dir.join(&source_map_path), source_map)?;
+            fs::write(dest, content)?;
+            Ok(Some(source_map_path))
+        }
+        FnSourceMaps::Inline => {
+            content.push_str(&format!(
+                "//# sourceMappingURL=data:application/json;base64,{}\n",
+                BASE64_STANDARD.encode(source_map)
+            ));
+            fs::write(dest, content)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Rewrites the absolute or project relative paths of a source map's sources to paths relative
+/// to the output's dir, which mirrors the dir of the output's source in the build dir.
+fn rebase_source_map_sources(
+    project_dir: &Path,
+    output_path: &Path,
+    source_map: &str,
+) -> io::Result<String> {
+    let mut source_map: Value = serde_json::from_str(source_map)?;
+    let output_dir = output_path.parent().unwrap_or(Path::new(""));
+    if let Some(Value::Array(sources)) = source_map.get_mut("sources") {
+        for source in sources.iter_mut() {
+            if let Value::String(source_path) = source {
+                let source_path = Path::new(source_path.as_str());
+                let source_path = source_path.strip_prefix(project_dir).unwrap_or(source_path);
+                *source = Value::String(
+                    relative_path(output_dir, source_path)
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                );
+            }
+        }
+    }
+    Ok(source_map.to_string())
+}
+
+async fn generate_file(
+    build_dir: Arc<PathBuf>,
+    path: PathBuf,
+    content: String,
+) -> FnBuildResult<BuildTaskResult> {
+    let dest = build_dir.join(&path);
+    if fs::read_to_string(&dest).is_ok_and(|written| written == content) {
+        return Ok(BuildTaskResult::Untracked { wrote_files: false });
+    }
+    _ = fs::create_dir_all(dest.parent().unwrap());
+    fs::write(dest, content)?;
+    Ok(BuildTaskResult::Untracked { wrote_files: true })
+}
+
 async fn copy_source(
     project_dir: Arc<PathBuf>,
     build_dir: Arc<PathBuf>,
     source_path: PathBuf,
+    cached: Option<Arc<CachedOutputs>>,
 ) -> FnBuildResult<BuildTaskResult> {
-    let dest = build_dir.join(&source_path);
-    _ = fs::create_dir_all(dest.parent().unwrap());
     let source_content = fs::read_to_string(project_dir.join(&source_path))?;
     let checksum = Checksum::try_from(source_content.as_str())?;
+    if let Some((output_path, source_map_path)) =
+        cached.and_then(|c| c.reusable_output(&source_path, &checksum))
+    {
+        return Ok(BuildTaskResult::Reused {
+            checksum,
+            source_path,
+            output_path,
+            source_map_path,
+        });
+    }
+    let dest = build_dir.join(&source_path);
+    _ = fs::create_dir_all(dest.parent().unwrap());
     fs::write(&dest, &source_content)?;
     Ok(BuildTaskResult::Copied {
         checksum,
@@ -187,20 +585,59 @@ async fn copy_source(
 }
 
 // todo parallelize
+// files are only copied when the build dir does not have a copy of the same size modified since
+// the project file was last modified
 async fn copy_directory(
     project_dir: Arc<PathBuf>,
     build_dir: Arc<PathBuf>,
     source_path: PathBuf,
 ) -> FnBuildResult<BuildTaskResult> {
+    let mut wrote_files = false;
     for abs in l3_api_base::collect_files(&project_dir.join(&source_path)) {
         let rel = abs
             .strip_prefix(project_dir.as_path())
             .unwrap()
             .to_path_buf();
         let build_path = build_dir.join(&rel);
+        if is_copy_up_to_date(&abs, &build_path) {
+            continue;
+        }
         // todo optimize collect_files to visitor pattern to only call create_dir_all once per dir
         fs::create_dir_all(build_path.parent().unwrap())?;
         fs::copy(project_dir.join(&rel), build_path)?;
+        wrote_files = true;
+    }
+    Ok(BuildTaskResult::Untracked { wrote_files })
+}
+
+async fn copy_directory_files(
+    dir: PathBuf,
+    build_dir: Arc<PathBuf>,
+    files: Vec<PathBuf>,
+) -> FnBuildResult<BuildTaskResult> {
+    let mut wrote_files = false;
+    for rel in files {
+        assert!(rel.is_relative());
+        let abs = dir.join(&rel);
+        let build_path = build_dir.join(&rel);
+        if is_copy_up_to_date(&abs, &build_path) {
+            continue;
+        }
+        fs::create_dir_all(build_path.parent().unwrap())?;
+        fs::copy(abs, build_path)?;
+        wrote_files = true;
+    }
+    Ok(BuildTaskResult::Untracked { wrote_files })
+}
+
+fn is_copy_up_to_date(from: &Path, to: &Path) -> bool {
+    match (fs::metadata(from), fs::metadata(to)) {
+        (Ok(from), Ok(to)) => match (from.modified(), to.modified()) {
+            (Ok(from_modified), Ok(to_modified)) => {
+                from.len() == to.len() && to_modified >= from_modified
+            }
+            _ => false,
+        },
+        _ => false,
     }
-    Ok(BuildTaskResult::Untracked)
 }
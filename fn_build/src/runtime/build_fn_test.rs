@@ -11,8 +11,8 @@ use crate::{
         build_fn::{build_fn_inner, BuildTask, TransformResult},
         Runtime,
     },
-    BuildMode, FnBuildResult, FnBuildSpec, FnDependencies, FnEntrypoint, FnOutputConfig,
-    FnParseManifest, FnSource,
+    BuildMode, FnBuildManifest, FnBuildResult, FnBuildSpec, FnDependencies, FnEntrypoint,
+    FnOutputConfig, FnParseManifest, FnSource, FnSourceMapConfig, FnSourceMaps,
 };
 
 fn retaining_transform(_p: &Path, _s: String) -> FnBuildResult<TransformResult> {
@@ -28,6 +28,8 @@ async fn test_build_fn_represents_transform_with_retained_path() {
     let temp_dir = TempDir::new().unwrap();
     fs::write(temp_dir.child("source.js"), "human friendly").unwrap();
     let build_spec = FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: PathBuf::new(),
         handler_fn_name: "PATCH".into(),
         mode: BuildMode::Debug,
@@ -37,8 +39,10 @@ async fn test_build_fn_represents_transform_with_retained_path() {
             dirname: "my-sweet-lambda".into(),
             use_build_mode: false,
         },
+        previous_build: None,
         project_dir: Arc::new(temp_dir.path().to_path_buf()),
         runtime: Runtime::Node(None),
+        source_maps: None,
     };
     let parse_manifest = FnParseManifest {
         dependencies: FnDependencies::Unused,
@@ -57,6 +61,7 @@ async fn test_build_fn_represents_transform_with_retained_path() {
         &build_spec,
         parse_manifest,
         build_tasks,
+        "",
         retaining_transform,
     )
     .await
@@ -122,6 +127,8 @@ async fn test_build_fn_represents_transform_with_path_rewrite() {
     let temp_dir = TempDir::new().unwrap();
     fs::write(temp_dir.child("source.ts"), "human friendly").unwrap();
     let build_spec = FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: PathBuf::new(),
         handler_fn_name: "PATCH".into(),
         mode: BuildMode::Debug,
@@ -131,8 +138,10 @@ async fn test_build_fn_represents_transform_with_path_rewrite() {
             dirname: "my-sweet-lambda".into(),
             use_build_mode: false,
         },
+        previous_build: None,
         project_dir: Arc::new(temp_dir.path().to_path_buf()),
         runtime: Runtime::Node(None),
+        source_maps: None,
     };
     let parse_manifest = FnParseManifest {
         dependencies: FnDependencies::Unused,
@@ -151,6 +160,7 @@ async fn test_build_fn_represents_transform_with_path_rewrite() {
         &build_spec,
         parse_manifest,
         build_tasks,
+        "",
         rewriting_transform,
     )
     .await
@@ -211,3 +221,258 @@ async fn test_build_fn_represents_transform_with_path_rewrite() {
         Some(&PathBuf::from("source.js"))
     );
 }
+
+fn panicking_transform(_p: &Path, _s: String) -> FnBuildResult<TransformResult> {
+    panic!("transform of unchanged source");
+}
+
+fn cache_test_build_spec(
+    temp_dir: &TempDir,
+    previous_build: Option<FnBuildManifest>,
+) -> FnBuildSpec {
+    FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: PathBuf::from("source.js"),
+        handler_fn_name: "PATCH".into(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: temp_dir.child("build"),
+            create_archive: false,
+            dirname: "my-sweet-lambda".into(),
+            use_build_mode: false,
+        },
+        previous_build,
+        project_dir: Arc::new(temp_dir.path().to_path_buf()),
+        runtime: Runtime::Node(None),
+        source_maps: None,
+    }
+}
+
+fn cache_test_parse_manifest(paths: &[&str]) -> FnParseManifest {
+    FnParseManifest {
+        dependencies: FnDependencies::Unused,
+        entrypoint: FnEntrypoint {
+            path: PathBuf::from("source.js"),
+            handlers: Vec::new(),
+        },
+        sources: paths
+            .iter()
+            .map(|path| FnSource {
+                path: PathBuf::from(path),
+                imports: Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+fn cache_test_build_tasks(paths: &[&str]) -> Vec<BuildTask> {
+    paths
+        .iter()
+        .map(|path| BuildTask::TransformSourceFile(PathBuf::from(path)))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_build_fn_reuses_outputs_of_unchanged_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("source.js"), "human friendly").unwrap();
+    let paths = ["source.js"];
+    let first_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, None),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        retaining_transform,
+    )
+    .await
+    .unwrap();
+    let second_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, Some(first_build.clone())),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        panicking_transform,
+    )
+    .await
+    .unwrap();
+    assert_eq!(second_build.checksums, first_build.checksums);
+    assert_eq!(second_build.output.paths, first_build.output.paths);
+    assert_eq!(
+        fs::read_to_string(temp_dir.child("build/my-sweet-lambda/source.js")).unwrap(),
+        "compiled"
+    );
+}
+
+#[tokio::test]
+async fn test_build_fn_transforms_changed_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("source.js"), "human friendly").unwrap();
+    fs::write(temp_dir.child("data.js"), "data").unwrap();
+    let paths = ["source.js", "data.js"];
+    let first_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, None),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        retaining_transform,
+    )
+    .await
+    .unwrap();
+    fs::write(temp_dir.child("data.js"), "more data").unwrap();
+    let second_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, Some(first_build.clone())),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        |p: &Path, s: String| {
+            assert!(p.ends_with("data.js"));
+            Ok(TransformResult::RetainPath(s))
+        },
+    )
+    .await
+    .unwrap();
+    assert_ne!(
+        second_build.checksums.get(Path::new("data.js")),
+        first_build.checksums.get(Path::new("data.js"))
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.child("build/my-sweet-lambda/data.js")).unwrap(),
+        "more data"
+    );
+}
+
+#[tokio::test]
+async fn test_build_fn_transforms_all_sources_when_options_change() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("source.js"), "human friendly").unwrap();
+    let paths = ["source.js"];
+    let first_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, None),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        retaining_transform,
+    )
+    .await
+    .unwrap();
+    let second_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, Some(first_build.clone())),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "rewrite_relative_imports=true",
+        rewriting_transform,
+    )
+    .await
+    .unwrap();
+    assert_ne!(second_build.build_options, first_build.build_options);
+    assert_eq!(
+        second_build.output.paths.get(Path::new("source.js")),
+        Some(&PathBuf::from("source.js"))
+    );
+}
+
+#[tokio::test]
+async fn test_build_fn_removes_outputs_of_removed_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("source.js"), "human friendly").unwrap();
+    fs::write(temp_dir.child("data.js"), "data").unwrap();
+    let first_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, None),
+        cache_test_parse_manifest(&["source.js", "data.js"]),
+        cache_test_build_tasks(&["source.js", "data.js"]),
+        "",
+        retaining_transform,
+    )
+    .await
+    .unwrap();
+    assert!(temp_dir.child("build/my-sweet-lambda/data.js").is_file());
+    let second_build = build_fn_inner(
+        &cache_test_build_spec(&temp_dir, Some(first_build)),
+        cache_test_parse_manifest(&["source.js"]),
+        cache_test_build_tasks(&["source.js"]),
+        "",
+        panicking_transform,
+    )
+    .await
+    .unwrap();
+    assert_eq!(second_build.output.paths.len(), 1);
+    assert!(!temp_dir.child("build/my-sweet-lambda/data.js").exists());
+}
+
+fn source_mapping_transform(p: &Path, _s: String) -> FnBuildResult<TransformResult> {
+    let source_map = format!(
+        r#"{{"version":3,"sources":["{}"],"names":[],"mappings":"AAAA"}}"#,
+        p.to_string_lossy()
+    );
+    Ok(TransformResult::RewriteExt("compiled".into(), "js".into())
+        .with_source_map(Some(source_map)))
+}
+
+fn source_map_test_build_spec(temp_dir: &TempDir, source_maps: FnSourceMaps) -> FnBuildSpec {
+    FnBuildSpec {
+        source_maps: Some(FnSourceMapConfig {
+            source_maps,
+            enable_node_source_maps: false,
+        }),
+        ..cache_test_build_spec(temp_dir, None)
+    }
+}
+
+#[tokio::test]
+async fn test_build_fn_writes_external_source_maps() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.child("routes")).unwrap();
+    fs::write(temp_dir.child("routes/source.ts"), "human friendly").unwrap();
+    let paths = ["routes/source.ts"];
+    let build_manifest = build_fn_inner(
+        &source_map_test_build_spec(&temp_dir, FnSourceMaps::External),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        source_mapping_transform,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        build_manifest
+            .output
+            .source_maps
+            .get(Path::new("routes/source.ts")),
+        Some(&PathBuf::from("routes/source.js.map"))
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.child("build/my-sweet-lambda/routes/source.js")).unwrap(),
+        "compiled\n//# sourceMappingURL=source.js.map\n"
+    );
+    let source_map: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp_dir.child("build/my-sweet-lambda/routes/source.js.map")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(source_map["sources"], serde_json::json!(["source.ts"]));
+}
+
+#[tokio::test]
+async fn test_build_fn_writes_inline_source_maps() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("source.ts"), "human friendly").unwrap();
+    let paths = ["source.ts"];
+    let build_manifest = build_fn_inner(
+        &source_map_test_build_spec(&temp_dir, FnSourceMaps::Inline),
+        cache_test_parse_manifest(&paths),
+        cache_test_build_tasks(&paths),
+        "",
+        source_mapping_transform,
+    )
+    .await
+    .unwrap();
+    assert!(build_manifest.output.source_maps.is_empty());
+    assert!(!temp_dir
+        .child("build/my-sweet-lambda/source.js.map")
+        .exists());
+    assert!(
+        fs::read_to_string(temp_dir.child("build/my-sweet-lambda/source.js"))
+            .unwrap()
+            .starts_with("compiled\n//# sourceMappingURL=data:application/json;base64,")
+    );
+}
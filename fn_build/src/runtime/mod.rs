@@ -1,5 +1,7 @@
 use crate::runtime::node::NodeConfig;
-use crate::{FnParseResult, FnSource, ModuleImport};
+use crate::{FnHandler, FnParseResult, FnSource, ModuleImport};
+use l3_ast_parse::swc::visitors::ImportKind;
+use l3_fn_config::PythonVersion;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -16,7 +18,13 @@ mod build_fn_test;
 mod ts_imports_test;
 
 trait ImportResolver: Send + Sync {
-    fn resolve(&self, project_dir: &Path, from: &Path, import: &str) -> ModuleImport;
+    fn resolve(
+        &self,
+        project_dir: &Path,
+        from: &Path,
+        import: &str,
+        kind: ImportKind,
+    ) -> ModuleImport;
 }
 
 trait FnSourceParser: Send + Sync {
@@ -25,16 +33,18 @@ trait FnSourceParser: Send + Sync {
         &self,
         project_dir: &Path,
         source_path: &Path,
-    ) -> FnParseResult<Vec<String>>;
+    ) -> FnParseResult<Vec<FnHandler>>;
     /// Provide sources for deployment to a serverless runtime that are not explicit imports of the
     /// function's source tree. For Node.js this would include package.json.
     fn collect_runtime_sources(&self, project_dir: &Path) -> Vec<FnSource>;
+    /// Version requirement of a package dependency declared by the project's package manifest.
+    fn package_version(&self, package: &str) -> Option<String>;
     /// Parse a function entrypoint for exports that could be deployed as lambda functions.
     fn parse_fn_entrypoint(
         &self,
         project_dir: &Path,
         source_path: PathBuf,
-    ) -> FnParseResult<(FnSource, Vec<String>)>;
+    ) -> FnParseResult<(FnSource, Vec<FnHandler>)>;
     /// Parse a source file to find imported sources for a function's source tree.
     fn parse_for_imports(
         &self,
@@ -48,5 +58,7 @@ pub enum Runtime {
     /// A Node.js build. `package.json` will be parsed from the project directory unless
     /// NodeConfig is explicitly provided.
     Node(Option<Arc<NodeConfig>>),
-    Python,
+    /// A Python build targeting the configured Python version. Standard library imports are
+    /// resolved for the latest supported Python version unless a version is provided.
+    Python(Option<PythonVersion>),
 }
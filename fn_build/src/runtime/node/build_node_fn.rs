@@ -1,28 +1,54 @@
+use crate::paths::relative_path;
 use crate::runtime::build_fn::{build_fn_inner, BuildTask, TransformResult};
-use crate::runtime::node::parse_node_fn;
-use crate::runtime::Runtime;
-use crate::swc::compiler::{CompileError, SwcCompiler};
+use crate::runtime::node::bundle::NodeBundleLoader;
+use crate::runtime::node::dependencies::{resolve_package_dirs, PackageDirs};
+use crate::runtime::node::{parse_node_fn, NodeConfig};
+use crate::runtime::ts_imports::resolve_ts_path_alias;
+use crate::swc::compiler::{CompileError, SwcCompiler, TranspileOptions};
+use crate::typescript::TsConfigJson;
 use crate::{
-    BuildMode, FnBuildError, FnBuildManifest, FnBuildResult, FnBuildSpec, FnDependencies,
-    FnParseError,
+    BuildMode, FnBuildError, FnBuildManifest, FnBuildResult, FnBuildSpec, FnBundleConfig,
+    FnDependencies, FnParseError, FnParseManifest, FnSourceMaps,
 };
+use l3_ast_parse::swc::visitors::{ImportKind, RewriteSpecifier, TsImportRewrites};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub async fn build_node_fn(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildManifest> {
+    let enable_node_source_maps = build_spec
+        .source_maps
+        .as_ref()
+        .is_some_and(|config| config.enable_node_source_maps);
+    let mut build_manifest = build_node_fn_outputs(build_spec).await?;
+    if enable_node_source_maps {
+        build_manifest
+            .env_vars
+            .insert("NODE_OPTIONS".into(), "--enable-source-maps".into());
+    }
+    Ok(build_manifest)
+}
+
+async fn build_node_fn_outputs(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildManifest> {
     let parse_manifest = parse_node_fn(build_spec.to_parse_spec()).await?;
+    parse_manifest
+        .entrypoint
+        .require_handler(&build_spec.handler_fn_name)?;
+    if let Some(bundle_config) = &build_spec.bundle {
+        return bundle_node_fn(&build_spec, bundle_config, parse_manifest).await;
+    }
+    let node_config = read_node_config(&build_spec)?;
     let mut build_tasks = Vec::new();
-    if let FnDependencies::Required = parse_manifest.dependencies {
-        build_tasks.push(BuildTask::CopyDirectoryRecursively(PathBuf::from(
-            "node_modules",
-        )));
+    let package_dirs =
+        resolve_node_package_dirs(&build_spec, &node_config, &parse_manifest, false)?;
+    for package_dir in &package_dirs.copied {
+        build_tasks.push(BuildTask::CopyDirectoryRecursively(package_dir.clone()));
     }
-    let mut ts = false;
     let mut copy_sources: Vec<PathBuf> = Vec::new();
     match build_spec.mode {
         BuildMode::Debug => {
             for p in parse_manifest.source_paths() {
                 if is_ts(&p) {
-                    ts = true;
                     build_tasks.push(BuildTask::TransformSourceFile(p));
                 } else {
                     copy_sources.push(p)
@@ -31,8 +57,7 @@ pub async fn build_node_fn(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildMani
         }
         BuildMode::Release => {
             for p in parse_manifest.source_paths() {
-                if is_ts_or_es(&p) {
-                    ts = true;
+                if is_ts_or_js(&p) {
                     build_tasks.push(BuildTask::TransformSourceFile(p));
                 } else {
                     copy_sources.push(p)
@@ -40,99 +65,253 @@ pub async fn build_node_fn(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildMani
             }
         }
     }
-    let rewrite_ts_imports = match &build_spec.runtime {
-        Runtime::Node(Some(node_config)) => {
-            if let Some(tsconfig) = &node_config.ts {
-                tsconfig.compiler.rewrite_relative_imports
-            } else {
-                false
-            }
-        }
-        _ => false,
-    };
+    let tsconfig = node_config.ts.clone();
+    let transpile_options = TranspileOptions::new(
+        tsconfig.as_ref().map(|tsconfig| &tsconfig.compiler),
+        node_config.version.as_ref(),
+    )
+    .with_source_map(build_spec.source_maps.is_some());
+    let rewrite_ts_imports = tsconfig
+        .as_ref()
+        .is_some_and(|tsconfig| tsconfig.compiler.rewrite_relative_imports);
+    let path_aliases = tsconfig.filter(|tsconfig| {
+        tsconfig.compiler.base_url.is_some() || !tsconfig.compiler.paths.is_empty()
+    });
     build_tasks.push(BuildTask::CopySourceFiles(copy_sources));
+    // aliases are included so that outputs of sources importing an alias rebuild on a change,
+    // conditions are included since they resolve imports to different sources and packages are
+    // included so that a previous build's unused packages are not reused
+    let transform_options = format!(
+        "rewrite_relative_imports={rewrite_ts_imports}\ntranspile={transpile_options:?}\nsource_maps={:?}\npath_aliases={}\nconditions={}\npackages={}",
+        source_maps(&build_spec),
+        path_aliases_key(path_aliases.as_deref()),
+        build_spec.conditions.join(","),
+        join_paths(&package_dirs.copied)
+    );
+    let project_dir = build_spec.project_dir.clone();
+    let mode = build_spec.mode.clone();
+    let source_map = build_spec.source_maps.is_some();
     build_fn_inner(
         &build_spec,
         parse_manifest,
         build_tasks,
-        match (ts, rewrite_ts_imports, &build_spec.mode) {
-            (true, false, BuildMode::Debug) => swc_transpile,
-            (true, true, BuildMode::Debug) => swc_transpile_and_rewrite_imports,
-            (true, false, BuildMode::Release) => swc_transpile_and_minify,
-            (true, true, BuildMode::Release) => swc_transpile_and_minify_and_rewrite_imports,
-            (false, _, BuildMode::Release) => swc_minify,
-            _ => noop,
+        &transform_options,
+        move |p, s| {
+            // only release builds transform JS sources
+            if !is_ts(p) {
+                return swc_minify(p, s, source_map);
+            }
+            let rewrites = TsImportRewrites {
+                ts_extensions: rewrite_ts_imports,
+                specifiers: path_aliases
+                    .clone()
+                    .map(|tsconfig| rewrite_path_aliases(project_dir.clone(), tsconfig, p)),
+            };
+            let compiler = SwcCompiler::new();
+            let js = match mode {
+                BuildMode::Debug => {
+                    compiler.transpile_ts(p.to_path_buf(), s, &transpile_options, rewrites)?
+                }
+                BuildMode::Release => compiler.transpile_and_minify_ts(
+                    p.to_path_buf(),
+                    s,
+                    &transpile_options,
+                    rewrites,
+                )?,
+            };
+            Ok(TransformResult::RewriteExt(js.code, "js".into()).with_source_map(js.source_map))
         },
     )
     .await
 }
 
-fn noop(_p: &Path, _s: String) -> FnBuildResult<TransformResult> {
-    panic!();
+fn read_node_config(build_spec: &FnBuildSpec) -> FnBuildResult<Arc<NodeConfig>> {
+    Ok(super::parse_node_fn::read_node_config(
+        &build_spec.runtime,
+        &build_spec.project_dir,
+        &build_spec.conditions,
+    )
+    .map_err(FnParseError::from)?)
+}
+
+/// Rewrites imports of tsconfig.json path aliases by a TS source to relative specifiers of the
+/// aliased sources' build outputs.
+fn rewrite_path_aliases(
+    project_dir: Arc<PathBuf>,
+    tsconfig: Arc<TsConfigJson>,
+    source_path: &Path,
+) -> RewriteSpecifier {
+    let from_dir = source_path
+        .strip_prefix(project_dir.as_path())
+        .unwrap_or(source_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    Arc::new(move |specifier| {
+        if specifier.starts_with('.') || specifier.starts_with('#') {
+            return None;
+        }
+        let resolved = resolve_ts_path_alias(&tsconfig.compiler, &project_dir, specifier)?;
+        let output = if is_ts(&resolved) {
+            resolved.with_extension("js")
+        } else {
+            resolved
+        };
+        let relative = relative_path(&from_dir, &output)
+            .to_string_lossy()
+            .replace('\\', "/");
+        Some(if relative.starts_with("..") {
+            relative
+        } else {
+            format!("./{relative}")
+        })
+    })
+}
+
+/// Builds a single ES module of the function's JS and TS sources with its other sources, such as
+/// package.json, and npm packages that are not inlined copied to the build.
+async fn bundle_node_fn(
+    build_spec: &FnBuildSpec,
+    bundle_config: &FnBundleConfig,
+    parse_manifest: FnParseManifest,
+) -> FnBuildResult<FnBuildManifest> {
+    let node_config = read_node_config(build_spec)?;
+    let mut build_tasks = Vec::new();
+    let package_dirs = resolve_node_package_dirs(
+        build_spec,
+        &node_config,
+        &parse_manifest,
+        bundle_config.inline_packages,
+    )?;
+    for package_dir in &package_dirs.copied {
+        build_tasks.push(BuildTask::CopyDirectoryRecursively(package_dir.clone()));
+    }
+    let (bundle_sources, copy_sources): (Vec<PathBuf>, Vec<PathBuf>) = parse_manifest
+        .source_paths()
+        .into_iter()
+        .partition(|p| is_ts_or_js(p));
+    let loader = NodeBundleLoader::new(
+        build_spec.project_dir.clone(),
+        &node_config,
+        bundle_config,
+        &bundle_sources,
+    );
+    build_tasks.push(BuildTask::TransformSourceTree {
+        entrypoint: parse_manifest.entrypoint.path.clone(),
+        sources: bundle_sources,
+    });
+    build_tasks.push(BuildTask::CopySourceFiles(copy_sources));
+    let mode = build_spec.mode.clone();
+    let source_map = build_spec.source_maps.is_some();
+    build_fn_inner(
+        build_spec,
+        parse_manifest,
+        build_tasks,
+        // tsconfig.json is not a source of the bundle, so its transpile options and aliases are
+        // included for a change to rebuild the bundle, as are versions of inlined packages
+        &format!(
+            "bundle=true\ninline_packages={}\ntranspile={:?}\nsource_maps={:?}\npath_aliases={}\nconditions={}\npackages={}\ninlined={}",
+            bundle_config.inline_packages,
+            loader.transpile_options(),
+            source_maps(build_spec),
+            path_aliases_key(node_config.ts.as_deref()),
+            build_spec.conditions.join(","),
+            join_paths(&package_dirs.copied),
+            package_dirs
+                .inlined
+                .iter()
+                .map(|p| format!(
+                    "{}@{}",
+                    p.to_string_lossy(),
+                    read_package_version(&build_spec.project_dir.join(p)).unwrap_or_default()
+                ))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        move |p, _| loader.bundle(p, &mode, source_map),
+    )
+    .await
+}
+
+fn resolve_node_package_dirs(
+    build_spec: &FnBuildSpec,
+    node_config: &NodeConfig,
+    parse_manifest: &FnParseManifest,
+    inline_packages: bool,
+) -> FnBuildResult<PackageDirs> {
+    match parse_manifest.dependencies {
+        FnDependencies::Packages(_) => Ok(resolve_package_dirs(
+            &build_spec.project_dir,
+            &parse_manifest.sources,
+            inline_packages,
+            &|condition| node_config.matches_condition(condition, ImportKind::Static),
+        )
+        .map_err(FnParseError::from)?),
+        FnDependencies::Unused => Ok(PackageDirs::default()),
+    }
 }
 
-fn swc_minify(p: &Path, s: String) -> FnBuildResult<TransformResult> {
-    Ok(TransformResult::RetainPath(
-        SwcCompiler::new().minify_js(p.to_path_buf(), s)?,
-    ))
+/// Formats the `baseUrl` and `paths` of a tsconfig.json for a build's cache key.
+fn path_aliases_key(tsconfig: Option<&TsConfigJson>) -> String {
+    tsconfig
+        .filter(|tsconfig| {
+            tsconfig.compiler.base_url.is_some() || !tsconfig.compiler.paths.is_empty()
+        })
+        .map(|tsconfig| {
+            format!(
+                "{:?}{:?}",
+                tsconfig.compiler.base_url, tsconfig.compiler.paths
+            )
+        })
+        .unwrap_or_default()
 }
 
-// if not minifying, js/mjs should be copied as is so this transform does not check for ts or es like swc_transpile_and_minify
-fn swc_transpile(p: &Path, s: String) -> FnBuildResult<TransformResult> {
-    Ok(TransformResult::RewriteExt(
-        SwcCompiler::new().transpile_ts(p.to_path_buf(), s, false)?,
-        "js".into(),
-    ))
+fn read_package_version(package_dir: &Path) -> Option<String> {
+    let package_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(package_dir.join("package.json")).ok()?).ok()?;
+    package_json
+        .get("version")
+        .and_then(|version| version.as_str())
+        .map(String::from)
 }
 
-// if not minifying, js/mjs should be copied as is so this transform does not check for ts or es like swc_transpile_and_minify
-fn swc_transpile_and_rewrite_imports(p: &Path, s: String) -> FnBuildResult<TransformResult> {
-    Ok(TransformResult::RewriteExt(
-        SwcCompiler::new().transpile_ts(p.to_path_buf(), s, true)?,
-        "js".into(),
-    ))
+fn join_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
-fn swc_transpile_and_minify(p: &Path, s: String) -> FnBuildResult<TransformResult> {
-    if is_ts(p) {
-        Ok(TransformResult::RewriteExt(
-            SwcCompiler::new().transpile_and_minify_ts(p.to_path_buf(), s, false)?,
-            "js".into(),
-        ))
-    } else {
-        Ok(TransformResult::RetainPath(
-            SwcCompiler::new().minify_js(p.to_path_buf(), s)?,
-        ))
-    }
+fn swc_minify(p: &Path, s: String, source_map: bool) -> FnBuildResult<TransformResult> {
+    let js = SwcCompiler::new().minify_js(p.to_path_buf(), s, source_map, None)?;
+    Ok(TransformResult::RetainPath(js.code).with_source_map(js.source_map))
 }
 
-fn swc_transpile_and_minify_and_rewrite_imports(
-    p: &Path,
-    s: String,
-) -> FnBuildResult<TransformResult> {
-    if is_ts(p) {
-        let compiler = SwcCompiler::new();
-        let compiled = compiler.transpile_and_minify_ts(p.to_path_buf(), s, true)?;
-        Ok(TransformResult::RewriteExt(compiled, "js".into()))
-    } else {
-        Ok(TransformResult::RetainPath(
-            SwcCompiler::new().minify_js(p.to_path_buf(), s)?,
-        ))
-    }
+fn source_maps(build_spec: &FnBuildSpec) -> Option<&FnSourceMaps> {
+    build_spec
+        .source_maps
+        .as_ref()
+        .map(|config| &config.source_maps)
 }
 
-fn is_ts_or_es(p: &Path) -> bool {
+pub(super) fn is_ts_or_js(p: &Path) -> bool {
     if let Some(extension) = p.extension() {
-        if extension == "ts" || extension == "js" || extension == "mjs" {
+        if extension == "ts"
+            || extension == "tsx"
+            || extension == "js"
+            || extension == "mjs"
+            || extension == "cjs"
+        {
             return true;
         }
     }
     false
 }
 
-fn is_ts(p: &Path) -> bool {
+pub(super) fn is_ts(p: &Path) -> bool {
     if let Some(extension) = p.extension() {
-        if extension == "ts" {
+        if extension == "ts" || extension == "tsx" {
             return true;
         }
     }
@@ -0,0 +1,182 @@
+use crate::paths::join_file_paths;
+use crate::runtime::build_fn::TransformResult;
+use crate::runtime::node::build_node_fn::{is_ts, is_ts_or_js};
+use crate::runtime::node::dependencies::{
+    resolve_es_module_entry, resolve_package_dir, split_package_specifier,
+};
+use crate::runtime::node::parse_node_fn::{create_import_resolver, source_type};
+use crate::runtime::node::NodeConfig;
+use crate::runtime::ImportResolver;
+use crate::swc::bundler::{BundleImport, BundleLoader};
+use crate::swc::compiler::{CompileError, CompileResult, SwcCompiler, TranspileOptions};
+use crate::{BuildMode, FnBuildError, FnBuildResult, FnBundleConfig, ModuleImport};
+use l3_ast_parse::swc::visitors::{ImportKind, TsImportRewrites};
+use l3_ast_parse::swc::SourceType;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Loads a Node.js function's sources for bundling with the same import resolution used to
+/// parse its source tree, optionally inlining ES module npm packages.
+///
+/// The parse manifest's `FnSource` imports record the module an import resolved to but not its
+/// specifier, which the bundler must map to the module's bindings, so specifiers are resolved
+/// again and resolve to a module of the bundle only if it is one of the parsed sources. A relative
+/// import of a JS or TS source that resolves outside the parsed sources fails the bundle instead
+/// of being kept as an import the bundle's output would not resolve.
+pub struct NodeBundleLoader {
+    import_resolver: Box<dyn ImportResolver>,
+    inline_packages: bool,
+    node_config: Arc<NodeConfig>,
+    project_dir: Arc<PathBuf>,
+    /// JS and TS sources of the function's parse manifest that are linked into the bundle.
+    sources: HashSet<PathBuf>,
+    transpile_options: TranspileOptions,
+}
+
+impl NodeBundleLoader {
+    pub fn new(
+        project_dir: Arc<PathBuf>,
+        node_config: &Arc<NodeConfig>,
+        bundle_config: &FnBundleConfig,
+        sources: &[PathBuf],
+    ) -> Self {
+        Self {
+            import_resolver: create_import_resolver(node_config),
+            inline_packages: bundle_config.inline_packages,
+            node_config: node_config.clone(),
+            project_dir,
+            sources: sources.iter().cloned().collect(),
+            transpile_options: TranspileOptions::new(
+                node_config.ts.as_ref().map(|tsconfig| &tsconfig.compiler),
+                node_config.version.as_ref(),
+            ),
+        }
+    }
+
+    pub fn transpile_options(&self) -> &TranspileOptions {
+        &self.transpile_options
+    }
+
+    /// Bundles the function's source tree from its entrypoint into an `.mjs` ES module.
+    pub fn bundle(
+        &self,
+        entrypoint: &Path,
+        mode: &BuildMode,
+        source_map: bool,
+    ) -> FnBuildResult<TransformResult> {
+        let entrypoint = entrypoint
+            .strip_prefix(self.project_dir.as_path())
+            .unwrap_or(entrypoint);
+        let js = SwcCompiler::new()
+            .bundle(entrypoint, self, source_map)
+            .map_err(map_bundle_error)?;
+        let js = match mode {
+            BuildMode::Debug => js,
+            BuildMode::Release => SwcCompiler::new()
+                .minify_js(
+                    entrypoint.with_extension("mjs"),
+                    js.code,
+                    source_map,
+                    js.source_map,
+                )
+                .map_err(map_bundle_error)?,
+        };
+        Ok(TransformResult::RewriteExt(js.code, "mjs".into()).with_source_map(js.source_map))
+    }
+
+    fn resolve_package(&self, from: &Path, specifier: &str) -> BundleImport {
+        if !self.inline_packages {
+            return BundleImport::External;
+        }
+        let (package, subpath) = split_package_specifier(specifier);
+        // an external package installed within another package's node_modules will not resolve
+        // from the bundle's path since packages are copied with their project relative path
+        let from_dir = from.parent().unwrap_or(Path::new(""));
+        let Some(package_dir) = resolve_package_dir(&self.project_dir, from_dir, package) else {
+            return BundleImport::External;
+        };
+        let abs_package_dir = self.project_dir.join(&package_dir);
+        // packages are inlined by the same criteria resolve_package_dirs uses to not copy them
+        let matches_condition = |condition: &str| {
+            self.node_config
+                .matches_condition(condition, ImportKind::Static)
+        };
+        if resolve_es_module_entry(&abs_package_dir, None, &matches_condition).is_none() {
+            return BundleImport::External;
+        }
+        match resolve_es_module_entry(&abs_package_dir, subpath, &matches_condition) {
+            Some(entry) => BundleImport::Module(package_dir.join(entry)),
+            None => BundleImport::External,
+        }
+    }
+}
+
+impl BundleLoader for NodeBundleLoader {
+    fn load(&self, path: &Path) -> CompileResult<String> {
+        let abs_path = self.project_dir.join(path);
+        let source = fs::read_to_string(&abs_path)?;
+        if is_ts(path) {
+            SwcCompiler::new()
+                .transpile_ts(
+                    abs_path,
+                    source,
+                    &self.transpile_options,
+                    TsImportRewrites::default(),
+                )
+                .map(|js| js.code)
+        } else {
+            Ok(source)
+        }
+    }
+
+    fn resolve(&self, from: &Path, specifier: &str) -> BundleImport {
+        if is_package_source(from) {
+            if specifier.starts_with('.') {
+                let path = join_file_paths(from, Path::new(specifier));
+                if self.project_dir.join(&path).is_file() {
+                    return BundleImport::Module(path);
+                }
+                return BundleImport::External;
+            }
+            return self.resolve_package(from, specifier);
+        }
+        if !specifier.starts_with('.') && !specifier.starts_with('#') {
+            return self.resolve_package(from, specifier);
+        }
+        match self
+            .import_resolver
+            .resolve(&self.project_dir, from, specifier, ImportKind::Static)
+        {
+            ModuleImport::RelativeSource(path) if self.sources.contains(&path) => {
+                BundleImport::Module(path)
+            }
+            // sources such as .json are copied to the build rather than bundled
+            ModuleImport::RelativeSource(path) if !is_ts_or_js(&path) => BundleImport::External,
+            ModuleImport::RelativeSource(_) | ModuleImport::Unknown(_) => BundleImport::Unresolved,
+            ModuleImport::PackageDependency { .. } => BundleImport::External,
+        }
+    }
+
+    fn source_type(&self, path: &Path) -> SourceType {
+        // packages are only inlined by the entry of their ES module build
+        if is_package_source(path) || is_ts(path) {
+            SourceType::EsModule
+        } else {
+            source_type(&self.node_config, path)
+        }
+    }
+}
+
+fn is_package_source(path: &Path) -> bool {
+    path.components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+}
+
+fn map_bundle_error(err: CompileError) -> FnBuildError {
+    match err {
+        CompileError::OperationError(err) => FnBuildError::BundleError(err),
+        _ => FnBuildError::from(err),
+    }
+}
@@ -0,0 +1,292 @@
+use crate::runtime::node::imports::{read_package_target, NodePackageTargetResolution};
+use crate::runtime::node::NodeConfigError;
+use crate::{FnSource, ModuleImport};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fields of a package.json with dependencies installed for a package's runtime use.
+const PRODUCTION_DEPENDENCY_FIELDS: [&str; 3] =
+    ["dependencies", "optionalDependencies", "peerDependencies"];
+
+/// Project relative dirs of npm packages resolved for a function's build.
+#[derive(Debug, Default, PartialEq)]
+pub struct PackageDirs {
+    /// Packages copied to the build's node_modules.
+    pub copied: Vec<PathBuf>,
+    /// ES module packages inlined into a bundle that are not copied to the build.
+    pub inlined: Vec<PathBuf>,
+}
+
+/// Resolves project relative dirs of the npm packages imported by a function's sources and the
+/// packages those depend on for production, such as `node_modules/data-lib`.
+///
+/// With `inline_packages`, ES module packages resolved with the conditions matched by
+/// `matches_condition` are inlined and only their dependencies are resolved for copying. Every
+/// dependency of a copied package is also copied.
+pub fn resolve_package_dirs(
+    project_dir: &Path,
+    sources: &[FnSource],
+    inline_packages: bool,
+    matches_condition: &impl Fn(&str) -> bool,
+) -> Result<PackageDirs, NodeConfigError> {
+    let mut resolving: VecDeque<(PathBuf, String, bool)> = VecDeque::new();
+    for source in sources {
+        let from_dir = source.path.parent().unwrap_or(Path::new("")).to_path_buf();
+        for import in &source.imports {
+            if let ModuleImport::PackageDependency { package, .. } = import {
+                resolving.push_back((from_dir.clone(), package.clone(), false));
+            }
+        }
+    }
+    let mut copied: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut inlined: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut resolved: HashSet<(PathBuf, String, bool)> = HashSet::new();
+    while let Some((from_dir, package, copied_by_dependent)) = resolving.pop_front() {
+        if !resolved.insert((from_dir.clone(), package.clone(), copied_by_dependent)) {
+            continue;
+        }
+        // optional and peer dependencies may not be installed
+        let Some(package_dir) = resolve_package_dir(project_dir, &from_dir, &package) else {
+            continue;
+        };
+        let copy = copied_by_dependent
+            || !inline_packages
+            || resolve_es_module_entry(&project_dir.join(&package_dir), None, matches_condition)
+                .is_none();
+        let inserted = if copy {
+            copied.insert(package_dir.clone())
+        } else {
+            inlined.insert(package_dir.clone())
+        };
+        if !inserted {
+            continue;
+        }
+        for dependency in read_production_dependencies(&project_dir.join(&package_dir))? {
+            resolving.push_back((package_dir.clone(), dependency, copy));
+        }
+    }
+    // packages installed within another package's node_modules are copied with that package
+    Ok(PackageDirs {
+        copied: copied
+            .iter()
+            .filter(|package_dir| {
+                !copied
+                    .iter()
+                    .any(|other| *package_dir != other && package_dir.starts_with(other))
+            })
+            .cloned()
+            .collect(),
+        inlined: inlined.into_iter().collect(),
+    })
+}
+
+/// Looks up a package in node_modules dirs from `from_dir` to the project root the same way
+/// Node.js resolves a bare specifier.
+pub fn resolve_package_dir(project_dir: &Path, from_dir: &Path, package: &str) -> Option<PathBuf> {
+    let mut dir = Some(from_dir);
+    while let Some(d) = dir {
+        if !d.ends_with("node_modules") {
+            let package_dir = d.join("node_modules").join(package);
+            if project_dir
+                .join(&package_dir)
+                .join("package.json")
+                .is_file()
+            {
+                return Some(package_dir);
+            }
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+fn read_production_dependencies(package_dir: &Path) -> Result<Vec<String>, NodeConfigError> {
+    let package_json: Value =
+        serde_json::from_str(&fs::read_to_string(package_dir.join("package.json"))?)?;
+    let mut dependencies = Vec::new();
+    for field in PRODUCTION_DEPENDENCY_FIELDS {
+        if let Some(field_dependencies) = package_json.get(field).and_then(|v| v.as_object()) {
+            dependencies.extend(field_dependencies.keys().cloned());
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Splits a bare import specifier into its package name and subpath, such as
+/// `@scope/data-lib` and `utils` for `@scope/data-lib/utils`.
+pub fn split_package_specifier(specifier: &str) -> (&str, Option<&str>) {
+    let package_len = match specifier.strip_prefix('@') {
+        Some(scoped) => scoped.find('/').and_then(|scope_len| {
+            scoped[scope_len + 1..]
+                .find('/')
+                .map(|name_len| scope_len + name_len + 2)
+        }),
+        None => specifier.find('/'),
+    };
+    match package_len {
+        Some(package_len) => (
+            &specifier[..package_len],
+            Some(&specifier[package_len + 1..]),
+        ),
+        None => (specifier, None),
+    }
+}
+
+/// Resolves the module of a package's entrypoint or subpath relative to the package dir by
+/// package.json's "exports" with the conditions matched by `matches_condition` or, for a
+/// package without "exports", its "main" or "module". Resolves None if the package does not
+/// export the subpath or its module does not exist.
+pub fn resolve_package_entry(
+    package_dir: &Path,
+    subpath: Option<&str>,
+    matches_condition: &impl Fn(&str) -> bool,
+) -> Option<PathBuf> {
+    let package_json = read_package_json_value(package_dir)?;
+    match package_json.get("exports") {
+        Some(exports) => {
+            let (target, substitution) = match_exports_subpath(exports, subpath)?;
+            let resolution =
+                read_package_target(target)
+                    .ok()?
+                    .resolve(matches_condition, &|specifier| {
+                        // exports targets must be relative to the package dir
+                        let entry = substitute_export_pattern(specifier, substitution.as_deref());
+                        let entry = PathBuf::from(entry.strip_prefix("./")?);
+                        package_dir.join(&entry).is_file().then_some(entry)
+                    });
+            match resolution {
+                NodePackageTargetResolution::Resolved(entry) => Some(entry),
+                _ => None,
+            }
+        }
+        None => {
+            let entry = match subpath {
+                Some(subpath) => subpath,
+                None => ["main", "module"]
+                    .iter()
+                    .find_map(|field| package_json.get(*field).and_then(Value::as_str))
+                    .unwrap_or("index.js"),
+            };
+            resolve_legacy_entry(package_dir, entry)
+        }
+    }
+}
+
+/// Resolves a subpath or main of a package without exports the way Node.js does, which appends
+/// the `.js` extension instead of replacing the extension of an entry like `lib/index.min`.
+fn resolve_legacy_entry(package_dir: &Path, entry: &str) -> Option<PathBuf> {
+    let entry = PathBuf::from(entry.trim_start_matches("./"));
+    [
+        entry.clone(),
+        PathBuf::from(format!("{}.js", entry.display())),
+        entry.join("index.js"),
+    ]
+    .into_iter()
+    .find(|entry| package_dir.join(entry).is_file())
+}
+
+/// Resolves the ES module of a package's entrypoint or subpath relative to the package dir.
+/// Package.json "exports" are resolved the same way as resolve_package_entry with the conditions
+/// matched by `matches_condition` and the `module` condition of bundlers. Resolves None if the
+/// package distributes the module as CommonJS.
+pub fn resolve_es_module_entry(
+    package_dir: &Path,
+    subpath: Option<&str>,
+    matches_condition: &impl Fn(&str) -> bool,
+) -> Option<PathBuf> {
+    let package_json = read_package_json_value(package_dir)?;
+    let type_module = package_json.get("type").and_then(Value::as_str) == Some("module");
+    let (entry, es_condition) = match package_json.get("exports") {
+        Some(_) => {
+            let entry = resolve_package_entry(package_dir, subpath, &|condition| {
+                condition == "module" || matches_condition(condition)
+            })?;
+            // an entry that require() does not resolve is exported by an ES module condition
+            let required = resolve_package_entry(package_dir, subpath, &|condition| {
+                condition == "require"
+                    || (condition != "import"
+                        && condition != "module"
+                        && matches_condition(condition))
+            });
+            let es_condition = required.as_ref() != Some(&entry);
+            (entry, es_condition)
+        }
+        None => {
+            let (entry, es_condition) = match subpath {
+                Some(subpath) => (subpath.to_string(), false),
+                None => match package_json.get("module").and_then(Value::as_str) {
+                    Some(module) => (module.to_string(), true),
+                    None => (
+                        package_json
+                            .get("main")
+                            .and_then(Value::as_str)
+                            .unwrap_or("index.js")
+                            .to_string(),
+                        false,
+                    ),
+                },
+            };
+            (resolve_legacy_entry(package_dir, &entry)?, es_condition)
+        }
+    };
+    let es_module = match entry.extension().and_then(|ext| ext.to_str()) {
+        Some("mjs") => true,
+        Some("js") => type_module || es_condition,
+        _ => false,
+    };
+    if es_module && package_dir.join(&entry).is_file() {
+        Some(entry)
+    } else {
+        None
+    }
+}
+
+fn read_package_json_value(package_dir: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(package_dir.join("package.json")).ok()?).ok()
+}
+
+/// Matches a package's entrypoint or subpath to its target in package.json "exports" with the
+/// substitution for the `*` of a subpath pattern, such as `utils` of `./utils.js` matching
+/// the `./*.js` pattern.
+fn match_exports_subpath<'a>(
+    exports: &'a Value,
+    subpath: Option<&str>,
+) -> Option<(&'a Value, Option<String>)> {
+    let subpath_key = match subpath {
+        Some(subpath) => format!("./{subpath}"),
+        None => ".".to_string(),
+    };
+    match exports {
+        Value::Object(map) if map.keys().any(|key| key.starts_with('.')) => {
+            if let Some(target) = map.get(&subpath_key) {
+                return Some((target, None));
+            }
+            // the pattern with the longest prefix before its `*` takes precedence
+            let (key, target) = map
+                .iter()
+                .filter(|(key, _)| {
+                    key.split_once('*').is_some_and(|(before, after)| {
+                        subpath_key.len() >= before.len() + after.len()
+                            && subpath_key.starts_with(before)
+                            && subpath_key.ends_with(after)
+                    })
+                })
+                .max_by_key(|(key, _)| (key.find('*'), key.len()))?;
+            let (before, after) = key.split_once('*')?;
+            let substitution = &subpath_key[before.len()..subpath_key.len() - after.len()];
+            Some((target, Some(substitution.to_string())))
+        }
+        _ if subpath.is_none() => Some((exports, None)),
+        _ => None,
+    }
+}
+
+/// Replaces every `*` of a subpath pattern's target with the matched substitution.
+fn substitute_export_pattern(target: &str, substitution: Option<&str>) -> String {
+    match substitution {
+        Some(substitution) => target.replace('*', substitution),
+        None => target.to_string(),
+    }
+}
@@ -0,0 +1,310 @@
+use crate::runtime::node::dependencies::{
+    resolve_es_module_entry, resolve_package_dirs, resolve_package_entry, split_package_specifier,
+    PackageDirs,
+};
+use crate::runtime::node::imports::NODE_IMPORT_CONDITIONS;
+use crate::{FnSource, ModuleImport};
+use std::fs;
+use std::path::{Path, PathBuf};
+use temp_dir::TempDir;
+
+fn matches_node_condition(condition: &str) -> bool {
+    NODE_IMPORT_CONDITIONS.contains(&condition)
+}
+
+fn write_package(project_dir: &Path, package_dir: &str, package_json: &str) {
+    let package_dir = project_dir.join(package_dir);
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(package_dir.join("package.json"), package_json).unwrap();
+    fs::write(package_dir.join("index.js"), "export default {}").unwrap();
+}
+
+fn importing_source(packages: &[&str]) -> FnSource {
+    FnSource {
+        imports: packages
+            .iter()
+            .map(|package| ModuleImport::PackageDependency {
+                package: package.to_string(),
+                subpath: None,
+                path: None,
+            })
+            .collect(),
+        path: PathBuf::from("routes/data/lambda.js"),
+    }
+}
+
+#[test]
+fn test_resolve_package_dirs_includes_production_dependency_closure() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"dependencies":{"dep-a":"1.0.0"},"devDependencies":{"dev-lib":"1.0.0"}}"#,
+    );
+    write_package(
+        temp_dir.path(),
+        "node_modules/dep-a",
+        r#"{"dependencies":{"dep-b":"1.0.0"},"optionalDependencies":{"not-installed":"1.0.0"}}"#,
+    );
+    write_package(temp_dir.path(), "node_modules/dep-b", r#"{}"#);
+    write_package(temp_dir.path(), "node_modules/dev-lib", r#"{}"#);
+    write_package(temp_dir.path(), "node_modules/unused-lib", r#"{}"#);
+    let package_dirs = resolve_package_dirs(
+        temp_dir.path(),
+        &[importing_source(&["data-lib"])],
+        false,
+        &matches_node_condition,
+    )
+    .unwrap()
+    .copied;
+    assert_eq!(
+        package_dirs,
+        vec![
+            PathBuf::from("node_modules/data-lib"),
+            PathBuf::from("node_modules/dep-a"),
+            PathBuf::from("node_modules/dep-b"),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_package_dirs_prefers_nested_node_modules() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"dependencies":{"dep-a":"2.0.0"}}"#,
+    );
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib/node_modules/dep-a",
+        r#"{"dependencies":{"dep-b":"1.0.0"}}"#,
+    );
+    write_package(temp_dir.path(), "node_modules/dep-a", r#"{}"#);
+    write_package(temp_dir.path(), "node_modules/dep-b", r#"{}"#);
+    let package_dirs = resolve_package_dirs(
+        temp_dir.path(),
+        &[importing_source(&["data-lib"])],
+        false,
+        &matches_node_condition,
+    )
+    .unwrap()
+    .copied;
+    assert_eq!(
+        package_dirs,
+        vec![
+            PathBuf::from("node_modules/data-lib"),
+            PathBuf::from("node_modules/dep-b"),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_package_dirs_without_package_imports() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(temp_dir.path(), "node_modules/data-lib", r#"{}"#);
+    let package_dirs = resolve_package_dirs(
+        temp_dir.path(),
+        &[importing_source(&[])],
+        false,
+        &matches_node_condition,
+    )
+    .unwrap()
+    .copied;
+    assert!(package_dirs.is_empty());
+}
+
+#[test]
+fn test_resolve_package_dirs_inlines_es_module_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"type":"module","dependencies":{"cjs-lib":"1.0.0","es-lib":"1.0.0"}}"#,
+    );
+    write_package(
+        temp_dir.path(),
+        "node_modules/cjs-lib",
+        r#"{"dependencies":{"es-lib":"1.0.0"}}"#,
+    );
+    write_package(
+        temp_dir.path(),
+        "node_modules/es-lib",
+        r#"{"type":"module"}"#,
+    );
+    let package_dirs = resolve_package_dirs(
+        temp_dir.path(),
+        &[importing_source(&["data-lib"])],
+        true,
+        &matches_node_condition,
+    )
+    .unwrap();
+    assert_eq!(
+        package_dirs,
+        PackageDirs {
+            copied: vec![
+                PathBuf::from("node_modules/cjs-lib"),
+                PathBuf::from("node_modules/es-lib"),
+            ],
+            inlined: vec![
+                PathBuf::from("node_modules/data-lib"),
+                PathBuf::from("node_modules/es-lib"),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_resolve_es_module_entry_by_export_conditions() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"exports":{".":{"require":"./index.cjs","import":"./index.js"},"./utils":"./utils.mjs"}}"#,
+    );
+    fs::write(temp_dir.child("node_modules/data-lib/utils.mjs"), "").unwrap();
+    let package_dir = temp_dir.child("node_modules/data-lib");
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, None, &matches_node_condition),
+        Some(PathBuf::from("index.js"))
+    );
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, Some("utils"), &matches_node_condition),
+        Some(PathBuf::from("utils.mjs"))
+    );
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, Some("index.js"), &matches_node_condition),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_es_module_entry_by_export_condition_order() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"exports":{".":{"default":"./index.js","import":"./index.mjs"},"./worker":{"worker":"./worker.mjs","default":"./index.js"}}}"#,
+    );
+    fs::write(temp_dir.child("node_modules/data-lib/index.mjs"), "").unwrap();
+    fs::write(temp_dir.child("node_modules/data-lib/worker.mjs"), "").unwrap();
+    let package_dir = temp_dir.child("node_modules/data-lib");
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, None, &matches_node_condition),
+        None
+    );
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, Some("worker"), &matches_node_condition),
+        None
+    );
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, Some("worker"), &|condition| {
+            condition == "worker" || matches_node_condition(condition)
+        }),
+        Some(PathBuf::from("worker.mjs"))
+    );
+}
+
+#[test]
+fn test_resolve_es_module_entry_of_commonjs_package() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"main":"index.js"}"#,
+    );
+    assert_eq!(
+        resolve_es_module_entry(
+            &temp_dir.child("node_modules/data-lib"),
+            None,
+            &matches_node_condition
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_package_entry_by_export_patterns() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"exports":{".":{"require":"./index.cjs","node":"./index.js"},"./*":{"default":"./src/*.js"},"./src/*":null}}"#,
+    );
+    fs::create_dir_all(temp_dir.child("node_modules/data-lib/src")).unwrap();
+    fs::write(temp_dir.child("node_modules/data-lib/index.js"), "").unwrap();
+    fs::write(temp_dir.child("node_modules/data-lib/src/utils.js"), "").unwrap();
+    let package_dir = temp_dir.child("node_modules/data-lib");
+    assert_eq!(
+        resolve_package_entry(&package_dir, None, &matches_node_condition),
+        Some(PathBuf::from("index.js"))
+    );
+    assert_eq!(
+        resolve_package_entry(&package_dir, Some("utils"), &matches_node_condition),
+        Some(PathBuf::from("src/utils.js"))
+    );
+    assert_eq!(
+        resolve_package_entry(&package_dir, Some("src/utils"), &matches_node_condition),
+        None
+    );
+    assert_eq!(
+        resolve_package_entry(&package_dir, Some("dates"), &matches_node_condition),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_package_entry_by_main() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"main":"./lib/index"}"#,
+    );
+    fs::create_dir_all(temp_dir.child("node_modules/data-lib/lib")).unwrap();
+    fs::write(temp_dir.child("node_modules/data-lib/lib/index.js"), "").unwrap();
+    let package_dir = temp_dir.child("node_modules/data-lib");
+    assert_eq!(
+        resolve_package_entry(&package_dir, None, &matches_node_condition),
+        Some(PathBuf::from("lib/index.js"))
+    );
+    assert_eq!(
+        resolve_package_entry(&package_dir, Some("lib"), &matches_node_condition),
+        Some(PathBuf::from("lib/index.js"))
+    );
+}
+
+#[test]
+fn test_resolve_package_entry_by_dotted_extensionless_main() {
+    let temp_dir = TempDir::new().unwrap();
+    write_package(
+        temp_dir.path(),
+        "node_modules/data-lib",
+        r#"{"type":"module","main":"./lib/index.min"}"#,
+    );
+    fs::create_dir_all(temp_dir.child("node_modules/data-lib/lib")).unwrap();
+    fs::write(temp_dir.child("node_modules/data-lib/lib/index.min.js"), "").unwrap();
+    let package_dir = temp_dir.child("node_modules/data-lib");
+    assert_eq!(
+        resolve_package_entry(&package_dir, None, &matches_node_condition),
+        Some(PathBuf::from("lib/index.min.js"))
+    );
+    assert_eq!(
+        resolve_es_module_entry(&package_dir, None, &matches_node_condition),
+        Some(PathBuf::from("lib/index.min.js"))
+    );
+}
+
+#[test]
+fn test_split_package_specifier() {
+    assert_eq!(split_package_specifier("data-lib"), ("data-lib", None));
+    assert_eq!(
+        split_package_specifier("data-lib/utils/dates"),
+        ("data-lib", Some("utils/dates"))
+    );
+    assert_eq!(split_package_specifier("@l3/data"), ("@l3/data", None));
+    assert_eq!(
+        split_package_specifier("@l3/data/utils"),
+        ("@l3/data", Some("utils"))
+    );
+}
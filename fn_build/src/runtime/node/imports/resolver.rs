@@ -1,15 +1,15 @@
 use crate::paths::join_file_paths;
-use crate::runtime::node::imports::{
-    NodeSubpathImportAsterisks, NodeSubpathImportMapWildcardTo, NodeSubpathImportMapping,
+use crate::runtime::node::dependencies::{
+    resolve_package_dir, resolve_package_entry, split_package_specifier,
 };
+use crate::runtime::node::imports::{NodePackageTargetResolution, NodeSubpathImportMapping};
 use crate::runtime::node::NodeConfig;
 use crate::runtime::ImportResolver;
 use crate::ModuleImport;
+use l3_ast_parse::swc::visitors::ImportKind;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-// todo nodejs subpath imports
-//  https://nodejs.org/api/packages.html#subpath-imports
 // todo cross-check swc implementation
 //  https://github.com/swc-project/swc/blob/main/crates/swc_ecma_loader/src/resolvers/node.rs
 pub struct NodeImportResolver {
@@ -40,112 +40,104 @@ impl NodeImportResolver {
         }
     }
 
-    fn resolve_npm_dependency(&self, import: &str) -> Option<ModuleImport> {
-        let (package, subpath) = match import.split_once('/') {
-            None => (import.to_string(), None),
-            Some((before, after)) => (before.to_string(), Some(after.to_string())),
-        };
-        if self.node_config.package.has_npm_dependency(&package) {
-            Some(ModuleImport::PackageDependency { package, subpath })
-        } else {
-            None
-        }
-    }
-
-    fn resolve_subpath_import(&self, project_dir: &Path, import: &str) -> Option<ModuleImport> {
-        for mapping in &self.node_config.package.subpath_imports {
-            match &mapping {
-                NodeSubpathImportMapping::Explicit { from, to } => {
-                    if import == from {
-                        return self.resolve_subpath_import_mapped_explicitly(project_dir, to);
-                    }
-                }
-                NodeSubpathImportMapping::Wildcard { from, to } => {
-                    return match import.strip_prefix(&from.before) {
-                        None => continue,
-                        Some(import_remainder) => match to {
-                            NodeSubpathImportMapWildcardTo::Explicit(to) => self
-                                .resolve_subpath_import_mapping_wildcard_to_explicit_specifier(
-                                    project_dir,
-                                    to,
-                                ),
-                            NodeSubpathImportMapWildcardTo::Wildcard(to, asterisks) => self
-                                .resolve_subpath_import_mapping_wildcard_to_wildcard(
-                                    project_dir,
-                                    from.after
-                                        .as_ref()
-                                        .and_then(|from_after_asterisk| {
-                                            import_remainder.strip_suffix(from_after_asterisk)
-                                        })
-                                        .unwrap_or(import_remainder),
-                                    to,
-                                    asterisks,
-                                ),
-                        },
-                    }
-                }
-            }
-        }
-        None
-    }
-
-    fn resolve_subpath_import_mapped_explicitly(
+    /// Resolves a bare specifier of a package declared by package.json's "dependencies" to the
+    /// module the package exports for the specifier's subpath. A package that is not installed
+    /// resolves without a path, while an installed package that does not export the subpath
+    /// resolves None.
+    fn resolve_npm_dependency(
         &self,
         project_dir: &Path,
-        to: &str,
+        from: &Path,
+        import: &str,
+        kind: ImportKind,
     ) -> Option<ModuleImport> {
-        if to.starts_with('.') {
-            self.resolve_relative_path(project_dir, &self.package_json_path, to)
-                .map(ModuleImport::RelativeSource)
-        } else {
-            self.resolve_npm_dependency(to)
+        let (package, subpath) = split_package_specifier(import);
+        if !self.node_config.package.has_npm_dependency(package) {
+            return None;
         }
-    }
-
-    fn resolve_subpath_import_mapping_wildcard_to_wildcard(
-        &self,
-        project_dir: &Path,
-        substitution: &str,
-        to: &str,
-        asterisks: &NodeSubpathImportAsterisks,
-    ) -> Option<ModuleImport> {
-        let to = match asterisks {
-            NodeSubpathImportAsterisks::Single => to.replacen('*', substitution, 1),
-            NodeSubpathImportAsterisks::Multiple => to.replace('*', substitution),
+        let from_dir = from.parent().unwrap_or(Path::new(""));
+        let path = match resolve_package_dir(project_dir, from_dir, package) {
+            Some(package_dir) => Some(package_dir.join(resolve_package_entry(
+                &project_dir.join(&package_dir),
+                subpath,
+                &|condition| self.node_config.matches_condition(condition, kind),
+            )?)),
+            None => None,
         };
-        if to.starts_with('.') {
-            self.resolve_relative_path(project_dir, &self.package_json_path, to.as_str())
-                .map(ModuleImport::RelativeSource)
-        } else {
-            self.resolve_npm_dependency(to.as_str())
-        }
+        Some(ModuleImport::PackageDependency {
+            package: package.to_string(),
+            subpath: subpath.map(String::from),
+            path,
+        })
     }
 
-    fn resolve_subpath_import_mapping_wildcard_to_explicit_specifier(
+    /// Resolves a subpath import by the first mapping of its exact specifier or else the most
+    /// specific wildcard mapping matching the specifier.
+    fn resolve_subpath_import(
         &self,
         project_dir: &Path,
-        to: &str,
+        import: &str,
+        kind: ImportKind,
     ) -> Option<ModuleImport> {
-        if to.starts_with('.') {
-            self.resolve_relative_path(project_dir, &self.package_json_path, to)
-                .map(ModuleImport::RelativeSource)
-        } else {
-            self.resolve_npm_dependency(to)
+        let (to, substitution) =
+            self.node_config
+                .package
+                .subpath_imports
+                .iter()
+                .find_map(|mapping| match mapping {
+                    NodeSubpathImportMapping::Explicit { from, to } => {
+                        (import == from).then_some((to, None))
+                    }
+                    NodeSubpathImportMapping::Wildcard { from, to } => {
+                        Some((to, Some(from.match_specifier(import)?)))
+                    }
+                })?;
+        let resolution = to.resolve(
+            &|condition| self.node_config.matches_condition(condition, kind),
+            &|specifier| {
+                let specifier = match substitution {
+                    Some(substitution) => specifier.replace('*', substitution),
+                    None => specifier.to_string(),
+                };
+                if specifier.starts_with('.') {
+                    self.resolve_relative_path(project_dir, &self.package_json_path, &specifier)
+                        .map(ModuleImport::RelativeSource)
+                } else {
+                    self.resolve_npm_dependency(
+                        project_dir,
+                        &self.package_json_path,
+                        &specifier,
+                        kind,
+                    )
+                }
+            },
+        );
+        match resolution {
+            NodePackageTargetResolution::Resolved(import) => Some(import),
+            _ => None,
         }
     }
 }
 
 impl ImportResolver for NodeImportResolver {
-    fn resolve(&self, project_dir: &Path, from: &Path, import: &str) -> ModuleImport {
+    fn resolve(
+        &self,
+        project_dir: &Path,
+        from: &Path,
+        import: &str,
+        kind: ImportKind,
+    ) -> ModuleImport {
         if import.starts_with('.') {
             if let Some(relative_path) = self.resolve_relative_path(project_dir, from, import) {
                 return ModuleImport::RelativeSource(relative_path);
             }
         } else if import.starts_with('#') {
-            if let Some(subpath_import) = self.resolve_subpath_import(project_dir, import) {
+            if let Some(subpath_import) = self.resolve_subpath_import(project_dir, import, kind) {
                 return subpath_import;
             }
-        } else if let Some(npm_dependency) = self.resolve_npm_dependency(import) {
+        } else if let Some(npm_dependency) =
+            self.resolve_npm_dependency(project_dir, from, import, kind)
+        {
             return npm_dependency;
         }
         ModuleImport::Unknown(import.to_string())
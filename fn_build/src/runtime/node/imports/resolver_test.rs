@@ -2,6 +2,7 @@ use crate::runtime::node::imports::resolver::NodeImportResolver;
 use crate::runtime::node::NodeConfig;
 use crate::runtime::ImportResolver;
 use crate::ModuleImport;
+use l3_ast_parse::swc::visitors::ImportKind;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -21,11 +22,60 @@ fn test_node_import_resolver_resolves_relative_source() {
             &project_dir,
             &PathBuf::from("routes/data/lambda.js"),
             "../../lib/data.js",
+            ImportKind::Static,
         ),
         ModuleImport::RelativeSource(PathBuf::from("lib/data.js"))
     );
 }
 
+#[test]
+fn test_node_import_resolver_resolves_scoped_package_exports() {
+    let (project_dir, import_resolver) = create_import_resolver_for_fixture(
+        "fixtures/node/js/npm_dependencies/scoped_package_exports",
+    );
+    let from = PathBuf::from("routes/data/lambda.js");
+    assert_eq!(
+        import_resolver.resolve(&project_dir, &from, "@l3/data-lib", ImportKind::Static),
+        ModuleImport::PackageDependency {
+            package: "@l3/data-lib".to_string(),
+            subpath: None,
+            path: Some(PathBuf::from("node_modules/@l3/data-lib/dist/index.js")),
+        }
+    );
+    assert_eq!(
+        import_resolver.resolve(
+            &project_dir,
+            &from,
+            "@l3/data-lib/utils/format",
+            ImportKind::Static
+        ),
+        ModuleImport::PackageDependency {
+            package: "@l3/data-lib".to_string(),
+            subpath: Some("utils/format".to_string()),
+            path: Some(PathBuf::from(
+                "node_modules/@l3/data-lib/dist/utils/format.js"
+            )),
+        }
+    );
+}
+
+#[test]
+fn test_node_import_resolver_does_not_resolve_unexported_subpath() {
+    let (project_dir, import_resolver) = create_import_resolver_for_fixture(
+        "fixtures/node/js/npm_dependencies/scoped_package_exports",
+    );
+    let from = PathBuf::from("routes/data/lambda.js");
+    for import in [
+        "@l3/data-lib/dist/index.js",
+        "@l3/data-lib/utils/internal/format",
+    ] {
+        assert_eq!(
+            import_resolver.resolve(&project_dir, &from, import, ImportKind::Static),
+            ModuleImport::Unknown(import.to_string())
+        );
+    }
+}
+
 mod subpath_imports {
     use super::*;
 
@@ -42,6 +92,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("lib/redis.js"))
             );
@@ -57,10 +108,12 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::PackageDependency {
                     package: "data-dep".to_string(),
-                    subpath: None
+                    subpath: None,
+                    path: Some(PathBuf::from("node_modules/data-dep/index.js")),
                 }
             );
         }
@@ -79,6 +132,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/raw.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/raw.js"))
             );
@@ -87,6 +141,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/abstraction/orm.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/abstraction/orm.js"))
             );
@@ -102,6 +157,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/raw",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/raw.js"))
             );
@@ -110,6 +166,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/abstraction/orm",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/abstraction/orm.js"))
             );
@@ -125,10 +182,12 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::PackageDependency {
                     package: "data-dep".to_string(),
-                    subpath: None
+                    subpath: None,
+                    path: Some(PathBuf::from("node_modules/data-dep/index.js")),
                 },
             );
         }
@@ -143,6 +202,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("lib/redis.js"))
             );
@@ -163,6 +223,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/raw.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/raw.js"))
             );
@@ -171,6 +232,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/abstraction/orm.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/abstraction/orm.js"))
             );
@@ -186,6 +248,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/raw.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/raw.js"))
             );
@@ -194,6 +257,7 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data/abstraction/orm.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("data/abstraction/orm.js"))
             );
@@ -209,10 +273,12 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::PackageDependency {
                     package: "data-dep".to_string(),
-                    subpath: None
+                    subpath: None,
+                    path: Some(PathBuf::from("node_modules/data-dep/index.js")),
                 }
             );
         }
@@ -227,9 +293,86 @@ mod subpath_imports {
                     &project_dir,
                     &PathBuf::from("routes/data/lambda.js"),
                     "#lib/data.js",
+                    ImportKind::Static,
                 ),
                 ModuleImport::RelativeSource(PathBuf::from("lib/redis.js"))
             );
         }
     }
 }
+
+mod subpath_import_conditions {
+    use super::*;
+    use std::fs;
+    use temp_dir::TempDir;
+
+    fn create_project() -> TempDir {
+        let project_temp = TempDir::new().unwrap();
+        fs::write(
+            project_temp.child("package.json"),
+            r##"{
+                "type": "module",
+                "imports": {
+                    "#data/*": {
+                        "development": "./lib/mock/*.js",
+                        "default": "./lib/*.js"
+                    },
+                    "#data/internal/*": null
+                }
+            }"##,
+        )
+        .unwrap();
+        fs::create_dir_all(project_temp.child("lib/mock")).unwrap();
+        fs::create_dir_all(project_temp.child("lib/internal")).unwrap();
+        fs::write(project_temp.child("lib/users.js"), "").unwrap();
+        fs::write(project_temp.child("lib/mock/users.js"), "").unwrap();
+        fs::write(project_temp.child("lib/internal/keys.js"), "").unwrap();
+        project_temp
+    }
+
+    #[test]
+    fn test_resolves_custom_conditions_of_build() {
+        let project_temp = create_project();
+        let project_dir = project_temp.path().to_path_buf();
+        let node_config = NodeConfig::read_configs(&project_dir).unwrap();
+        let from = PathBuf::from("routes/data/lambda.js");
+        assert_eq!(
+            NodeImportResolver::new(Arc::new(node_config)).resolve(
+                &project_dir,
+                &from,
+                "#data/users",
+                ImportKind::Static
+            ),
+            ModuleImport::RelativeSource(PathBuf::from("lib/users.js"))
+        );
+        let node_config = NodeConfig::read_configs(&project_dir)
+            .unwrap()
+            .with_conditions(vec!["development".to_string()]);
+        assert_eq!(
+            NodeImportResolver::new(Arc::new(node_config)).resolve(
+                &project_dir,
+                &from,
+                "#data/users",
+                ImportKind::Static
+            ),
+            ModuleImport::RelativeSource(PathBuf::from("lib/mock/users.js"))
+        );
+    }
+
+    #[test]
+    fn test_does_not_resolve_excluded_by_more_specific_wildcard() {
+        let project_temp = create_project();
+        let project_dir = project_temp.path().to_path_buf();
+        let import_resolver =
+            NodeImportResolver::new(Arc::new(NodeConfig::read_configs(&project_dir).unwrap()));
+        assert_eq!(
+            import_resolver.resolve(
+                &project_dir,
+                &PathBuf::from("routes/data/lambda.js"),
+                "#data/internal/keys",
+                ImportKind::Static
+            ),
+            ModuleImport::Unknown("#data/internal/keys".to_string())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-pub(crate) use read::read_subpath_imports;
+pub(crate) use read::{read_package_target, read_subpath_imports};
 
 mod read;
 
@@ -10,54 +10,77 @@ mod read_test;
 pub enum SubpathImportError {
     #[error("subpath import condition `{0}` is invalid")]
     BadImportCondition(String),
+    #[error("subpath import target `{0}` is invalid")]
+    BadImportTarget(String),
 }
 
-/// Priority ordered collection of subpath import mappings. This collection is selective of
-/// conditions as configured in package.json and only contains the specifiers relevant for
-/// import resolution.
+/// Conditions matched when resolving imports for the Node.js runtime in addition to a build's
+/// custom conditions.
+pub const NODE_IMPORT_CONDITIONS: [&str; 4] = ["node-addons", "node", "import", "default"];
+
+/// Conditions matched when resolving `require()` calls for the Node.js runtime in addition to a
+/// build's custom conditions.
+pub const NODE_REQUIRE_CONDITIONS: [&str; 4] = ["node-addons", "node", "require", "default"];
+
+/// Priority ordered collection of subpath import mappings. Explicit mappings precede wildcard
+/// mappings, which are ordered by the specificity of their wildcard specifier the same way
+/// Node.js orders patterns.
 pub type NodeSubpathImports = Vec<NodeSubpathImportMapping>;
 
 /// Represents explicitly specified subpath imports like `#data` and wildcard specifiers such as
 /// `#lib/*.js`. Wildcard specifiers are built with str::split_once on the asterisk.
+#[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub enum NodeSubpathImportMapping {
     Explicit {
         from: String,
-        to: String,
+        to: NodePackageTarget,
     },
     Wildcard {
         from: NodeSubpathImportWildcard,
-        to: NodeSubpathImportMapWildcardTo,
+        to: NodePackageTarget,
     },
 }
 
 /// Represents a subpath import specifier that uses a wildcard asterisk for string substitution.
 /// The specifier is stored pre-split when building NodeSubpathImports.
+#[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct NodeSubpathImportWildcard {
     pub before: String,
     pub after: Option<String>,
 }
 
-/// Represents the destination of a NodeSubpathImportMapping::Wildcard mapping.
+/// Target of a package.json "imports" or "exports" entry resolved by the conditions of an
+/// import. Read docs at <https://nodejs.org/api/packages.html#conditional-exports>.
+#[derive(Clone)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
-pub enum NodeSubpathImportMapWildcardTo {
-    Explicit(String),
-    Wildcard(String, NodeSubpathImportAsterisks),
+pub enum NodePackageTarget {
+    /// A relative path or package specifier with every `*` substituted by a wildcard match.
+    Specifier(String),
+    /// Targets keyed by condition in package.json order.
+    Conditional(Vec<(String, NodePackageTarget)>),
+    /// Targets of an array tried in order until one resolves.
+    Fallbacks(Vec<NodePackageTarget>),
+    /// A `null` target excluding an import from resolving.
+    Excluded,
 }
 
-/// Flags whether to use replace or replacen 1 for wildcard substitution.
-#[cfg_attr(test, derive(Debug, PartialEq))]
-pub enum NodeSubpathImportAsterisks {
-    Single,
-    Multiple,
+/// Result of resolving a NodePackageTarget.
+#[derive(Debug, PartialEq)]
+pub enum NodePackageTargetResolution<T> {
+    Resolved(T),
+    /// No condition of a conditional target was matched.
+    Unmatched,
+    /// The target is excluded or its specifiers did not resolve.
+    Unresolved,
 }
 
 impl NodeSubpathImportMapping {
     /// Builds a wildcard mapping if the mapping from specifier includes an asterisk. A mapping
     /// from specifier without an asterisk can map to a specifier with an asterisk that will be
     /// treated as an explicit mapping for path replacement instead of wildcard mapping.
-    pub fn new(from: String, to: String) -> Self {
+    pub fn new(from: String, to: NodePackageTarget) -> Self {
         match from.split_once('*').map(|(b, a)| {
             (
                 b.to_string(),
@@ -71,27 +94,94 @@ impl NodeSubpathImportMapping {
             None => NodeSubpathImportMapping::Explicit { from, to },
             Some((before, after)) => Self::Wildcard {
                 from: NodeSubpathImportWildcard { before, after },
-                to: NodeSubpathImportMapWildcardTo::new(to),
+                to,
             },
         }
     }
 }
 
-impl NodeSubpathImportMapWildcardTo {
-    fn new(to: String) -> Self {
-        let mut asterisks = 0;
-        for c in to.chars() {
-            if c == '*' {
-                asterisks += 1;
-                if asterisks > 1 {
-                    continue;
+impl NodeSubpathImportWildcard {
+    /// Substitution of the asterisk for a specifier matching the wildcard, such as `data` for
+    /// `#lib/data.js` matching `#lib/*.js`.
+    pub fn match_specifier<'a>(&self, specifier: &'a str) -> Option<&'a str> {
+        let after = self.after.as_deref().unwrap_or_default();
+        if specifier.len() < self.before.len() + after.len() {
+            return None;
+        }
+        specifier.strip_prefix(&self.before)?.strip_suffix(after)
+    }
+
+    /// Length of the specifier without its asterisk, which orders wildcards of equal prefixes.
+    fn len(&self) -> usize {
+        self.before.len() + self.after.as_ref().map(String::len).unwrap_or_default()
+    }
+}
+
+impl NodePackageTarget {
+    /// Resolves a target with the Node.js algorithm for conditional targets. The first matched
+    /// condition that does not resolve as unmatched determines the resolution, and the first
+    /// fallback that resolves or is excluded, or else the last fallback, determines the
+    /// resolution of an array.
+    ///
+    /// Unlike Node.js, fallbacks are also skipped when `resolve_specifier` does not resolve a
+    /// specifier, such as a relative path to a missing source.
+    pub fn resolve<T>(
+        &self,
+        matches_condition: &impl Fn(&str) -> bool,
+        resolve_specifier: &impl Fn(&str) -> Option<T>,
+    ) -> NodePackageTargetResolution<T> {
+        match self {
+            NodePackageTarget::Specifier(specifier) => match resolve_specifier(specifier) {
+                Some(resolved) => NodePackageTargetResolution::Resolved(resolved),
+                None => NodePackageTargetResolution::Unresolved,
+            },
+            NodePackageTarget::Conditional(targets) => {
+                for (condition, target) in targets {
+                    if matches_condition(condition) {
+                        match target.resolve(matches_condition, resolve_specifier) {
+                            NodePackageTargetResolution::Unmatched => continue,
+                            resolution => return resolution,
+                        }
+                    }
                 }
+                NodePackageTargetResolution::Unmatched
             }
-        }
-        match asterisks {
-            0 => NodeSubpathImportMapWildcardTo::Explicit(to),
-            1 => NodeSubpathImportMapWildcardTo::Wildcard(to, NodeSubpathImportAsterisks::Single),
-            _ => NodeSubpathImportMapWildcardTo::Wildcard(to, NodeSubpathImportAsterisks::Multiple),
+            NodePackageTarget::Fallbacks(targets) => {
+                let mut last = NodePackageTargetResolution::Unresolved;
+                for target in targets {
+                    if let NodePackageTarget::Excluded = target {
+                        return NodePackageTargetResolution::Unresolved;
+                    }
+                    match target.resolve(matches_condition, resolve_specifier) {
+                        resolution @ NodePackageTargetResolution::Resolved(_) => return resolution,
+                        resolution => last = resolution,
+                    }
+                }
+                last
+            }
+            NodePackageTarget::Excluded => NodePackageTargetResolution::Unresolved,
         }
     }
 }
+
+/// Orders wildcard mappings by the length of the specifier before the asterisk and then by the
+/// specifier's length, both descending, after explicit mappings.
+pub(crate) fn sort_subpath_imports(subpath_imports: &mut NodeSubpathImports) {
+    subpath_imports.sort_by(|a, b| match (a, b) {
+        (
+            NodeSubpathImportMapping::Wildcard { from: a, .. },
+            NodeSubpathImportMapping::Wildcard { from: b, .. },
+        ) => b
+            .before
+            .len()
+            .cmp(&a.before.len())
+            .then_with(|| b.len().cmp(&a.len())),
+        (NodeSubpathImportMapping::Explicit { .. }, NodeSubpathImportMapping::Wildcard { .. }) => {
+            std::cmp::Ordering::Less
+        }
+        (NodeSubpathImportMapping::Wildcard { .. }, NodeSubpathImportMapping::Explicit { .. }) => {
+            std::cmp::Ordering::Greater
+        }
+        _ => std::cmp::Ordering::Equal,
+    });
+}
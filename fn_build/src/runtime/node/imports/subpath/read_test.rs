@@ -1,8 +1,8 @@
 use crate::runtime::node::imports::subpath::read::read_subpath_imports;
 use crate::runtime::node::imports::subpath::NodeSubpathImports;
 use crate::runtime::node::imports::{
-    NodeSubpathImportAsterisks, NodeSubpathImportMapWildcardTo, NodeSubpathImportMapping,
-    NodeSubpathImportWildcard,
+    NodePackageTarget, NodePackageTargetResolution, NodeSubpathImportMapping,
+    NodeSubpathImportWildcard, SubpathImportError, NODE_IMPORT_CONDITIONS,
 };
 use serde_json::{Map, Value};
 
@@ -21,7 +21,7 @@ pub fn test_read_subpath_imports_parses_explicit_subpath_import() {
     assert_eq!(
         &NodeSubpathImportMapping::Explicit {
             from: "#lib/data.js".to_string(),
-            to: "./lib/data.js".to_string()
+            to: NodePackageTarget::Specifier("./lib/data.js".to_string()),
         },
         subpath_imports.first().unwrap()
     );
@@ -41,10 +41,7 @@ pub fn test_read_subpath_imports_parses_wildcard_subpath_import() {
                 before: "#lib/".to_string(),
                 after: None,
             },
-            to: NodeSubpathImportMapWildcardTo::Wildcard(
-                "./lib/*".to_string(),
-                NodeSubpathImportAsterisks::Single
-            ),
+            to: NodePackageTarget::Specifier("./lib/*".to_string()),
         },
         subpath_imports.first().unwrap()
     );
@@ -64,10 +61,7 @@ pub fn test_read_subpath_imports_parses_wildcard_subpath_import_with_extension()
                 before: "#lib/".to_string(),
                 after: Some(".js".to_string()),
             },
-            to: NodeSubpathImportMapWildcardTo::Wildcard(
-                "./lib/*.js".to_string(),
-                NodeSubpathImportAsterisks::Single
-            ),
+            to: NodePackageTarget::Specifier("./lib/*.js".to_string()),
         },
         subpath_imports.first().unwrap()
     );
@@ -87,10 +81,7 @@ pub fn test_read_subpath_imports_parses_wildcard_subpath_import_with_multiple_as
                 before: "#lib/".to_string(),
                 after: Some(".js".to_string()),
             },
-            to: NodeSubpathImportMapWildcardTo::Wildcard(
-                "./lib/*/*.js".to_string(),
-                NodeSubpathImportAsterisks::Multiple
-            ),
+            to: NodePackageTarget::Specifier("./lib/*/*.js".to_string()),
         },
         subpath_imports.first().unwrap()
     );
@@ -110,14 +101,27 @@ pub fn test_read_subpath_imports_parses_wildcard_subpath_import_mapped_to_explic
                 before: "#lib/".to_string(),
                 after: Some(".js".to_string()),
             },
-            to: NodeSubpathImportMapWildcardTo::Explicit("./lib/data.js".to_string()),
+            to: NodePackageTarget::Specifier("./lib/data.js".to_string()),
         },
         subpath_imports.first().unwrap()
     );
 }
 
+fn resolve_subpath_import_target(
+    subpath_imports: &[NodeSubpathImportMapping],
+    conditions: &[&str],
+) -> NodePackageTargetResolution<String> {
+    let to = match subpath_imports.first().unwrap() {
+        NodeSubpathImportMapping::Explicit { to, .. } => to,
+        NodeSubpathImportMapping::Wildcard { to, .. } => to,
+    };
+    to.resolve(&|condition| conditions.contains(&condition), &|specifier| {
+        Some(specifier.to_string())
+    })
+}
+
 #[test]
-pub fn test_read_subpath_imports_removes_cjs_conditional_subpath_imports() {
+pub fn test_read_subpath_imports_keeps_conditions_in_package_json_order() {
     let subpath_imports = parse_for_read_result(
         r##"{
             "#lib/*": {
@@ -134,13 +138,27 @@ pub fn test_read_subpath_imports_removes_cjs_conditional_subpath_imports() {
                 before: "#lib/".to_string(),
                 after: None,
             },
-            to: NodeSubpathImportMapWildcardTo::Wildcard(
-                "./lib/mjs/*".to_string(),
-                NodeSubpathImportAsterisks::Single
-            ),
+            to: NodePackageTarget::Conditional(vec![
+                (
+                    "require".to_string(),
+                    NodePackageTarget::Specifier("./lib/cjs/*".to_string())
+                ),
+                (
+                    "module-sync".to_string(),
+                    NodePackageTarget::Specifier("./lib/cjs/*".to_string())
+                ),
+                (
+                    "import".to_string(),
+                    NodePackageTarget::Specifier("./lib/mjs/*".to_string())
+                ),
+            ]),
         },
         subpath_imports.first().unwrap()
     );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports, &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Resolved("./lib/mjs/*".to_string())
+    );
 }
 
 #[test]
@@ -161,10 +179,91 @@ pub fn test_read_subpath_imports_resolves_to_first_es_conditional_subpath_import
     );
     assert_eq!(1, subpath_imports.len());
     assert_eq!(
-        &NodeSubpathImportMapping::Explicit {
-            from: "#data".to_string(),
-            to: "data-dep".to_string()
-        },
-        subpath_imports.first().unwrap()
+        resolve_subpath_import_target(&subpath_imports, &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Resolved("data-dep".to_string())
     );
 }
+
+#[test]
+pub fn test_read_subpath_imports_resolves_custom_conditions() {
+    let subpath_imports = parse_for_read_result(
+        r##"{
+            "#data": {
+                "development": "./lib/data-mock.js",
+                "default": "./lib/data.js"
+            }
+        }"##,
+    );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports, &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Resolved("./lib/data.js".to_string())
+    );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports, &["development", "default"]),
+        NodePackageTargetResolution::Resolved("./lib/data-mock.js".to_string())
+    );
+}
+
+#[test]
+pub fn test_read_subpath_imports_resolves_null_and_array_targets() {
+    let subpath_imports = parse_for_read_result(
+        r##"{
+            "#data": {
+                "import": null,
+                "default": "./lib/data.js"
+            },
+            "#log": [{"worker": "./lib/worker-log.js"}, "./lib/log.js"],
+            "#lib/*": [null, "./lib/*.js"]
+        }"##,
+    );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports, &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Unresolved
+    );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports[1..], &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Resolved("./lib/log.js".to_string())
+    );
+    assert_eq!(
+        resolve_subpath_import_target(&subpath_imports[2..], &NODE_IMPORT_CONDITIONS),
+        NodePackageTargetResolution::Unresolved
+    );
+}
+
+#[test]
+pub fn test_read_subpath_imports_orders_wildcards_by_specificity() {
+    let subpath_imports = parse_for_read_result(
+        r##"{
+            "#lib/*": "./lib/*",
+            "#lib/data/*.js": "./lib/data/*.js",
+            "#lib/data/*": "./lib/data/*",
+            "#lib/data.js": "./lib/data.js"
+        }"##,
+    );
+    let specifiers: Vec<String> = subpath_imports
+        .iter()
+        .map(|mapping| match mapping {
+            NodeSubpathImportMapping::Explicit { from, .. } => from.clone(),
+            NodeSubpathImportMapping::Wildcard { from, .. } => format!(
+                "{}*{}",
+                from.before,
+                from.after.as_deref().unwrap_or_default()
+            ),
+        })
+        .collect();
+    assert_eq!(
+        specifiers,
+        vec!["#lib/data.js", "#lib/data/*.js", "#lib/data/*", "#lib/*"]
+    );
+}
+
+#[test]
+pub fn test_read_subpath_imports_errors_for_numeric_condition() {
+    let imports =
+        serde_json::from_str::<Map<String, Value>>(r##"{"#data": {"0": "./lib/data.js"}}"##)
+            .unwrap();
+    assert!(matches!(
+        read_subpath_imports(&imports),
+        Err(SubpathImportError::BadImportCondition(condition)) if condition == "0"
+    ));
+}
@@ -4,9 +4,13 @@ pub use parse_node_fn::parse_node_entrypoint;
 pub use parse_node_fn::parse_node_fn;
 
 mod build_node_fn;
+mod bundle;
+mod dependencies;
 mod imports;
 mod node_config;
 mod parse_node_fn;
 
+#[cfg(test)]
+mod dependencies_test;
 #[cfg(test)]
 mod node_config_test;
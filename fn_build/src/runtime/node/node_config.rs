@@ -1,6 +1,12 @@
-use crate::runtime::node::imports::{read_subpath_imports, NodeSubpathImports, SubpathImportError};
+use crate::runtime::node::imports::{
+    read_subpath_imports, NodeSubpathImports, SubpathImportError, NODE_IMPORT_CONDITIONS,
+    NODE_REQUIRE_CONDITIONS,
+};
 use crate::typescript::{TsConfigError, TsConfigJson};
+use l3_ast_parse::swc::visitors::ImportKind;
+use l3_fn_config::NodeVersion;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::{fs, io};
@@ -16,19 +22,31 @@ pub enum NodeConfigError {
     TsConfig(#[from] TsConfigError),
 }
 
+#[derive(Clone)]
 pub struct NodeConfig {
+    /// Custom conditions of a build matched by package.json "imports" and "exports" in addition
+    /// to the conditions of Node.js, like the `--conditions` flag of the Node.js CLI.
+    pub conditions: Vec<String>,
     pub package: PackageJson,
     pub ts: Option<Arc<TsConfigJson>>,
-    // version: Option<NodeVersion>
+    /// Node.js version targeted by a build's transpiled JS.
+    pub version: Option<NodeVersion>,
+}
+
+/// Module system of a package's .js sources explicitly set by package.json's "type".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PackageType {
+    CommonJs,
+    Module,
 }
 
 /// Parsed data from package.json necessary for building functions for Node.js.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct PackageJson {
-    /// Package names from package.json's "dependencies" object.
-    dependencies: Vec<String>,
-    /// True if package.json's "type" is explicitly set to "module".
-    module_type: bool,
+    /// Package names and version ranges from package.json's "dependencies" object.
+    dependencies: HashMap<String, String>,
+    /// Module system of the package's .js sources from package.json's "type".
+    module_type: Option<PackageType>,
     pub subpath_imports: NodeSubpathImports,
 }
 
@@ -38,6 +56,7 @@ impl NodeConfig {
         let ts_config_p = project_dir.join("tsconfig.json");
 
         Ok(Self {
+            conditions: Vec::new(),
             package: if package_json_p.exists() {
                 PackageJson::read_package_json(&package_json_p)?
             } else {
@@ -48,8 +67,29 @@ impl NodeConfig {
             } else {
                 None
             },
+            version: None,
         })
     }
+
+    pub fn with_conditions(mut self, conditions: Vec<String>) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    pub fn with_version(mut self, version: Option<NodeVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Whether a condition of package.json "imports" or "exports" is matched by an import of a
+    /// kind, where `require()` calls match `require` instead of `import`.
+    pub fn matches_condition(&self, condition: &str, kind: ImportKind) -> bool {
+        let runtime_conditions = match kind {
+            ImportKind::Static | ImportKind::Dynamic => NODE_IMPORT_CONDITIONS,
+            ImportKind::Require => NODE_REQUIRE_CONDITIONS,
+        };
+        runtime_conditions.contains(&condition) || self.conditions.iter().any(|c| c == condition)
+    }
 }
 
 impl PackageJson {
@@ -74,11 +114,20 @@ impl PackageJson {
         }
     }
 
-    pub fn has_npm_dependency(&self, package: &String) -> bool {
-        self.dependencies.contains(package)
+    pub fn has_npm_dependency(&self, package: &str) -> bool {
+        self.dependencies.contains_key(package)
+    }
+
+    pub fn npm_dependency_version(&self, package: &str) -> Option<&String> {
+        self.dependencies.get(package)
     }
 
     pub fn is_es_module(&self) -> bool {
+        self.module_type == Some(PackageType::Module)
+    }
+
+    /// Module system set by package.json's "type" or None if "type" is unspecified.
+    pub fn package_type(&self) -> Option<PackageType> {
         self.module_type
     }
 }
@@ -89,16 +138,25 @@ impl TryFrom<Value> for PackageJson {
     fn try_from(package_json: Value) -> Result<Self, Self::Error> {
         Ok(Self {
             dependencies: match &package_json.get("dependencies") {
-                None => Vec::new(),
+                None => HashMap::new(),
                 Some(dependencies) => match dependencies.as_object() {
-                    None => Vec::new(),
-                    Some(dependencies) => dependencies.keys().cloned().collect(),
+                    None => HashMap::new(),
+                    Some(dependencies) => dependencies
+                        .iter()
+                        .map(|(package, version)| {
+                            (
+                                package.clone(),
+                                version.as_str().unwrap_or_default().to_string(),
+                            )
+                        })
+                        .collect(),
                 },
             },
-            module_type: package_json["type"]
-                .as_str()
-                .map(|t| t == "module")
-                .unwrap_or(false),
+            module_type: match package_json["type"].as_str() {
+                Some("commonjs") => Some(PackageType::CommonJs),
+                Some("module") => Some(PackageType::Module),
+                _ => None,
+            },
             subpath_imports: match &package_json.get("imports") {
                 None => Vec::new(),
                 Some(imports) => match imports.as_object() {
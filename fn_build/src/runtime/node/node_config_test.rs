@@ -2,7 +2,8 @@ use std::fs;
 
 use temp_dir::TempDir;
 
-use crate::runtime::node::{NodeConfig, NodeConfigError};
+use crate::runtime::node::{NodeConfig, NodeConfigError, PackageType};
+use l3_ast_parse::swc::visitors::ImportKind;
 
 #[test]
 pub fn test_parse_node_config_reads_tsconfig() {
@@ -41,12 +42,12 @@ pub fn test_parse_node_config_with_dependencies() {
     )
     .unwrap();
     let node_config = NodeConfig::read_configs(temp_dir.path()).unwrap();
-    assert!(node_config
-        .package
-        .has_npm_dependency(&"data-lib".to_string()));
-    assert!(!node_config
-        .package
-        .has_npm_dependency(&"logging-lib".to_string()));
+    assert!(node_config.package.has_npm_dependency("data-lib"));
+    assert!(!node_config.package.has_npm_dependency("logging-lib"));
+    assert_eq!(
+        node_config.package.npm_dependency_version("data-lib"),
+        Some(&"0.0.1".to_string())
+    );
 }
 
 #[test]
@@ -74,14 +75,14 @@ pub fn test_parse_node_config_with_invalid_subpath_imports() {
     let temp_dir = TempDir::new().unwrap();
     fs::write(
         temp_dir.child("package.json"),
-        r##"{"imports":{"#lib":{"nick":"./lib.js"}}}"##,
+        r##"{"imports":{"#lib":{"0":"./lib.js"}}}"##,
     )
     .unwrap();
     match NodeConfig::read_configs(temp_dir.path()) {
         Err(NodeConfigError::SubpathImport(err)) => {
             assert_eq!(
                 err.to_string(),
-                "subpath import condition `nick` is invalid".to_string()
+                "subpath import condition `0` is invalid".to_string()
             );
         }
         _ => panic!(),
@@ -102,4 +103,34 @@ pub fn test_parse_node_config_resolves_not_module_package_type_when_unspecified(
     fs::write(temp_dir.child("package.json"), "{}").unwrap();
     let node_config = NodeConfig::read_configs(temp_dir.path()).unwrap();
     assert!(!node_config.package.is_es_module());
+    assert_eq!(node_config.package.package_type(), None);
+}
+
+#[test]
+pub fn test_parse_node_config_resolves_commonjs_package_type() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("package.json"), r#"{"type":"commonjs"}"#).unwrap();
+    let node_config = NodeConfig::read_configs(temp_dir.path()).unwrap();
+    assert!(!node_config.package.is_es_module());
+    assert_eq!(
+        node_config.package.package_type(),
+        Some(PackageType::CommonJs)
+    );
+}
+
+#[test]
+pub fn test_node_config_matches_custom_conditions_of_imports_and_requires() {
+    let temp_dir = TempDir::new().unwrap();
+    let node_config = NodeConfig::read_configs(temp_dir.path())
+        .unwrap()
+        .with_conditions(vec!["development".to_string()]);
+    for kind in [ImportKind::Static, ImportKind::Dynamic, ImportKind::Require] {
+        assert!(node_config.matches_condition("development", kind));
+        assert!(node_config.matches_condition("node", kind));
+        assert!(!node_config.matches_condition("production", kind));
+    }
+    assert!(node_config.matches_condition("import", ImportKind::Static));
+    assert!(!node_config.matches_condition("require", ImportKind::Static));
+    assert!(node_config.matches_condition("require", ImportKind::Require));
+    assert!(!node_config.matches_condition("import", ImportKind::Require));
 }
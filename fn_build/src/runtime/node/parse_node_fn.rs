@@ -1,23 +1,23 @@
 use crate::runtime::node::imports::resolver::NodeImportResolver;
-use crate::runtime::node::NodeConfig;
+use crate::runtime::node::{NodeConfig, NodeConfigError, PackageType};
 use crate::runtime::parse_fn::parse_fn_inner;
 use crate::runtime::ts_imports::TypeScriptImportResolver;
 use crate::runtime::{FnSourceParser, ImportResolver, Runtime};
-use crate::swc::compiler::{CompileError, SwcCompiler};
-use crate::swc::visitors::CollectImportsVisitor;
 use crate::{
-    FnEntrypoint, FnParseError, FnParseManifest, FnParseResult, FnParseSpec, FnSource, ModuleImport,
+    FnEntrypoint, FnHandler, FnHandlerDefinition, FnParseError, FnParseManifest, FnParseResult,
+    FnParseSpec, FnSource, ModuleImport,
 };
+use l3_ast_parse::ecmascript::{collect_commonjs_handlers, collect_module_exports, ModuleExport};
+use l3_ast_parse::swc::visitors::{CollectImportsVisitor, ImportKind};
+use l3_ast_parse::swc::{CompileError, ParsedSource, SourceType, SwcParser};
+use l3_ast_parse::AstParseError;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use swc_ecma_ast::{Decl, ExportDecl, Module, ModuleDecl};
+use swc_ecma_ast::Program;
 use swc_ecma_visit::FoldWith;
 
 pub async fn parse_node_entrypoint(parse_spec: FnParseSpec) -> FnParseResult<FnEntrypoint> {
-    let source_parser = match &parse_spec.runtime {
-        Runtime::Node(node_config) => create_parser(node_config, &parse_spec.project_dir)?,
-        _ => panic!(),
-    };
+    let source_parser = create_parser(&parse_spec)?;
     Ok(FnEntrypoint {
         handlers: source_parser
             .collect_handlers(&parse_spec.project_dir, &parse_spec.entrypoint)?,
@@ -26,67 +26,191 @@ pub async fn parse_node_entrypoint(parse_spec: FnParseSpec) -> FnParseResult<FnE
 }
 
 pub async fn parse_node_fn(parse_spec: FnParseSpec) -> FnParseResult<FnParseManifest> {
-    parse_fn_inner(
-        &parse_spec,
-        match &parse_spec.runtime {
-            Runtime::Node(node_config) => create_parser(node_config, &parse_spec.project_dir)?,
-            _ => panic!(),
-        },
-    )
-    .await
+    parse_fn_inner(&parse_spec, create_parser(&parse_spec)?).await
 }
 
-/// Initializes NodeConfig from project_dir if not provided and creates a NodeFnSourceParser.
+/// Creates a NodeFnSourceParser with the NodeConfig of the parse spec's runtime and conditions.
 /// NodeFnSourceParser determines whether to use TS+Node or vanilla Node import resolution.
-fn create_parser(
-    maybe_node_config: &Option<Arc<NodeConfig>>,
+fn create_parser(parse_spec: &FnParseSpec) -> FnParseResult<Arc<Box<dyn FnSourceParser>>> {
+    let node_config = read_node_config(
+        &parse_spec.runtime,
+        &parse_spec.project_dir,
+        &parse_spec.conditions,
+    )?;
+    Ok(Arc::new(Box::new(NodeFnSourceParser::new(node_config))))
+}
+
+/// NodeConfig of a Node.js runtime, initialized from project_dir if not provided, matching a
+/// spec's custom conditions.
+pub(super) fn read_node_config(
+    runtime: &Runtime,
     project_dir: &Path,
-) -> FnParseResult<Arc<Box<dyn FnSourceParser>>> {
-    let node_config = match maybe_node_config {
-        Some(nc) => nc.clone(),
-        None => Arc::new(NodeConfig::read_configs(project_dir)?),
+    conditions: &[String],
+) -> Result<Arc<NodeConfig>, NodeConfigError> {
+    let node_config = match runtime {
+        Runtime::Node(Some(node_config)) => node_config.clone(),
+        Runtime::Node(None) => Arc::new(NodeConfig::read_configs(project_dir)?),
+        _ => panic!(),
     };
-    Ok(Arc::new(Box::new(NodeFnSourceParser::new(node_config))))
+    if conditions.is_empty() {
+        Ok(node_config)
+    } else {
+        Ok(Arc::new(
+            NodeConfig::clone(&node_config).with_conditions(conditions.to_vec()),
+        ))
+    }
+}
+
+/// Creates an ImportResolver for TS+Node import resolution if the project has a tsconfig.json.
+pub(super) fn create_import_resolver(node_config: &Arc<NodeConfig>) -> Box<dyn ImportResolver> {
+    match &node_config.ts {
+        Some(tsconfig) => Box::new(TypeScriptImportResolver::new(
+            tsconfig.clone(),
+            Box::new(NodeImportResolver::new(node_config.clone())),
+        )),
+        None => Box::new(NodeImportResolver::new(node_config.clone())),
+    }
+}
+
+/// Module system of a source by its extension or, for .js sources, package.json's "type".
+/// TypeScript sources use ES module syntax regardless of the module system they compile to.
+pub(super) fn source_type(node_config: &NodeConfig, source_path: &Path) -> SourceType {
+    match source_path.extension().and_then(|ext| ext.to_str()) {
+        Some("cjs") => SourceType::CommonJs,
+        Some("mjs") => SourceType::EsModule,
+        Some("js") => match node_config.package.package_type() {
+            Some(PackageType::CommonJs) => SourceType::CommonJs,
+            Some(PackageType::Module) => SourceType::EsModule,
+            None => SourceType::Detect,
+        },
+        _ => SourceType::Detect,
+    }
 }
 
 impl From<CompileError> for FnParseError {
     fn from(err: CompileError) -> Self {
-        match err {
-            CompileError::CompilerDiagnostics(diagnostics) => {
-                // todo map SWC diagnostics to a public API type
-                dbg!(diagnostics);
-                FnParseError::SyntaxError
-            }
-            CompileError::OperationError(err) => todo!("compiler op error: {}", err),
-            CompileError::ReadError(err) => FnParseError::IoError(err),
-        }
+        AstParseError::from(err).into()
     }
 }
 
 struct NodeFnSourceParser {
-    compiler: SwcCompiler,
+    compiler: SwcParser,
     import_resolver: Arc<Box<dyn ImportResolver>>,
+    node_config: Arc<NodeConfig>,
 }
 
 impl NodeFnSourceParser {
     fn new(node_config: Arc<NodeConfig>) -> Self {
         Self {
-            compiler: SwcCompiler::new(),
-            import_resolver: match &node_config.ts {
-                Some(tsconfig) => Arc::new(Box::new(TypeScriptImportResolver::new(
-                    tsconfig.clone(),
-                    Box::new(NodeImportResolver::new(node_config)),
-                ))),
-                None => Arc::new(Box::new(NodeImportResolver::new(node_config))),
-            },
+            compiler: SwcParser::new(),
+            import_resolver: Arc::new(create_import_resolver(&node_config)),
+            node_config,
         }
     }
 
-    fn parse_module(&self, project_dir: &Path, source_path: &Path) -> FnParseResult<Module> {
-        Ok(self
-            .compiler
-            .clone()
-            .parse_module_from_fs(&project_dir.join(source_path))?)
+    fn parse_program(&self, project_dir: &Path, source_path: &Path) -> FnParseResult<Program> {
+        Ok(self.compiler.clone().parse_program_from_fs(
+            &project_dir.join(source_path),
+            self.source_type(source_path),
+        )?)
+    }
+
+    fn parse_source(&self, project_dir: &Path, source_path: &Path) -> FnParseResult<ParsedSource> {
+        Ok(self.compiler.clone().parse_source_from_fs(
+            &project_dir.join(source_path),
+            self.source_type(source_path),
+        )?)
+    }
+
+    fn source_type(&self, source_path: &Path) -> SourceType {
+        source_type(&self.node_config, source_path)
+    }
+
+    /// Collects handlers of a source, following re-exports to the handlers of the function's
+    /// other sources. Sources already visited by a circular re-export do not export handlers.
+    fn collect_source_handlers(
+        &self,
+        project_dir: &Path,
+        source_path: &Path,
+        visited: &mut Vec<PathBuf>,
+    ) -> FnParseResult<Vec<FnHandler>> {
+        if visited.iter().any(|visited| visited == source_path) {
+            return Ok(Vec::new());
+        }
+        visited.push(source_path.to_path_buf());
+        let source = self.parse_source(project_dir, source_path)?;
+        let module = match &source.program {
+            Program::Module(module) => module,
+            Program::Script(script) => return Ok(collect_commonjs_handlers(&source, &script.body)),
+        };
+        let mut handlers: Vec<FnHandler> = Vec::new();
+        for module_export in collect_module_exports(&source, module) {
+            match module_export {
+                ModuleExport::Handler(handler) => handlers.push(handler),
+                ModuleExport::ReExport {
+                    name,
+                    specifier,
+                    imported,
+                } => {
+                    let Some(path) = self.resolve_source(project_dir, source_path, &specifier)
+                    else {
+                        continue;
+                    };
+                    let re_exported = self.collect_source_handlers(project_dir, &path, visited)?;
+                    if let Some(handler) = re_exported
+                        .into_iter()
+                        .find(|handler| handler.name == imported)
+                    {
+                        handlers.push(FnHandler {
+                            definition: FnHandlerDefinition::ReExport {
+                                name: imported,
+                                path,
+                            },
+                            doc: handler.doc,
+                            name,
+                            signature: handler.signature,
+                        });
+                    }
+                }
+                ModuleExport::ReExportAll { specifier } => {
+                    let Some(path) = self.resolve_source(project_dir, source_path, &specifier)
+                    else {
+                        continue;
+                    };
+                    // export * does not re-export a module's default export
+                    for handler in self.collect_source_handlers(project_dir, &path, visited)? {
+                        if handler.name != "default" {
+                            handlers.push(FnHandler {
+                                definition: FnHandlerDefinition::ReExport {
+                                    name: handler.name.clone(),
+                                    path: path.clone(),
+                                },
+                                doc: handler.doc,
+                                name: handler.name,
+                                signature: handler.signature,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(handlers)
+    }
+
+    /// Resolves a specifier to a source of the project.
+    fn resolve_source(
+        &self,
+        project_dir: &Path,
+        source_path: &Path,
+        specifier: &str,
+    ) -> Option<PathBuf> {
+        match self
+            .import_resolver
+            .resolve(project_dir, source_path, specifier, ImportKind::Static)
+        {
+            ModuleImport::RelativeSource(path) => Some(path),
+            _ => None,
+        }
     }
 
     fn collect_imports(
@@ -94,15 +218,19 @@ impl NodeFnSourceParser {
         project_dir: &Path,
         source_path: &Path,
     ) -> FnParseResult<Vec<ModuleImport>> {
-        let module = self.parse_module(project_dir, source_path)?;
+        let program = self.parse_program(project_dir, source_path)?;
         let mut visitor = CollectImportsVisitor::new();
-        module.fold_with(&mut visitor);
+        program.fold_with(&mut visitor);
         let imports = visitor
             .result()
             .into_iter()
             .map(|import| {
-                self.import_resolver
-                    .resolve(project_dir, source_path, import.as_str())
+                self.import_resolver.resolve(
+                    project_dir,
+                    source_path,
+                    &import.specifier,
+                    import.kind,
+                )
             })
             .collect();
         Ok(imports)
@@ -114,41 +242,8 @@ impl FnSourceParser for NodeFnSourceParser {
         &self,
         project_dir: &Path,
         source_path: &Path,
-    ) -> FnParseResult<Vec<String>> {
-        let module = self.parse_module(project_dir, source_path)?;
-        let mut handlers: Vec<String> = Vec::new();
-        let parse_fn_name = |s: &str| {
-            s.trim_end_matches(char::is_numeric)
-                .trim_end_matches(char::is_numeric)
-                .trim_end_matches('#')
-                .to_string()
-        };
-        for module_item in module.body {
-            if let Some(module_decl) = module_item.module_decl() {
-                match module_decl {
-                    ModuleDecl::ExportDecl(ExportDecl {
-                        decl: Decl::Fn(fn_decl),
-                        ..
-                    }) => handlers.push(parse_fn_name(fn_decl.ident.as_ref())),
-                    ModuleDecl::ExportDecl(ExportDecl {
-                        decl: Decl::Var(var_decl),
-                        ..
-                    }) => {
-                        for var_declarator in var_decl.decls {
-                            if let Some(expr) = var_declarator.init {
-                                if expr.as_arrow().is_some() || expr.as_fn_expr().is_some() {
-                                    handlers.push(parse_fn_name(
-                                        var_declarator.name.ident().unwrap().as_ref(),
-                                    ))
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            };
-        }
-        Ok(handlers)
+    ) -> FnParseResult<Vec<FnHandler>> {
+        self.collect_source_handlers(project_dir, source_path, &mut Vec::new())
     }
 
     fn collect_runtime_sources(&self, project_dir: &Path) -> Vec<FnSource> {
@@ -163,11 +258,18 @@ impl FnSourceParser for NodeFnSourceParser {
         }
     }
 
+    fn package_version(&self, package: &str) -> Option<String> {
+        self.node_config
+            .package
+            .npm_dependency_version(package)
+            .cloned()
+    }
+
     fn parse_fn_entrypoint(
         &self,
         project_dir: &Path,
         source_path: PathBuf,
-    ) -> FnParseResult<(FnSource, Vec<String>)> {
+    ) -> FnParseResult<(FnSource, Vec<FnHandler>)> {
         let handlers = self.collect_handlers(project_dir, &source_path)?;
         let source = self.parse_for_imports(project_dir, source_path)?;
         Ok((source, handlers))
@@ -1,9 +1,9 @@
 use crate::runtime::FnSourceParser;
 use crate::{
-    FnDependencies, FnEntrypoint, FnParseError, FnParseManifest, FnParseResult, FnParseSpec,
-    FnSource, ModuleImport,
+    FnDependencies, FnEntrypoint, FnPackageDependency, FnParseError, FnParseManifest,
+    FnParseResult, FnParseSpec, FnSource, ModuleImport,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
@@ -24,7 +24,7 @@ pub async fn parse_fn_inner(
     source_parser: Arc<Box<dyn FnSourceParser>>,
 ) -> FnParseResult<FnParseManifest> {
     let mut sources: HashMap<PathBuf, SourceParsingState> = HashMap::new();
-    let mut uses_deps = false;
+    let mut packages: BTreeSet<String> = BTreeSet::new();
     let mut parsing: usize = 1;
     let mut join_handles: Vec<JoinHandle<_>> = Vec::new();
     let (tx, mut rx) = unbounded_channel::<ParseFnMessage>();
@@ -47,7 +47,9 @@ pub async fn parse_fn_inner(
             ParseFnMessage::ParsedSourceFile { source_file } => {
                 for import in &source_file.imports {
                     match import {
-                        ModuleImport::PackageDependency { .. } => uses_deps = true,
+                        ModuleImport::PackageDependency { package, .. } => {
+                            packages.insert(package.clone());
+                        }
                         ModuleImport::RelativeSource(relative_source) => {
                             debug_assert!(
                                 relative_source.is_relative(),
@@ -88,10 +90,18 @@ pub async fn parse_fn_inner(
         }
     }
     Ok(FnParseManifest {
-        dependencies: if uses_deps {
-            FnDependencies::Required
-        } else {
+        dependencies: if packages.is_empty() {
             FnDependencies::Unused
+        } else {
+            FnDependencies::Packages(
+                packages
+                    .into_iter()
+                    .map(|package| FnPackageDependency {
+                        version: source_parser.package_version(&package),
+                        name: package,
+                    })
+                    .collect(),
+            )
         },
         entrypoint: FnEntrypoint {
             handlers,
@@ -1,16 +1,110 @@
 use crate::runtime::build_fn::{build_fn_inner, BuildTask, TransformResult};
+use crate::runtime::python::dependencies::SitePackages;
 use crate::runtime::python::parse_python_fn;
-use crate::{FnBuildManifest, FnBuildResult, FnBuildSpec, FnDependencies};
-use std::path::Path;
+use crate::runtime::python::parse_python_fn::is_async_handler;
+use crate::runtime::python::python_config::PythonRequirements;
+use crate::{
+    FnBuildError, FnBuildManifest, FnBuildResult, FnBuildSpec, FnDependencies, FnParseError,
+};
+use std::path::{Path, PathBuf};
 
 pub async fn build_python_fn(build_spec: FnBuildSpec) -> FnBuildResult<FnBuildManifest> {
     let parse_manifest = parse_python_fn(build_spec.to_parse_spec()).await?;
-    match parse_manifest.dependencies {
-        FnDependencies::Unused => {}
-        _ => todo!(),
+    parse_manifest
+        .entrypoint
+        .require_handler(&build_spec.handler_fn_name)?;
+    let mut build_tasks = Vec::new();
+    let mut distributions = Vec::new();
+    if let FnDependencies::Packages(_) = parse_manifest.dependencies {
+        // without a virtualenv, only packages provided by the Lambda runtime can be imported
+        let site_packages = SitePackages::read_site_packages(&build_spec.project_dir)
+            .map_err(FnParseError::from)?
+            .unwrap_or_default();
+        let requirements = PythonRequirements::read_requirements(&build_spec.project_dir)
+            .map_err(FnParseError::from)?;
+        let mut files = Vec::new();
+        for distribution in site_packages
+            .resolve_distributions(&parse_manifest.sources, &requirements)
+            .map_err(FnBuildError::UninstalledPackage)?
+        {
+            distributions.push(format!(
+                "{}@{}",
+                distribution.name,
+                distribution.version.as_deref().unwrap_or_default()
+            ));
+            files.extend(distribution.files.iter().cloned());
+        }
+        if !files.is_empty() {
+            build_tasks.push(BuildTask::CopyDirectoryFiles {
+                dir: site_packages.dir,
+                files,
+            });
+        }
+    }
+    let async_handler_path = if is_async_handler(
+        &build_spec.project_dir,
+        &build_spec.entrypoint,
+        &build_spec.handler_fn_name,
+    )? {
+        let path = async_handler_path(&build_spec.entrypoint);
+        build_tasks.push(BuildTask::GenerateFile {
+            path: path.clone(),
+            content: async_handler_module(&build_spec.entrypoint, &build_spec.handler_fn_name),
+        });
+        Some(path)
+    } else {
+        None
     };
-    let build_tasks = vec![BuildTask::CopySourceFiles(parse_manifest.source_paths())];
-    build_fn_inner(&build_spec, parse_manifest, build_tasks, noop_transform).await
+    build_tasks.push(BuildTask::CopySourceFiles(parse_manifest.source_paths()));
+    let mut build_manifest = build_fn_inner(
+        &build_spec,
+        parse_manifest,
+        build_tasks,
+        // distributions are included so that a previous build's unused distributions are not reused
+        &format!(
+            "async_handler={}\ndistributions={}",
+            async_handler_path.is_some(),
+            distributions.join(",")
+        ),
+        noop_transform,
+    )
+    .await?;
+    if let Some(path) = async_handler_path {
+        build_manifest.handler.path = path;
+    }
+    Ok(build_manifest)
+}
+
+/// Path of the module generated to run an async handler fn, such as
+/// `routes/data/lambda_l3_async.py` for `routes/data/lambda.py`.
+fn async_handler_path(entrypoint: &Path) -> PathBuf {
+    entrypoint.with_file_name(format!(
+        "{}_l3_async.py",
+        entrypoint.file_stem().unwrap().to_string_lossy()
+    ))
+}
+
+/// Generates a module with a sync handler fn of the same name as an async handler fn that runs
+/// the handler's coroutine with an event loop reused across invocations.
+fn async_handler_module(entrypoint: &Path, handler_fn_name: &str) -> String {
+    let module = entrypoint
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+    format!(
+        r#"import asyncio
+import importlib
+
+_loop = asyncio.new_event_loop()
+_handler = importlib.import_module('{module}').{handler_fn_name}
+
+
+def {handler_fn_name}(*args, **kwargs):
+    return _loop.run_until_complete(_handler(*args, **kwargs))
+"#
+    )
 }
 
 fn noop_transform(_: &Path, _: String) -> FnBuildResult<TransformResult> {
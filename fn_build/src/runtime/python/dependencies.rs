@@ -0,0 +1,262 @@
+use crate::runtime::python::python_config::{
+    normalize_distribution_name, parse_requirement, PythonConfigError, PythonRequirements,
+};
+use crate::{FnSource, ModuleImport};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Virtualenv dirs of a project searched for installed distributions.
+const VIRTUALENV_DIRS: [&str; 2] = [".venv", "venv"];
+
+/// Packages provided by the Lambda Python runtime that do not need to be installed for a build.
+const LAMBDA_RUNTIME_PACKAGES: [&str; 2] = ["boto3", "botocore"];
+
+/// A distribution installed to a virtualenv's site-packages.
+#[derive(Debug, PartialEq)]
+pub struct InstalledDistribution {
+    /// Normalized name of the distribution, such as `data-lib`.
+    pub name: String,
+    pub version: Option<String>,
+    /// Files of the distribution relative to site-packages from its `.dist-info/RECORD`.
+    pub files: Vec<PathBuf>,
+    /// Top-level packages and modules importable from the distribution, such as `data_lib`.
+    pub top_level: Vec<String>,
+    /// Normalized names of the distributions it requires when installed without extras.
+    pub requires: Vec<String>,
+}
+
+/// Distributions installed to a project's virtualenv.
+#[derive(Debug, Default)]
+pub struct SitePackages {
+    /// Project relative path of site-packages, such as `.venv/lib/python3.13/site-packages`.
+    pub dir: PathBuf,
+    pub distributions: BTreeMap<String, InstalledDistribution>,
+}
+
+impl SitePackages {
+    /// Reads distributions of site-packages from the project's `.venv` or `venv` virtualenv.
+    pub fn read_site_packages(project_dir: &Path) -> Result<Option<Self>, PythonConfigError> {
+        let Some(dir) = find_site_packages(project_dir) else {
+            return Ok(None);
+        };
+        let mut distributions = BTreeMap::new();
+        for dir_entry in fs::read_dir(project_dir.join(&dir))? {
+            let dist_info_dir = dir_entry?.path();
+            if dist_info_dir.is_dir()
+                && dist_info_dir
+                    .extension()
+                    .is_some_and(|ext| ext == "dist-info")
+            {
+                if let Some(distribution) = read_dist_info(&dist_info_dir)? {
+                    distributions.insert(distribution.name.clone(), distribution);
+                }
+            }
+        }
+        Ok(Some(Self { dir, distributions }))
+    }
+
+    /// Distributions providing a top-level package or module. A namespace package, such as
+    /// `google`, is provided by many distributions, which are narrowed to those declared by the
+    /// project's requirements when any of them are declared.
+    pub fn distributions_of(
+        &self,
+        package: &str,
+        requirements: &PythonRequirements,
+    ) -> Vec<&InstalledDistribution> {
+        let providing: Vec<&InstalledDistribution> = self
+            .distributions
+            .values()
+            .filter(|distribution| distribution.top_level.iter().any(|p| p == package))
+            .collect();
+        let declared: Vec<&InstalledDistribution> = providing
+            .iter()
+            .copied()
+            .filter(|distribution| requirements.is_declared(&distribution.name))
+            .collect();
+        if declared.is_empty() {
+            providing
+        } else {
+            declared
+        }
+    }
+
+    /// Resolves the distributions providing the packages imported by a function's sources and
+    /// the distributions those require. Errors with the name of an imported package that is not
+    /// installed, unless the package is provided by the Lambda Python runtime.
+    pub fn resolve_distributions(
+        &self,
+        sources: &[FnSource],
+        requirements: &PythonRequirements,
+    ) -> Result<Vec<&InstalledDistribution>, String> {
+        let mut resolving: VecDeque<&InstalledDistribution> = VecDeque::new();
+        for source in sources {
+            for import in &source.imports {
+                if let ModuleImport::PackageDependency { package, .. } = import {
+                    let distributions = self.distributions_of(package, requirements);
+                    if distributions.is_empty()
+                        && !LAMBDA_RUNTIME_PACKAGES.contains(&package.as_str())
+                    {
+                        return Err(package.clone());
+                    }
+                    resolving.extend(distributions);
+                }
+            }
+        }
+        let mut resolved: BTreeSet<&str> = BTreeSet::new();
+        while let Some(distribution) = resolving.pop_front() {
+            if !resolved.insert(&distribution.name) {
+                continue;
+            }
+            // requirements of other platforms or python versions are not installed
+            for requirement in &distribution.requires {
+                if let Some(required) = self.distributions.get(requirement) {
+                    resolving.push_back(required);
+                }
+            }
+        }
+        Ok(resolved
+            .into_iter()
+            .map(|name| &self.distributions[name])
+            .collect())
+    }
+}
+
+fn find_site_packages(project_dir: &Path) -> Option<PathBuf> {
+    for virtualenv_dir in VIRTUALENV_DIRS.map(PathBuf::from) {
+        let windows_site_packages = virtualenv_dir.join("Lib").join("site-packages");
+        if project_dir.join(&windows_site_packages).is_dir() {
+            return Some(windows_site_packages);
+        }
+        let Ok(lib_dir_entries) = fs::read_dir(project_dir.join(&virtualenv_dir).join("lib"))
+        else {
+            continue;
+        };
+        let mut python_dirs: Vec<_> = lib_dir_entries
+            .filter_map(Result::ok)
+            .map(|dir_entry| dir_entry.file_name().to_string_lossy().to_string())
+            .filter(|dirname| dirname.starts_with("python"))
+            .collect();
+        python_dirs.sort();
+        for python_dir in python_dirs {
+            let site_packages = virtualenv_dir
+                .join("lib")
+                .join(python_dir)
+                .join("site-packages");
+            if project_dir.join(&site_packages).is_dir() {
+                return Some(site_packages);
+            }
+        }
+    }
+    None
+}
+
+fn read_dist_info(
+    dist_info_dir: &Path,
+) -> Result<Option<InstalledDistribution>, PythonConfigError> {
+    let metadata_p = dist_info_dir.join("METADATA");
+    let record_p = dist_info_dir.join("RECORD");
+    if !metadata_p.is_file() || !record_p.is_file() {
+        return Ok(None);
+    }
+    let metadata = parse_metadata(&fs::read_to_string(metadata_p)?);
+    let Some(name) = metadata.get("Name").and_then(|names| names.first()) else {
+        return Ok(None);
+    };
+    let files = parse_record(&fs::read_to_string(record_p)?);
+    let mut top_level: Vec<String> = Vec::new();
+    for file in &files {
+        if let Some(package) = top_level_package(file) {
+            if !top_level.contains(&package) {
+                top_level.push(package);
+            }
+        }
+    }
+    Ok(Some(InstalledDistribution {
+        name: normalize_distribution_name(name),
+        version: metadata
+            .get("Version")
+            .and_then(|versions| versions.first())
+            .cloned(),
+        files,
+        top_level,
+        requires: metadata
+            .get("Requires-Dist")
+            .into_iter()
+            .flatten()
+            .filter(|requirement| !is_extra_requirement(requirement))
+            .filter_map(|requirement| parse_requirement(requirement))
+            .map(|(name, _)| name)
+            .collect(),
+    }))
+}
+
+/// Parses the header fields of a distribution's METADATA, which may repeat such as
+/// `Requires-Dist`, ending at the blank line before the description body.
+fn parse_metadata(metadata: &str) -> HashMap<String, Vec<String>> {
+    let mut fields: HashMap<String, Vec<String>> = HashMap::new();
+    for line in metadata.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((field, value)) = line.split_once(':') {
+            if !field.starts_with(char::is_whitespace) {
+                fields
+                    .entry(field.to_string())
+                    .or_default()
+                    .push(value.trim().to_string());
+            }
+        }
+    }
+    fields
+}
+
+/// Parses the file paths of a RECORD's `path,hash,size` rows that are installed within
+/// site-packages, excluding files installed outside of it, such as scripts in the virtualenv's
+/// bin dir, and bytecode caches.
+fn parse_record(record: &str) -> Vec<PathBuf> {
+    record
+        .lines()
+        .filter_map(|row| {
+            let path = match row.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"')?.0,
+                None => row.split(',').next()?,
+            };
+            if path.is_empty()
+                || path.starts_with("..")
+                || path.starts_with('/')
+                || path.split('/').any(|segment| segment == "__pycache__")
+            {
+                None
+            } else {
+                Some(PathBuf::from(path))
+            }
+        })
+        .collect()
+}
+
+/// Top-level package of a distribution file, such as `data_lib` of `data_lib/__init__.py` or
+/// the module of `data_lib.cpython-313-x86_64-linux-gnu.so`.
+fn top_level_package(file: &Path) -> Option<String> {
+    let first = file.components().next()?.as_os_str().to_string_lossy();
+    if file.components().count() > 1 {
+        if first.ends_with(".dist-info") || first.ends_with(".data") {
+            None
+        } else {
+            Some(first.to_string())
+        }
+    } else {
+        let (module, ext) = first.split_once('.')?;
+        match ext.rsplit('.').next()? {
+            "py" | "so" | "pyd" => Some(module.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a `Requires-Dist` requirement is only installed with an extra.
+fn is_extra_requirement(requirement: &str) -> bool {
+    requirement
+        .split_once(';')
+        .is_some_and(|(_, marker)| marker.contains("extra"))
+}
@@ -0,0 +1,190 @@
+use crate::runtime::python::dependencies::SitePackages;
+use crate::runtime::python::python_config::PythonRequirements;
+use crate::{FnSource, ModuleImport};
+use std::fs;
+use std::path::{Path, PathBuf};
+use temp_dir::TempDir;
+
+const SITE_PACKAGES: &str = ".venv/lib/python3.13/site-packages";
+
+fn write_distribution(project_dir: &Path, name: &str, requires: &[&str], files: &[&str]) {
+    let site_packages = project_dir.join(SITE_PACKAGES);
+    let dist_info = format!("{}-1.0.0.dist-info", name.replace('-', "_"));
+    fs::create_dir_all(site_packages.join(&dist_info)).unwrap();
+    let mut metadata = format!("Metadata-Version: 2.1\nName: {name}\nVersion: 1.0.0\n");
+    for requirement in requires {
+        metadata.push_str(&format!("Requires-Dist: {requirement}\n"));
+    }
+    metadata.push_str("\nDescription mentioning Requires-Dist: not-a-requirement\n");
+    fs::write(site_packages.join(&dist_info).join("METADATA"), metadata).unwrap();
+    let mut record = String::new();
+    for file in files {
+        let path = site_packages.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+        record.push_str(&format!("{file},sha256=,0\n"));
+    }
+    record.push_str(&format!("{dist_info}/METADATA,,\n{dist_info}/RECORD,,\n"));
+    record.push_str("../../../bin/data-cli,,\n");
+    fs::write(site_packages.join(&dist_info).join("RECORD"), record).unwrap();
+}
+
+fn importing_source(packages: &[&str]) -> FnSource {
+    FnSource {
+        imports: packages
+            .iter()
+            .map(|package| ModuleImport::PackageDependency {
+                package: package.to_string(),
+                subpath: None,
+                path: None,
+            })
+            .collect(),
+        path: PathBuf::from("routes/data/lambda.py"),
+    }
+}
+
+#[test]
+fn test_read_site_packages_without_virtualenv() {
+    let temp_dir = TempDir::new().unwrap();
+    assert!(SitePackages::read_site_packages(temp_dir.path())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_read_site_packages_reads_dist_info() {
+    let temp_dir = TempDir::new().unwrap();
+    write_distribution(
+        temp_dir.path(),
+        "Data_Lib",
+        &["format-lib>=1.0", "test-lib ; extra == \"test\""],
+        &[
+            "data_lib/__init__.py",
+            "data_lib/__pycache__/__init__.cpython-313.pyc",
+            "data_ext.cpython-313-x86_64-linux-gnu.so",
+        ],
+    );
+    let site_packages = SitePackages::read_site_packages(temp_dir.path())
+        .unwrap()
+        .unwrap();
+    assert_eq!(site_packages.dir, PathBuf::from(SITE_PACKAGES));
+    let distribution =
+        site_packages.distributions_of("data_ext", &PythonRequirements::default())[0];
+    assert_eq!(distribution.name, "data-lib");
+    assert_eq!(distribution.version.as_deref(), Some("1.0.0"));
+    assert_eq!(distribution.top_level, vec!["data_lib", "data_ext"]);
+    assert_eq!(distribution.requires, vec!["format-lib"]);
+    assert_eq!(
+        distribution.files,
+        vec![
+            PathBuf::from("data_lib/__init__.py"),
+            PathBuf::from("data_ext.cpython-313-x86_64-linux-gnu.so"),
+            PathBuf::from("Data_Lib-1.0.0.dist-info/METADATA"),
+            PathBuf::from("Data_Lib-1.0.0.dist-info/RECORD"),
+        ]
+    );
+}
+
+#[test]
+fn test_resolve_distributions_includes_required_distributions() {
+    let temp_dir = TempDir::new().unwrap();
+    write_distribution(
+        temp_dir.path(),
+        "data-lib",
+        &[
+            "format-lib",
+            "not-installed",
+            "test-lib ; extra == \"test\"",
+        ],
+        &["data_lib/__init__.py"],
+    );
+    write_distribution(temp_dir.path(), "format-lib", &[], &["format_lib.py"]);
+    write_distribution(temp_dir.path(), "test-lib", &[], &["test_lib.py"]);
+    write_distribution(temp_dir.path(), "unused-lib", &[], &["unused_lib.py"]);
+    let site_packages = SitePackages::read_site_packages(temp_dir.path())
+        .unwrap()
+        .unwrap();
+    let resolved: Vec<&str> = site_packages
+        .resolve_distributions(
+            &[importing_source(&["data_lib", "boto3"])],
+            &PythonRequirements::default(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|distribution| distribution.name.as_str())
+        .collect();
+    assert_eq!(resolved, vec!["data-lib", "format-lib"]);
+}
+
+#[test]
+fn test_resolve_distributions_errors_for_uninstalled_package() {
+    let temp_dir = TempDir::new().unwrap();
+    write_distribution(temp_dir.path(), "data-lib", &[], &["data_lib/__init__.py"]);
+    let site_packages = SitePackages::read_site_packages(temp_dir.path())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        site_packages
+            .resolve_distributions(
+                &[importing_source(&["data_lib", "not_installed"])],
+                &PythonRequirements::default(),
+            )
+            .unwrap_err(),
+        "not_installed"
+    );
+}
+
+#[test]
+fn test_distributions_of_namespace_package_narrowed_by_requirements() {
+    let temp_dir = TempDir::new().unwrap();
+    write_distribution(
+        temp_dir.path(),
+        "google-api-core",
+        &[],
+        &["google/api_core/__init__.py"],
+    );
+    write_distribution(
+        temp_dir.path(),
+        "google-auth",
+        &[],
+        &["google/auth/__init__.py"],
+    );
+    write_distribution(
+        temp_dir.path(),
+        "google-cloud-storage",
+        &["google-api-core"],
+        &["google/cloud/storage/__init__.py"],
+    );
+    let site_packages = SitePackages::read_site_packages(temp_dir.path())
+        .unwrap()
+        .unwrap();
+    let distribution_names = |requirements: &PythonRequirements| -> Vec<String> {
+        site_packages
+            .distributions_of("google", requirements)
+            .into_iter()
+            .map(|distribution| distribution.name.clone())
+            .collect()
+    };
+    assert_eq!(
+        distribution_names(&PythonRequirements::default()),
+        vec!["google-api-core", "google-auth", "google-cloud-storage"]
+    );
+
+    fs::write(
+        temp_dir.child("requirements.txt"),
+        "google-cloud-storage==2.0.0\n",
+    )
+    .unwrap();
+    let requirements = PythonRequirements::read_requirements(temp_dir.path()).unwrap();
+    assert_eq!(
+        distribution_names(&requirements),
+        vec!["google-cloud-storage"]
+    );
+    let resolved: Vec<&str> = site_packages
+        .resolve_distributions(&[importing_source(&["google"])], &requirements)
+        .unwrap()
+        .into_iter()
+        .map(|distribution| distribution.name.as_str())
+        .collect();
+    assert_eq!(resolved, vec!["google-api-core", "google-cloud-storage"]);
+}
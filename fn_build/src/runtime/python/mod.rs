@@ -1,6 +1,15 @@
 mod build_python_fn;
+mod dependencies;
 mod parse_python_fn;
+mod python_config;
+mod stdlib;
 
 pub use build_python_fn::build_python_fn;
 pub use parse_python_fn::parse_python_entrypoint;
 pub use parse_python_fn::parse_python_fn;
+pub use python_config::PythonConfigError;
+
+#[cfg(test)]
+mod dependencies_test;
+#[cfg(test)]
+mod python_config_test;
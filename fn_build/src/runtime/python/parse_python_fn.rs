@@ -1,17 +1,20 @@
-use crate::paths::join_file_paths;
 use crate::runtime::parse_fn::parse_fn_inner;
-use crate::runtime::{FnSourceParser, ImportResolver};
+use crate::runtime::python::dependencies::SitePackages;
+use crate::runtime::python::python_config::PythonRequirements;
+use crate::runtime::python::stdlib::{is_python_stdlib_module, is_removed_python_stdlib_module};
+use crate::runtime::{FnSourceParser, Runtime};
 use crate::{
-    FnEntrypoint, FnParseError, FnParseManifest, FnParseResult, FnParseSpec, FnSource, ModuleImport,
+    FnEntrypoint, FnHandler, FnParseManifest, FnParseResult, FnParseSpec, FnSource, ModuleImport,
 };
+use l3_ast_parse::python::{collect_python_handlers, parse_python_source};
+use l3_fn_config::PythonVersion;
+use rustpython_parser::ast;
 use rustpython_parser::ast::Stmt;
-use rustpython_parser::{ast, Parse, ParseError};
-use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 pub async fn parse_python_entrypoint(parse_spec: FnParseSpec) -> FnParseResult<FnEntrypoint> {
-    let source_parser = PythonSourceParser::new();
+    let source_parser = PythonSourceParser::new(&parse_spec.runtime);
     let handlers =
         source_parser.collect_handlers(&parse_spec.project_dir, &parse_spec.entrypoint)?;
     Ok(FnEntrypoint {
@@ -21,58 +24,130 @@ pub async fn parse_python_entrypoint(parse_spec: FnParseSpec) -> FnParseResult<F
 }
 
 pub async fn parse_python_fn(parse_spec: FnParseSpec) -> FnParseResult<FnParseManifest> {
-    parse_fn_inner(&parse_spec, Arc::new(Box::new(PythonSourceParser::new()))).await
+    let source_parser = PythonSourceParser::new(&parse_spec.runtime)
+        .read_project_dependencies(&parse_spec.project_dir)?;
+    parse_fn_inner(&parse_spec, Arc::new(Box::new(source_parser))).await
 }
 
-impl From<ParseError> for FnParseError {
-    fn from(err: ParseError) -> Self {
-        // todo map rustpython_parser::ParseError diagnostics to a public API type
-        dbg!(err);
-        FnParseError::SyntaxError
-    }
+/// Whether a handler fn of a function's entrypoint is a coroutine function declared with
+/// `async def`.
+pub(super) fn is_async_handler(
+    project_dir: &Path,
+    entrypoint: &Path,
+    handler_fn_name: &str,
+) -> FnParseResult<bool> {
+    let ast = PythonSourceParser::parse_ast(project_dir, entrypoint)?;
+    Ok(ast.iter().any(|stmt| match stmt {
+        Stmt::AsyncFunctionDef(function) => function.name.as_str() == handler_fn_name,
+        _ => false,
+    }))
 }
 
 struct PythonSourceParser {
     import_resolver: PythonImportResolver,
+    requirements: PythonRequirements,
+    site_packages: Option<SitePackages>,
 }
 
 impl PythonSourceParser {
-    fn new() -> Self {
+    fn new(runtime: &Runtime) -> Self {
+        let python_version = match runtime {
+            Runtime::Python(Some(python_version)) => python_version.clone(),
+            _ => PythonVersion::ThreeFourteen,
+        };
         Self {
-            import_resolver: PythonImportResolver {},
+            import_resolver: PythonImportResolver { python_version },
+            requirements: Default::default(),
+            site_packages: None,
         }
     }
 
+    /// Reads the project's declared requirements and installed distributions to provide the
+    /// versions of imported packages.
+    fn read_project_dependencies(mut self, project_dir: &Path) -> FnParseResult<Self> {
+        self.requirements = PythonRequirements::read_requirements(project_dir)?;
+        self.site_packages = SitePackages::read_site_packages(project_dir)?;
+        Ok(self)
+    }
+
     fn parse_ast(project_dir: &Path, path: &Path) -> FnParseResult<Vec<Stmt>> {
-        let abs_path = project_dir.join(path);
-        let python_code = fs::read_to_string(abs_path)?;
-        let ast = ast::Suite::parse(&python_code, &path.to_string_lossy())?;
-        Ok(ast)
+        Ok(parse_python_source(&project_dir.join(path))?.1)
     }
 
     fn collect_imports(
         &self,
         project_dir: &Path,
         source_path: &Path,
-        ast: &Vec<Stmt>,
+        ast: &[Stmt],
     ) -> Vec<ModuleImport> {
-        let mut import_specifiers: Vec<String> = Vec::new();
-        for stmt in ast {
-            match stmt {
-                Stmt::Import(import) => {
-                    import_specifiers.push(import.names.first().cloned().unwrap().name.to_string())
+        let mut import_stmts: Vec<&Stmt> = Vec::new();
+        collect_import_stmts(ast, &mut import_stmts);
+        let mut imports: Vec<ModuleImport> = Vec::new();
+        for stmt in import_stmts {
+            let resolved = match stmt {
+                Stmt::Import(import) => import
+                    .names
+                    .iter()
+                    .flat_map(|alias| {
+                        self.import_resolver.resolve_import(
+                            project_dir,
+                            source_path,
+                            0,
+                            Some(alias.name.as_str()),
+                            &[],
+                        )
+                    })
+                    .collect(),
+                Stmt::ImportFrom(import_from) => {
+                    let names: Vec<&str> = import_from
+                        .names
+                        .iter()
+                        .map(|alias| alias.name.as_str())
+                        .collect();
+                    self.import_resolver.resolve_import(
+                        project_dir,
+                        source_path,
+                        import_from.level.map(|level| level.to_u32()).unwrap_or(0),
+                        import_from.module.as_ref().map(|module| module.as_str()),
+                        &names,
+                    )
+                }
+                _ => Vec::new(),
+            };
+            for import in resolved {
+                if !imports.contains(&import) {
+                    imports.push(import);
                 }
-                Stmt::ImportFrom(_import_from) => {}
-                _ => {}
             }
         }
-        import_specifiers
-            .iter()
-            .map(|specifier| {
-                self.import_resolver
-                    .resolve(project_dir, source_path, specifier.as_str())
-            })
-            .collect()
+        imports
+    }
+}
+
+/// Collects import statements from a module's body and the bodies of its compound statements.
+fn collect_import_stmts<'a>(stmts: &'a [Stmt], import_stmts: &mut Vec<&'a Stmt>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Import(_) | Stmt::ImportFrom(_) => import_stmts.push(stmt),
+            Stmt::FunctionDef(function) => collect_import_stmts(&function.body, import_stmts),
+            Stmt::AsyncFunctionDef(function) => collect_import_stmts(&function.body, import_stmts),
+            Stmt::ClassDef(class) => collect_import_stmts(&class.body, import_stmts),
+            Stmt::If(stmt_if) => {
+                collect_import_stmts(&stmt_if.body, import_stmts);
+                collect_import_stmts(&stmt_if.orelse, import_stmts);
+            }
+            Stmt::With(with) => collect_import_stmts(&with.body, import_stmts),
+            Stmt::Try(stmt_try) => {
+                collect_import_stmts(&stmt_try.body, import_stmts);
+                for handler in &stmt_try.handlers {
+                    let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                    collect_import_stmts(&handler.body, import_stmts);
+                }
+                collect_import_stmts(&stmt_try.orelse, import_stmts);
+                collect_import_stmts(&stmt_try.finalbody, import_stmts);
+            }
+            _ => {}
+        }
     }
 }
 
@@ -81,31 +156,46 @@ impl FnSourceParser for PythonSourceParser {
         &self,
         project_dir: &Path,
         source_path: &Path,
-    ) -> FnParseResult<Vec<String>> {
-        let ast = Self::parse_ast(project_dir, source_path)?;
-        let mut handlers: Vec<String> = Vec::new();
-        for stmt in ast {
-            match stmt {
-                Stmt::FunctionDef(function) => handlers.push(function.name.to_string()),
-                Stmt::AsyncFunctionDef(_) => todo!("to support python async functions as handlers, build_python_fn will have to generate code for a non async handler that launches the async python function with the python async runtime"),
-                _ => {}
-            }
-        }
-        Ok(handlers)
+    ) -> FnParseResult<Vec<FnHandler>> {
+        let (python_code, ast) = parse_python_source(&project_dir.join(source_path))?;
+        Ok(collect_python_handlers(&python_code, &ast))
     }
 
     fn collect_runtime_sources(&self, _project_dir: &Path) -> Vec<FnSource> {
         Vec::new()
     }
 
+    fn package_version(&self, package: &str) -> Option<String> {
+        // an imported package's name may differ from the names of its distributions
+        self.site_packages
+            .iter()
+            .flat_map(|site_packages| site_packages.distributions_of(package, &self.requirements))
+            .find_map(|distribution| self.requirements.dependency_version(&distribution.name))
+            .or_else(|| self.requirements.dependency_version(package))
+            .cloned()
+    }
+
     fn parse_fn_entrypoint(
         &self,
         project_dir: &Path,
         path: PathBuf,
-    ) -> FnParseResult<(FnSource, Vec<String>)> {
+    ) -> FnParseResult<(FnSource, Vec<FnHandler>)> {
         let ast = Self::parse_ast(project_dir, &path)?;
         let handlers = self.collect_handlers(project_dir, &path)?;
-        let imports = self.collect_imports(project_dir, &path, &ast);
+        // Lambda imports the packages of a handler's module before the module itself
+        let mut imports: Vec<ModuleImport> = path
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("__init__.py"))
+            .filter(|init| project_dir.join(init).is_file())
+            .map(ModuleImport::RelativeSource)
+            .collect();
+        imports.reverse();
+        for import in self.collect_imports(project_dir, &path, &ast) {
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
         Ok((FnSource { imports, path }, handlers))
     }
 
@@ -116,16 +206,124 @@ impl FnSourceParser for PythonSourceParser {
     }
 }
 
-struct PythonImportResolver {}
-
-impl ImportResolver for PythonImportResolver {
-    fn resolve(&self, project_dir: &Path, from: &Path, import: &str) -> ModuleImport {
-        dbg!(project_dir);
-        dbg!(from);
-        dbg!(import);
-        ModuleImport::RelativeSource(join_file_paths(
-            from,
-            &PathBuf::from(format!("./{import}.py")),
-        ))
+struct PythonImportResolver {
+    python_version: PythonVersion,
+}
+
+impl PythonImportResolver {
+    /// Resolves the sources of an `import` or `from ... import` statement. Packages resolve to
+    /// their `__init__.py` sources and the names of a `from` import resolve to submodules of a
+    /// package. Absolute imports only resolve from the project root, as the Lambda task root is
+    /// on `sys.path` and the importing source's dir is not. An absolute import of a module in the
+    /// importing source's dir is unknown, since it would fail to import in the Lambda runtime.
+    ///
+    /// Absolute imports that do not resolve to project sources are standard library modules of
+    /// the targeted Python version, which are not imported as sources or dependencies, or
+    /// otherwise package dependencies.
+    fn resolve_import(
+        &self,
+        project_dir: &Path,
+        from: &Path,
+        level: u32,
+        module: Option<&str>,
+        names: &[&str],
+    ) -> Vec<ModuleImport> {
+        let from_dir = from.parent().unwrap_or(Path::new(""));
+        let base_dirs: Vec<PathBuf> = if level == 0 {
+            vec![PathBuf::new()]
+        } else {
+            let mut base_dir = Some(from_dir);
+            for _ in 1..level {
+                base_dir = base_dir.and_then(Path::parent);
+            }
+            base_dir.map(Path::to_path_buf).into_iter().collect()
+        };
+        let segments: Vec<&str> = module
+            .map(|module| module.split('.').collect())
+            .unwrap_or_default();
+        for base_dir in base_dirs {
+            if let Some((mut sources, package_dir)) =
+                resolve_module(project_dir, &base_dir, &segments)
+            {
+                if let Some(package_dir) = package_dir {
+                    for name in names {
+                        if let Some((submodule_sources, _)) =
+                            resolve_module(project_dir, &package_dir, &[name])
+                        {
+                            sources.extend(submodule_sources);
+                        }
+                    }
+                }
+                // a namespace package without sources is ambiguous with a dir of any name in the
+                // project, so it defers to the stdlib module or package dependency
+                if level == 0 && sources.is_empty() {
+                    continue;
+                }
+                return sources
+                    .into_iter()
+                    .map(ModuleImport::RelativeSource)
+                    .collect();
+            }
+        }
+        if let (0, Some(module)) = (level, module) {
+            if !from_dir.as_os_str().is_empty()
+                && resolve_module(project_dir, from_dir, &segments)
+                    .is_some_and(|(sources, _)| !sources.is_empty())
+            {
+                return vec![ModuleImport::Unknown(module.to_string())];
+            }
+            let (package, subpath) = match module.split_once('.') {
+                Some((package, subpath)) => (package, Some(subpath.to_string())),
+                None => (module, None),
+            };
+            if is_python_stdlib_module(&self.python_version, package) {
+                return Vec::new();
+            } else if !is_removed_python_stdlib_module(&self.python_version, package) {
+                return vec![ModuleImport::PackageDependency {
+                    package: package.to_string(),
+                    subpath,
+                    path: None,
+                }];
+            }
+        }
+        vec![ModuleImport::Unknown(format!(
+            "{}{}",
+            ".".repeat(level as usize),
+            module.unwrap_or_default()
+        ))]
+    }
+}
+
+/// Resolves the sources of a module path's packages and module from a base dir, and the dir of
+/// the module if it is a package.
+fn resolve_module(
+    project_dir: &Path,
+    base_dir: &Path,
+    segments: &[&str],
+) -> Option<(Vec<PathBuf>, Option<PathBuf>)> {
+    let mut sources = Vec::new();
+    let mut dir = base_dir.to_path_buf();
+    for (i, segment) in segments.iter().enumerate() {
+        let package_dir = dir.join(segment);
+        let init = package_dir.join("__init__.py");
+        let module = dir.join(format!("{segment}.py"));
+        // like Python's path finder, a regular package precedes a module of the same name and a
+        // module precedes a namespace package
+        if project_dir.join(&init).is_file() {
+            sources.push(init);
+        } else if i == segments.len() - 1 && project_dir.join(&module).is_file() {
+            sources.push(module);
+            return Some((sources, None));
+        } else if !project_dir.join(&package_dir).is_dir() {
+            return None;
+        }
+        dir = package_dir;
+    }
+    if segments.is_empty() {
+        let init = dir.join("__init__.py");
+        if project_dir.join(&init).is_file() {
+            sources.push(init);
+        }
     }
+    Some((sources, Some(dir)))
 }
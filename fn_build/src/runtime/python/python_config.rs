@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::{fs, io};
+
+/// Errors that occur when reading a Python project's requirements and installed distributions.
+#[derive(Debug, thiserror::Error)]
+#[error("error reading python requirements: {0}")]
+pub enum PythonConfigError {
+    IoRead(#[from] io::Error),
+    TomlParse(#[from] toml::de::Error),
+}
+
+/// Version requirements of a Python project's dependencies keyed by normalized distribution name.
+///
+/// Requirements are declared by pyproject.toml's PEP 621 `project.dependencies` or, without a
+/// pyproject.toml, by requirements.txt. Versions pinned by a uv.lock or poetry.lock take
+/// precedence over declared requirements.
+#[derive(Debug, Default, PartialEq)]
+pub struct PythonRequirements {
+    /// Normalized names of the distributions declared by pyproject.toml or requirements.txt.
+    declared: HashSet<String>,
+    dependencies: HashMap<String, String>,
+}
+
+impl PythonRequirements {
+    pub fn read_requirements(project_dir: &Path) -> Result<Self, PythonConfigError> {
+        let mut dependencies = HashMap::new();
+        let mut declared_names = HashSet::new();
+        let pyproject_toml_p = project_dir.join("pyproject.toml");
+        let requirements_txt_p = project_dir.join("requirements.txt");
+        let declared = if pyproject_toml_p.is_file() {
+            parse_pyproject_toml(&fs::read_to_string(pyproject_toml_p)?)?
+        } else if requirements_txt_p.is_file() {
+            parse_requirements_txt(&fs::read_to_string(requirements_txt_p)?)
+        } else {
+            Vec::new()
+        };
+        for (name, specifier) in declared {
+            if let Some(specifier) = specifier {
+                dependencies.insert(name.clone(), specifier);
+            }
+            declared_names.insert(name);
+        }
+        for lock_file in ["uv.lock", "poetry.lock"] {
+            let lock_file_p = project_dir.join(lock_file);
+            if lock_file_p.is_file() {
+                for (name, version) in parse_lock_file(&fs::read_to_string(lock_file_p)?)? {
+                    dependencies.insert(name, format!("=={version}"));
+                }
+                break;
+            }
+        }
+        Ok(Self {
+            declared: declared_names,
+            dependencies,
+        })
+    }
+
+    /// Whether a distribution is declared by pyproject.toml or requirements.txt.
+    pub fn is_declared(&self, distribution: &str) -> bool {
+        self.declared
+            .contains(&normalize_distribution_name(distribution))
+    }
+
+    /// Version requirement of a distribution by its name in any of its equivalent spellings.
+    pub fn dependency_version(&self, distribution: &str) -> Option<&String> {
+        self.dependencies
+            .get(&normalize_distribution_name(distribution))
+    }
+}
+
+/// Normalizes a distribution name per PEP 503 so `Data_Lib` and `data-lib` are equivalent.
+pub fn normalize_distribution_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut separator = false;
+    for c in name.chars() {
+        if c == '-' || c == '_' || c == '.' {
+            separator = true;
+        } else {
+            if separator && !normalized.is_empty() {
+                normalized.push('-');
+            }
+            separator = false;
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Parses the distribution name and version specifier of a PEP 508 requirement, such as
+/// `data-lib[extra] >=1.0 ; python_version >= "3.12"`, without its extras or environment marker.
+pub fn parse_requirement(requirement: &str) -> Option<(String, Option<String>)> {
+    let requirement = requirement
+        .split_once(';')
+        .map(|(requirement, _)| requirement)
+        .unwrap_or(requirement)
+        .trim();
+    let name_len = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    if name_len == 0 {
+        return None;
+    }
+    let mut specifier = requirement[name_len..].trim_start();
+    if specifier.starts_with('[') {
+        specifier = specifier
+            .split_once(']')
+            .map(|(_, specifier)| specifier.trim_start())
+            .unwrap_or_default();
+    }
+    let specifier = specifier
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    Some((
+        normalize_distribution_name(&requirement[..name_len]),
+        if specifier.is_empty() {
+            None
+        } else {
+            Some(specifier.to_string())
+        },
+    ))
+}
+
+fn parse_pyproject_toml(
+    pyproject_toml: &str,
+) -> Result<Vec<(String, Option<String>)>, PythonConfigError> {
+    let pyproject = pyproject_toml.parse::<toml::Table>()?;
+    Ok(pyproject
+        .get("project")
+        .and_then(|project| project.get("dependencies"))
+        .and_then(|dependencies| dependencies.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|requirement| requirement.as_str())
+        .filter_map(parse_requirement)
+        .collect())
+}
+
+fn parse_requirements_txt(requirements_txt: &str) -> Vec<(String, Option<String>)> {
+    requirements_txt
+        .lines()
+        .map(|line| line.split_once('#').map(|(line, _)| line).unwrap_or(line))
+        .map(str::trim)
+        // options such as `-r other.txt` and `--index-url` are not requirements
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(parse_requirement)
+        .collect()
+}
+
+/// Parses the pinned versions of a uv.lock or poetry.lock, which both list `[[package]]` tables
+/// with a `name` and `version`.
+fn parse_lock_file(lock_file: &str) -> Result<Vec<(String, String)>, PythonConfigError> {
+    let lock = lock_file.parse::<toml::Table>()?;
+    Ok(lock
+        .get("package")
+        .and_then(|packages| packages.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|package| {
+            Some((
+                normalize_distribution_name(package.get("name")?.as_str()?),
+                package.get("version")?.as_str()?.to_string(),
+            ))
+        })
+        .collect())
+}
@@ -0,0 +1,113 @@
+use crate::runtime::python::python_config::{
+    normalize_distribution_name, parse_requirement, PythonRequirements,
+};
+use std::fs;
+use temp_dir::TempDir;
+
+#[test]
+fn test_normalize_distribution_name() {
+    for name in [
+        "data-lib",
+        "Data_Lib",
+        "data.lib",
+        "data__lib",
+        "DATA-_.LIB",
+    ] {
+        assert_eq!(normalize_distribution_name(name), "data-lib");
+    }
+}
+
+#[test]
+fn test_parse_requirement() {
+    assert_eq!(
+        parse_requirement("requests"),
+        Some(("requests".into(), None))
+    );
+    assert_eq!(
+        parse_requirement("Data_Lib[extra] >=1.0,<2 ; python_version >= \"3.12\""),
+        Some(("data-lib".into(), Some(">=1.0,<2".into())))
+    );
+    assert_eq!(
+        parse_requirement("data-lib (==1.0.0)"),
+        Some(("data-lib".into(), Some("==1.0.0".into())))
+    );
+    assert_eq!(parse_requirement(">=1.0"), None);
+}
+
+#[test]
+fn test_read_requirements_from_requirements_txt() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.child("requirements.txt"),
+        "# data\n-r other.txt\ndata-lib>=1.0 # comment\nformat_lib==2.0.0\nunpinned\n",
+    )
+    .unwrap();
+    let requirements = PythonRequirements::read_requirements(temp_dir.path()).unwrap();
+    assert_eq!(
+        requirements.dependency_version("data_lib"),
+        Some(&">=1.0".to_string())
+    );
+    assert_eq!(
+        requirements.dependency_version("format-lib"),
+        Some(&"==2.0.0".to_string())
+    );
+    assert_eq!(requirements.dependency_version("unpinned"), None);
+}
+
+#[test]
+fn test_read_requirements_prefers_pyproject_toml() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("requirements.txt"), "data-lib>=1.0").unwrap();
+    fs::write(
+        temp_dir.child("pyproject.toml"),
+        r#"
+[project]
+name = "data"
+dependencies = ["data-lib>=2.0"]
+"#,
+    )
+    .unwrap();
+    let requirements = PythonRequirements::read_requirements(temp_dir.path()).unwrap();
+    assert_eq!(
+        requirements.dependency_version("data-lib"),
+        Some(&">=2.0".to_string())
+    );
+}
+
+#[test]
+fn test_read_requirements_pins_locked_versions() {
+    for lock_file in ["uv.lock", "poetry.lock"] {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.child("pyproject.toml"),
+            r#"
+[project]
+name = "data"
+dependencies = ["data-lib>=2.0"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.child(lock_file),
+            r#"
+[[package]]
+name = "data-lib"
+version = "2.1.0"
+
+[[package]]
+name = "Format_Lib"
+version = "1.0.0"
+"#,
+        )
+        .unwrap();
+        let requirements = PythonRequirements::read_requirements(temp_dir.path()).unwrap();
+        assert_eq!(
+            requirements.dependency_version("data-lib"),
+            Some(&"==2.1.0".to_string())
+        );
+        assert_eq!(
+            requirements.dependency_version("format-lib"),
+            Some(&"==1.0.0".to_string())
+        );
+    }
+}
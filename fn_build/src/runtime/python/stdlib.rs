@@ -1,17 +1,22 @@
-use crate::runtime::python::PythonVersion;
+use l3_fn_config::PythonVersion;
 
 // todo `optparse` is deprecated and yet to be removed
 // todo make fun of python3 docs page being built with table layouts
 
 pub fn is_python_stdlib_module(version: &PythonVersion, specifier: &str) -> bool {
     is_pre_python3_12_stdlib_module(specifier)
-        && match version {
-            PythonVersion::Python3_14 | PythonVersion::Python3_13 => {
-                !is_removed_in_python3_13(specifier)
-            }
-            PythonVersion::Python3_12 => !is_removed_in_python3_12(specifier),
-            _ => true,
+        && !is_removed_python_stdlib_module(version, specifier)
+}
+
+/// Whether a module of the standard library has been removed from the Python version.
+pub fn is_removed_python_stdlib_module(version: &PythonVersion, specifier: &str) -> bool {
+    match version {
+        PythonVersion::ThreeFourteen | PythonVersion::ThreeThirteen => {
+            is_removed_in_python3_13(specifier)
         }
+        PythonVersion::ThreeTwelve => is_removed_in_python3_12(specifier),
+        PythonVersion::ThreeTen | PythonVersion::ThreeNine => false,
+    }
 }
 
 fn is_removed_in_python3_13(specifier: &str) -> bool {
@@ -1,15 +1,14 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::paths::join_file_paths;
+use crate::paths::{join_file_paths, normalize_path};
 use crate::runtime::ImportResolver;
-use crate::typescript::TsConfigJson;
+use crate::typescript::{TsCompilerOptions, TsConfigJson, TsPathAlias};
 use crate::ModuleImport;
+use l3_ast_parse::swc::visitors::ImportKind;
 
-// todo check ts path aliases
 pub struct TypeScriptImportResolver {
     runtime: Box<dyn ImportResolver>,
-    #[allow(dead_code)]
     tsconfig: Arc<TsConfigJson>,
 }
 
@@ -25,22 +24,97 @@ impl TypeScriptImportResolver {
         import: &str,
     ) -> Option<PathBuf> {
         debug_assert!(import.starts_with('.'));
-        let path = join_file_paths(from, &PathBuf::from(import)).with_extension("ts");
-        if project_dir.join(&path).is_file() {
-            Some(path)
-        } else {
-            None
-        }
+        let path = join_file_paths(from, &PathBuf::from(import));
+        [path.with_extension("ts"), path.with_extension("tsx")]
+            .into_iter()
+            .find(|path| project_dir.join(path).is_file())
     }
 }
 
 impl ImportResolver for TypeScriptImportResolver {
-    fn resolve(&self, project_dir: &Path, from: &Path, import: &str) -> ModuleImport {
+    fn resolve(
+        &self,
+        project_dir: &Path,
+        from: &Path,
+        import: &str,
+        kind: ImportKind,
+    ) -> ModuleImport {
         if import.starts_with('.') {
             if let Some(resolved) = self.resolve_relative_path(project_dir, from, import) {
                 return ModuleImport::RelativeSource(resolved);
             }
+        } else if !import.starts_with('#') {
+            if let Some(resolved) =
+                resolve_ts_path_alias(&self.tsconfig.compiler, project_dir, import)
+            {
+                return ModuleImport::RelativeSource(resolved);
+            }
         }
-        self.runtime.resolve(project_dir, from, import)
+        self.runtime.resolve(project_dir, from, import, kind)
+    }
+}
+
+/// Resolves a bare import specifier to a project source by the compilerOptions.paths alias
+/// with the longest prefix matching the import or, if no alias matches, by compilerOptions.baseUrl.
+pub fn resolve_ts_path_alias(
+    compiler: &TsCompilerOptions,
+    project_dir: &Path,
+    import: &str,
+) -> Option<PathBuf> {
+    if let Some((alias, substitution)) = match_path_alias(&compiler.paths, import) {
+        // an alias that does not resolve falls back to node_modules, not baseUrl
+        return alias.targets.iter().find_map(|target| {
+            resolve_ts_source(project_dir, Path::new(&target.replace('*', substitution)))
+        });
+    }
+    let base_url = compiler.base_url.as_ref()?;
+    resolve_ts_source(project_dir, &normalize_path(&base_url.join(import)))
+}
+
+/// Matches an import to an alias of the exact pattern or else the wildcard pattern with the
+/// longest prefix, with the substitution of the wildcard.
+fn match_path_alias<'a, 'i>(
+    paths: &'a [TsPathAlias],
+    import: &'i str,
+) -> Option<(&'a TsPathAlias, &'i str)> {
+    if let Some(alias) = paths.iter().find(|alias| alias.pattern == import) {
+        return Some((alias, ""));
     }
+    paths
+        .iter()
+        .filter_map(|alias| {
+            let (before, after) = alias.pattern.split_once('*')?;
+            if import.len() < before.len() + after.len() {
+                return None;
+            }
+            let substitution = import.strip_prefix(before)?.strip_suffix(after)?;
+            Some((before.len(), alias, substitution))
+        })
+        .max_by_key(|(prefix_len, _, _)| *prefix_len)
+        .map(|(_, alias, substitution)| (alias, substitution))
+}
+
+/// Resolves a project relative path of an aliased import to a TS source, or a JS source, with
+/// TypeScript's extension and index file lookups.
+fn resolve_ts_source(project_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let with_ext = |ext: &str| PathBuf::from(format!("{}.{ext}", path.to_string_lossy()));
+    let candidates = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts") | Some("tsx") => vec![path.to_path_buf()],
+        Some("js") => vec![
+            path.with_extension("ts"),
+            path.with_extension("tsx"),
+            path.to_path_buf(),
+        ],
+        _ => vec![
+            with_ext("ts"),
+            with_ext("tsx"),
+            path.join("index.ts"),
+            path.join("index.tsx"),
+            with_ext("js"),
+            path.join("index.js"),
+        ],
+    };
+    candidates
+        .into_iter()
+        .find(|candidate| project_dir.join(candidate).is_file())
 }
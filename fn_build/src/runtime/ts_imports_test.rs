@@ -2,6 +2,7 @@ use crate::runtime::node::NodeConfig;
 use crate::runtime::ts_imports::TypeScriptImportResolver;
 use crate::runtime::ImportResolver;
 use crate::ModuleImport;
+use l3_ast_parse::swc::visitors::ImportKind;
 use std::{
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
@@ -12,7 +13,13 @@ struct TestFallthrough {
 }
 
 impl ImportResolver for TestFallthrough {
-    fn resolve(&self, _project_dir: &Path, from: &Path, import: &str) -> ModuleImport {
+    fn resolve(
+        &self,
+        _project_dir: &Path,
+        from: &Path,
+        import: &str,
+        _kind: ImportKind,
+    ) -> ModuleImport {
         self.unresolved
             .lock()
             .unwrap()
@@ -52,6 +59,7 @@ fn test_ts_import_resolver_resolves_relative_source() {
             &test.project_dir,
             &PathBuf::from("routes/data/lambda.ts"),
             "../../lib/data.js",
+            ImportKind::Static,
         ),
         ModuleImport::RelativeSource(PathBuf::from("lib/data.ts"))
     );
@@ -66,6 +74,7 @@ fn test_ts_import_resolver_delegates_to_runtime_resolver() {
             &test.project_dir,
             &PathBuf::from("routes/data/lambda.ts"),
             "../../lib/mongodb.js",
+            ImportKind::Static,
         ),
         ModuleImport::Unknown(String::from("../../lib/mongodb.js"))
     );
@@ -75,3 +84,26 @@ fn test_ts_import_resolver_delegates_to_runtime_resolver() {
     assert_eq!(from, &PathBuf::from("routes/data/lambda.ts"));
     assert_eq!(import, &String::from("../../lib/mongodb.js"));
 }
+
+#[test]
+fn test_ts_import_resolver_resolves_path_alias() {
+    let test = create_import_resolver_for_fixture("fixtures/node/ts/path_aliases");
+    assert_eq!(
+        test.import_resolver.resolve(
+            &test.project_dir,
+            &PathBuf::from("routes/data/lambda.ts"),
+            "@lib/data.js",
+            ImportKind::Static,
+        ),
+        ModuleImport::RelativeSource(PathBuf::from("lib/data.ts"))
+    );
+    assert_eq!(
+        test.import_resolver.resolve(
+            &test.project_dir,
+            &PathBuf::from("routes/data/lambda.ts"),
+            "@lib/mongodb",
+            ImportKind::Static,
+        ),
+        ModuleImport::Unknown(String::from("@lib/mongodb"))
+    );
+}
@@ -0,0 +1,984 @@
+use crate::swc::compiler::{es_target, CompileOutput, CompileResult, SwcCompiler};
+use l3_ast_parse::swc::SourceType;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use swc::atoms::Atom;
+use swc::config::SourceMapsConfig;
+use swc::{Compiler, PrintArgs};
+use swc_common::errors::Handler;
+use swc_common::{Mark, SyntaxContext, DUMMY_SP};
+use swc_ecma_ast::{
+    AssignPat, AssignPatProp, CallExpr, Callee, ClassDecl, Decl, DefaultDecl, ExportSpecifier,
+    Expr, FnDecl, Ident, IdentName, ImportNamedSpecifier, ImportSpecifier, KeyValuePatProp,
+    KeyValueProp, Lit, Module, ModuleDecl, ModuleExportName, ModuleItem, ObjectPatProp, Pat,
+    Program, Prop, PropName, Stmt, VarDecl, VarDeclKind, VarDeclarator,
+};
+use swc_ecma_parser::{EsSyntax, Syntax};
+use swc_ecma_transforms_base::resolver;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+/// Resolution of a module's import specifier for bundling.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BundleImport {
+    /// Module inlined into the bundle by its path relative to the project dir.
+    Module(PathBuf),
+    /// Import that is kept as an import of the bundle, such as an npm package.
+    External,
+    /// Import of a source that is not a module of the bundle, which fails the bundle.
+    Unresolved,
+}
+
+/// Provides module sources and import resolution to the bundler.
+pub trait BundleLoader {
+    /// JavaScript of a module, transpiled if the module is TypeScript.
+    fn load(&self, path: &Path) -> CompileResult<String>;
+    /// Resolves an import specifier of the module at `from`.
+    fn resolve(&self, from: &Path, specifier: &str) -> BundleImport;
+    /// Module system of a module. Only ES modules can be linked into the bundle.
+    fn source_type(&self, path: &Path) -> SourceType;
+}
+
+/// Sym of an anonymous default export's binding until it is named by the bundle.
+const ANONYMOUS_DEFAULT: &str = "*default*";
+
+/// Globals referenced by code generated for the bundle.
+const BUNDLE_GLOBALS: [&str; 2] = ["Object", "Promise"];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Target {
+    Module(usize),
+    External(String),
+}
+
+#[derive(Clone, Debug)]
+enum Imported {
+    Named(Atom),
+    Namespace,
+}
+
+#[derive(Clone, Debug)]
+enum ExportSource {
+    Local(Atom),
+    Reexport(Target, Imported),
+}
+
+#[derive(Clone, Debug)]
+enum Resolved {
+    Binding(Atom),
+    Namespace(usize),
+}
+
+struct LinkModule {
+    path: PathBuf,
+    module: Module,
+    top_level_ctxt: SyntaxContext,
+    specifiers: HashMap<String, Target>,
+    /// Modules evaluated before this module, in order of its imports and re-exports.
+    deps: Vec<usize>,
+    /// Modules imported with `import()` that are evaluated with the bundle.
+    dynamic_deps: Vec<usize>,
+    imports: HashMap<Atom, (Target, Imported)>,
+    exports: Vec<(Atom, ExportSource)>,
+    star_exports: Vec<Target>,
+    /// Syms of bindings declared at the module's top-level scope, excluding imports.
+    declared: Vec<Atom>,
+    /// Syms of every identifier in the module.
+    syms: HashSet<Atom>,
+    /// Syms of nested bindings and globals that a hoisted binding of the same name would shadow.
+    inner_syms: HashSet<Atom>,
+}
+
+impl SwcCompiler {
+    /// Links the module graph of an ES module entrypoint into a single ES module by hoisting
+    /// each module's top-level scope into the bundle in evaluation order, renaming bindings
+    /// that would collide and replacing imports with the bindings they import.
+    pub fn bundle(
+        self,
+        entrypoint: &Path,
+        loader: &dyn BundleLoader,
+        source_map: bool,
+    ) -> CompileResult<CompileOutput> {
+        self.with_compiler(entrypoint, |compiler, handler| {
+            let mut linker = Linker::new(compiler, handler);
+            linker.load_modules(entrypoint, loader)?;
+            linker.link(source_map)
+        })
+    }
+}
+
+struct Linker<'a> {
+    compiler: &'a Compiler,
+    handler: &'a Handler,
+    modules: Vec<LinkModule>,
+    /// Final names of each module's top-level bindings and external imports.
+    names: Vec<HashMap<Atom, Atom>>,
+    namespaces: HashMap<usize, Atom>,
+    synthetic_imports: Vec<(String, Imported, Atom)>,
+    reserved: HashSet<Atom>,
+    allocated: HashSet<Atom>,
+}
+
+impl<'a> Linker<'a> {
+    fn new(compiler: &'a Compiler, handler: &'a Handler) -> Self {
+        Self {
+            compiler,
+            handler,
+            modules: Vec::new(),
+            names: Vec::new(),
+            namespaces: HashMap::new(),
+            synthetic_imports: Vec::new(),
+            reserved: HashSet::new(),
+            allocated: BUNDLE_GLOBALS.iter().map(|g| Atom::from(*g)).collect(),
+        }
+    }
+
+    fn parse(&self, path: &Path, js: String, source_type: SourceType) -> anyhow::Result<Module> {
+        // require() calls and module.exports of a CommonJS module are not linked
+        if source_type == SourceType::CommonJs {
+            return Err(commonjs_error(path));
+        }
+        let source_file = self
+            .compiler
+            .cm
+            .new_source_file(Arc::new(path.to_path_buf().into()), js);
+        match self.compiler.parse_js(
+            source_file,
+            self.handler,
+            es_target(),
+            Syntax::Es(EsSyntax::default()),
+            source_type.into(),
+            None,
+        )? {
+            Program::Module(module) => Ok(module),
+            // a detected script is run by Node.js as a CommonJS module
+            Program::Script(_) => Err(commonjs_error(path)),
+        }
+    }
+
+    // walks the module graph from the entrypoint since linking requires each module's import
+    // specifiers resolved by the loader, which also decides the modules that are inlined
+    fn load_modules(&mut self, entrypoint: &Path, loader: &dyn BundleLoader) -> anyhow::Result<()> {
+        let mut indices: HashMap<PathBuf, usize> = HashMap::from([(entrypoint.to_path_buf(), 0)]);
+        let mut loading: VecDeque<PathBuf> = VecDeque::from([entrypoint.to_path_buf()]);
+        while let Some(path) = loading.pop_front() {
+            let js = loader.load(&path)?;
+            let mut program = Program::Module(self.parse(&path, js, loader.source_type(&path))?);
+            let top_level_mark = Mark::new();
+            program.visit_mut_with(&mut resolver(Mark::new(), top_level_mark, false));
+            let module = match program {
+                Program::Module(module) => module,
+                Program::Script(_) => unreachable!(),
+            };
+            let mut specifiers = HashMap::new();
+            let mut unresolved: Option<String> = None;
+            let mut resolve = |specifier: &str| -> Target {
+                let target = match loader.resolve(&path, specifier) {
+                    BundleImport::External => Target::External(specifier.to_string()),
+                    // the bundle errors after the module is analyzed
+                    BundleImport::Unresolved => {
+                        unresolved.get_or_insert_with(|| specifier.to_string());
+                        Target::External(specifier.to_string())
+                    }
+                    BundleImport::Module(module_path) => {
+                        let next = indices.len();
+                        let index = *indices.entry(module_path.clone()).or_insert_with(|| {
+                            loading.push_back(module_path);
+                            next
+                        });
+                        Target::Module(index)
+                    }
+                };
+                specifiers.insert(specifier.to_string(), target.clone());
+                target
+            };
+            let mut link_module = LinkModule {
+                path: path.clone(),
+                top_level_ctxt: SyntaxContext::empty().apply_mark(top_level_mark),
+                specifiers: HashMap::new(),
+                deps: Vec::new(),
+                dynamic_deps: Vec::new(),
+                imports: HashMap::new(),
+                exports: Vec::new(),
+                star_exports: Vec::new(),
+                declared: Vec::new(),
+                syms: HashSet::new(),
+                inner_syms: HashSet::new(),
+                module: Module::default(),
+            };
+            for item in &module.body {
+                analyze_module_item(item, &mut link_module, &mut resolve);
+            }
+            let mut collect_syms = CollectSyms {
+                top_level_ctxt: link_module.top_level_ctxt,
+                syms: HashSet::new(),
+                inner_syms: HashSet::new(),
+                top_level_syms: Vec::new(),
+                dynamic_imports: Vec::new(),
+            };
+            module.visit_with(&mut collect_syms);
+            for specifier in collect_syms.dynamic_imports {
+                if let Target::Module(index) = resolve(&specifier) {
+                    link_module.dynamic_deps.push(index);
+                }
+            }
+            if let Some(specifier) = unresolved {
+                return Err(anyhow::anyhow!(
+                    "could not resolve \"{specifier}\" from {} to a source of the bundle",
+                    path.to_string_lossy()
+                ));
+            }
+            for sym in collect_syms.top_level_syms {
+                if !link_module.imports.contains_key(&sym) && !link_module.declared.contains(&sym) {
+                    link_module.declared.push(sym);
+                }
+            }
+            link_module.syms = collect_syms.syms;
+            link_module.inner_syms = collect_syms.inner_syms;
+            link_module.specifiers = specifiers;
+            link_module.module = module;
+            self.modules.push(link_module);
+        }
+        // modules are loaded in the order their indices are assigned
+        Ok(())
+    }
+
+    fn evaluation_order(&self) -> Vec<usize> {
+        fn visit(
+            modules: &[LinkModule],
+            m: usize,
+            visited: &mut Vec<bool>,
+            order: &mut Vec<usize>,
+        ) {
+            visited[m] = true;
+            for dep in modules[m].deps.iter().chain(&modules[m].dynamic_deps) {
+                if !visited[*dep] {
+                    visit(modules, *dep, visited, order);
+                }
+            }
+            order.push(m);
+        }
+        let mut visited = vec![false; self.modules.len()];
+        let mut order = Vec::new();
+        visit(&self.modules, 0, &mut visited, &mut order);
+        order
+    }
+
+    fn allocate(&mut self, name: &Atom, keep: bool) -> Atom {
+        if keep && !self.allocated.contains(name) {
+            self.allocated.insert(name.clone());
+            return name.clone();
+        }
+        let base = name.trim_start_matches('*').trim_end_matches('*');
+        let mut n = 1;
+        loop {
+            let candidate = Atom::from(format!("{base}${n}"));
+            if !self.reserved.contains(&candidate) && !self.allocated.contains(&candidate) {
+                self.allocated.insert(candidate.clone());
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    fn allocate_names(&mut self, order: &[usize]) {
+        for module in &self.modules {
+            self.reserved.extend(module.syms.iter().cloned());
+        }
+        self.names = vec![HashMap::new(); self.modules.len()];
+        for m in order {
+            let mut syms: Vec<Atom> = self.modules[*m].declared.clone();
+            let mut externals: Vec<Atom> = self.modules[*m]
+                .imports
+                .iter()
+                .filter(|(_, (target, _))| matches!(target, Target::External(_)))
+                .map(|(sym, _)| sym.clone())
+                .collect();
+            externals.sort();
+            syms.extend(externals);
+            for sym in syms {
+                let (name, keep) = if sym.as_str() == ANONYMOUS_DEFAULT {
+                    let name = Atom::from("_default");
+                    let keep = !self.reserved.contains(&name);
+                    (name, keep)
+                } else {
+                    let keep =
+                        self.modules.iter().enumerate().all(|(other, module)| {
+                            other == *m || !module.inner_syms.contains(&sym)
+                        });
+                    (sym.clone(), keep)
+                };
+                let name = self.allocate(&name, keep);
+                self.names[*m].insert(sym, name);
+            }
+        }
+    }
+
+    fn namespace(&mut self, m: usize) -> Atom {
+        if let Some(name) = self.namespaces.get(&m) {
+            return name.clone();
+        }
+        let stem: String = self.modules[m]
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let name = Atom::from(format!("{stem}_ns"));
+        let name = self.allocate(&name, !self.reserved.contains(&name));
+        self.namespaces.insert(m, name.clone());
+        name
+    }
+
+    fn synthetic_import(&mut self, specifier: &str, imported: Imported) -> Atom {
+        let base = match &imported {
+            Imported::Named(name) if is_ident(name) && name.as_str() != "default" => name.clone(),
+            _ => Atom::from("_import"),
+        };
+        let name = self.allocate(&base, !self.reserved.contains(&base));
+        self.synthetic_imports
+            .push((specifier.to_string(), imported, name.clone()));
+        name
+    }
+
+    fn resolve_import(&mut self, target: &Target, imported: &Imported) -> anyhow::Result<Resolved> {
+        match (target, imported) {
+            (Target::Module(t), Imported::Namespace) => Ok(Resolved::Namespace(*t)),
+            (Target::Module(t), Imported::Named(name)) => {
+                match self.resolve_export(*t, name, &mut HashSet::new())? {
+                    Some(resolved) => Ok(resolved),
+                    None => Err(anyhow::anyhow!(
+                        "{} does not export {name}",
+                        self.modules[*t].path.to_string_lossy()
+                    )),
+                }
+            }
+            (Target::External(specifier), imported) => Ok(Resolved::Binding(
+                self.synthetic_import(specifier, imported.clone()),
+            )),
+        }
+    }
+
+    fn resolve_local(&mut self, m: usize, sym: &Atom) -> anyhow::Result<Resolved> {
+        match self.modules[m].imports.get(sym).cloned() {
+            Some((Target::Module(t), imported)) => {
+                self.resolve_import(&Target::Module(t), &imported)
+            }
+            _ => match self.names[m].get(sym) {
+                Some(name) => Ok(Resolved::Binding(name.clone())),
+                None => Err(anyhow::anyhow!(
+                    "{} exports undeclared binding {sym}",
+                    self.modules[m].path.to_string_lossy()
+                )),
+            },
+        }
+    }
+
+    fn resolve_export(
+        &mut self,
+        m: usize,
+        name: &Atom,
+        visiting: &mut HashSet<(usize, Atom)>,
+    ) -> anyhow::Result<Option<Resolved>> {
+        if !visiting.insert((m, name.clone())) {
+            return Ok(None);
+        }
+        let export = self.modules[m]
+            .exports
+            .iter()
+            .find(|(exported, _)| exported == name)
+            .map(|(_, source)| source.clone());
+        match export {
+            Some(ExportSource::Local(sym)) => return self.resolve_local(m, &sym).map(Some),
+            Some(ExportSource::Reexport(target, imported)) => {
+                return self.resolve_import(&target, &imported).map(Some)
+            }
+            None => {}
+        }
+        if name.as_str() == "default" {
+            return Ok(None);
+        }
+        let mut external_star = None;
+        for target in self.modules[m].star_exports.clone() {
+            match target {
+                Target::Module(t) => {
+                    if let Some(resolved) = self.resolve_export(t, name, visiting)? {
+                        return Ok(Some(resolved));
+                    }
+                }
+                Target::External(specifier) => {
+                    external_star.get_or_insert(specifier);
+                }
+            }
+        }
+        // names of an external module's star export are only known at runtime
+        Ok(external_star.map(|specifier| {
+            Resolved::Binding(self.synthetic_import(&specifier, Imported::Named(name.clone())))
+        }))
+    }
+
+    fn export_names(&self, m: usize, visiting: &mut HashSet<usize>) -> Vec<Atom> {
+        if !visiting.insert(m) {
+            return Vec::new();
+        }
+        let mut names: Vec<Atom> = self.modules[m]
+            .exports
+            .iter()
+            .map(|(exported, _)| exported.clone())
+            .collect();
+        for target in &self.modules[m].star_exports {
+            if let Target::Module(t) = target {
+                for name in self.export_names(*t, visiting) {
+                    if name.as_str() != "default" && !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    fn resolved_name(&mut self, resolved: Resolved) -> Atom {
+        match resolved {
+            Resolved::Binding(name) => name,
+            Resolved::Namespace(t) => self.namespace(t),
+        }
+    }
+
+    fn link(mut self, source_map: bool) -> anyhow::Result<CompileOutput> {
+        let order = self.evaluation_order();
+        self.allocate_names(&order);
+
+        let mut hoisted: Vec<ModuleItem> = Vec::new();
+        let mut bodies: Vec<ModuleItem> = Vec::new();
+        for m in &order {
+            let mut replacements = self.names[*m].clone();
+            let mut imports: Vec<(Atom, (Target, Imported))> = self.modules[*m]
+                .imports
+                .iter()
+                .map(|(sym, import)| (sym.clone(), import.clone()))
+                .collect();
+            imports.sort_by(|a, b| a.0.cmp(&b.0));
+            for (sym, (target, imported)) in imports {
+                if let Target::Module(_) = target {
+                    let resolved = self.resolve_import(&target, &imported)?;
+                    let name = self.resolved_name(resolved);
+                    replacements.insert(sym, name);
+                }
+            }
+            let mut dynamic_namespaces = HashMap::new();
+            for (specifier, target) in self.modules[*m].specifiers.clone() {
+                if let Target::Module(t) = target {
+                    if self.modules[*m].dynamic_deps.contains(&t) {
+                        dynamic_namespaces.insert(specifier, self.namespace(t));
+                    }
+                }
+            }
+            let link_module = &mut self.modules[*m];
+            let mut module = std::mem::take(&mut link_module.module);
+            module.visit_mut_with(&mut Relink {
+                top_level_ctxt: link_module.top_level_ctxt,
+                replacements: &replacements,
+                dynamic_namespaces: &dynamic_namespaces,
+            });
+            for item in module.body {
+                match item {
+                    ModuleItem::Stmt(stmt) => bodies.push(ModuleItem::Stmt(stmt)),
+                    ModuleItem::ModuleDecl(module_decl) => match module_decl {
+                        ModuleDecl::Import(import_decl) => {
+                            if let Some(Target::External(_)) =
+                                link_module.specifiers.get(import_decl.src.value.as_str())
+                            {
+                                hoisted
+                                    .push(ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)));
+                            }
+                        }
+                        ModuleDecl::ExportDecl(export_decl) => {
+                            bodies.push(ModuleItem::Stmt(Stmt::Decl(export_decl.decl)))
+                        }
+                        ModuleDecl::ExportDefaultDecl(export_default) => {
+                            let default_name = || {
+                                Ident::new_no_ctxt(
+                                    replacements[&Atom::from(ANONYMOUS_DEFAULT)].clone(),
+                                    DUMMY_SP,
+                                )
+                            };
+                            let decl = match export_default.decl {
+                                DefaultDecl::Fn(fn_expr) => Decl::Fn(FnDecl {
+                                    ident: fn_expr.ident.unwrap_or_else(default_name),
+                                    declare: false,
+                                    function: fn_expr.function,
+                                }),
+                                DefaultDecl::Class(class_expr) => Decl::Class(ClassDecl {
+                                    ident: class_expr.ident.unwrap_or_else(default_name),
+                                    declare: false,
+                                    class: class_expr.class,
+                                }),
+                                DefaultDecl::TsInterfaceDecl(_) => continue,
+                            };
+                            bodies.push(ModuleItem::Stmt(Stmt::Decl(decl)));
+                        }
+                        ModuleDecl::ExportDefaultExpr(export_default) => {
+                            bodies.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(
+                                VarDecl {
+                                    kind: VarDeclKind::Const,
+                                    decls: vec![VarDeclarator {
+                                        span: DUMMY_SP,
+                                        name: Pat::Ident(
+                                            Ident::new_no_ctxt(
+                                                replacements[&Atom::from(ANONYMOUS_DEFAULT)]
+                                                    .clone(),
+                                                DUMMY_SP,
+                                            )
+                                            .into(),
+                                        ),
+                                        init: Some(export_default.expr),
+                                        definite: false,
+                                    }],
+                                    ..Default::default()
+                                },
+                            )))));
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+
+        // entrypoint exports are resolved before namespaces that may be added by resolving
+        let mut exports_js = String::new();
+        for name in self.export_names(0, &mut HashSet::new()) {
+            if let Some(resolved) = self.resolve_export(0, &name, &mut HashSet::new())? {
+                let local = self.resolved_name(resolved);
+                exports_js.push_str(&format!(
+                    "export {{ {local} as {} }};\n",
+                    export_name_js(&name)
+                ));
+            }
+        }
+        for target in self.modules[0].star_exports.clone() {
+            if let Target::External(specifier) = target {
+                exports_js.push_str(&format!("export * from {};\n", js_string(&specifier)));
+            }
+        }
+
+        let mut namespaces_js = String::new();
+        let mut defined: HashSet<usize> = HashSet::new();
+        loop {
+            let mut pending: Vec<(usize, Atom)> = self
+                .namespaces
+                .iter()
+                .filter(|(m, _)| !defined.contains(m))
+                .map(|(m, name)| (*m, name.clone()))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            pending.sort();
+            for (m, ns) in pending {
+                defined.insert(m);
+                let mut getters = Vec::new();
+                for name in self.export_names(m, &mut HashSet::new()) {
+                    if let Some(resolved) = self.resolve_export(m, &name, &mut HashSet::new())? {
+                        let local = self.resolved_name(resolved);
+                        getters.push(format!("get {}() {{ return {local}; }}", js_string(&name)));
+                    }
+                }
+                namespaces_js.push_str(&format!(
+                    "const {ns} = Object.freeze({{ __proto__: null, {} }});\n",
+                    getters.join(", ")
+                ));
+            }
+        }
+
+        for (specifier, imported, local) in &self.synthetic_imports {
+            let js = match imported {
+                Imported::Namespace => {
+                    format!("import * as {local} from {};", js_string(specifier))
+                }
+                Imported::Named(name) => format!(
+                    "import {{ {} as {local} }} from {};",
+                    export_name_js(name),
+                    js_string(specifier)
+                ),
+            };
+            hoisted.extend(
+                self.parse(Path::new("bundle_imports.js"), js, SourceType::EsModule)?
+                    .body,
+            );
+        }
+
+        let mut body = hoisted;
+        body.extend(
+            self.parse(
+                Path::new("bundle_namespaces.js"),
+                namespaces_js,
+                SourceType::EsModule,
+            )?
+            .body,
+        );
+        body.extend(bodies);
+        body.extend(
+            self.parse(
+                Path::new("bundle_exports.js"),
+                exports_js,
+                SourceType::EsModule,
+            )?
+            .body,
+        );
+        let bundle = Module {
+            span: DUMMY_SP,
+            body,
+            shebang: None,
+        };
+        Ok(self
+            .compiler
+            .print(
+                &Program::Module(bundle),
+                PrintArgs {
+                    inline_sources_content: true,
+                    source_map: SourceMapsConfig::Bool(source_map),
+                    ..Default::default()
+                },
+            )?
+            .into())
+    }
+}
+
+fn commonjs_error(path: &Path) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{} is a CommonJS module and cannot be bundled",
+        path.to_string_lossy()
+    )
+}
+
+fn analyze_module_item(
+    item: &ModuleItem,
+    link_module: &mut LinkModule,
+    resolve: &mut dyn FnMut(&str) -> Target,
+) {
+    let add_dep = |link_module: &mut LinkModule, target: &Target| {
+        if let Target::Module(index) = target {
+            if !link_module.deps.contains(index) {
+                link_module.deps.push(*index);
+            }
+        }
+    };
+    let ModuleItem::ModuleDecl(module_decl) = item else {
+        return;
+    };
+    match module_decl {
+        ModuleDecl::Import(import_decl) => {
+            let target = resolve(import_decl.src.value.as_str());
+            add_dep(link_module, &target);
+            for specifier in &import_decl.specifiers {
+                let (local, imported) = match specifier {
+                    ImportSpecifier::Named(named) => (
+                        named.local.sym.clone(),
+                        Imported::Named(match &named.imported {
+                            Some(imported) => export_name(imported),
+                            None => named.local.sym.clone(),
+                        }),
+                    ),
+                    ImportSpecifier::Default(default) => {
+                        (default.local.sym.clone(), Imported::Named("default".into()))
+                    }
+                    ImportSpecifier::Namespace(namespace) => {
+                        (namespace.local.sym.clone(), Imported::Namespace)
+                    }
+                };
+                link_module
+                    .imports
+                    .insert(local, (target.clone(), imported));
+            }
+        }
+        ModuleDecl::ExportDecl(export_decl) => {
+            let mut syms = Vec::new();
+            match &export_decl.decl {
+                Decl::Class(class_decl) => syms.push(class_decl.ident.sym.clone()),
+                Decl::Fn(fn_decl) => syms.push(fn_decl.ident.sym.clone()),
+                Decl::Var(var_decl) => {
+                    for declarator in &var_decl.decls {
+                        collect_pat_syms(&declarator.name, &mut syms);
+                    }
+                }
+                _ => {}
+            }
+            for sym in syms {
+                link_module
+                    .exports
+                    .push((sym.clone(), ExportSource::Local(sym)));
+            }
+        }
+        ModuleDecl::ExportNamed(named_export) => {
+            let target = named_export
+                .src
+                .as_ref()
+                .map(|src| resolve(src.value.as_str()));
+            if let Some(target) = &target {
+                add_dep(link_module, target);
+            }
+            for specifier in &named_export.specifiers {
+                let (exported, source) = match specifier {
+                    ExportSpecifier::Named(named) => {
+                        let orig = export_name(&named.orig);
+                        let exported = named.exported.as_ref().map(export_name);
+                        let exported = exported.unwrap_or_else(|| orig.clone());
+                        let source = match &target {
+                            Some(target) => {
+                                ExportSource::Reexport(target.clone(), Imported::Named(orig))
+                            }
+                            None => ExportSource::Local(orig),
+                        };
+                        (exported, source)
+                    }
+                    ExportSpecifier::Namespace(namespace) => match &target {
+                        Some(target) => (
+                            export_name(&namespace.name),
+                            ExportSource::Reexport(target.clone(), Imported::Namespace),
+                        ),
+                        None => continue,
+                    },
+                    ExportSpecifier::Default(default) => match &target {
+                        Some(target) => (
+                            default.exported.sym.clone(),
+                            ExportSource::Reexport(
+                                target.clone(),
+                                Imported::Named("default".into()),
+                            ),
+                        ),
+                        None => continue,
+                    },
+                };
+                link_module.exports.push((exported, source));
+            }
+        }
+        ModuleDecl::ExportDefaultDecl(export_default) => {
+            let ident = match &export_default.decl {
+                DefaultDecl::Class(class_expr) => class_expr.ident.as_ref(),
+                DefaultDecl::Fn(fn_expr) => fn_expr.ident.as_ref(),
+                DefaultDecl::TsInterfaceDecl(_) => return,
+            };
+            let sym = match ident {
+                Some(ident) => ident.sym.clone(),
+                None => {
+                    link_module.declared.push(ANONYMOUS_DEFAULT.into());
+                    ANONYMOUS_DEFAULT.into()
+                }
+            };
+            link_module
+                .exports
+                .push(("default".into(), ExportSource::Local(sym)));
+        }
+        ModuleDecl::ExportDefaultExpr(_) => {
+            link_module.declared.push(ANONYMOUS_DEFAULT.into());
+            link_module.exports.push((
+                "default".into(),
+                ExportSource::Local(ANONYMOUS_DEFAULT.into()),
+            ));
+        }
+        ModuleDecl::ExportAll(export_all) => {
+            let target = resolve(export_all.src.value.as_str());
+            add_dep(link_module, &target);
+            link_module.star_exports.push(target);
+        }
+        _ => {}
+    }
+}
+
+fn collect_pat_syms(pat: &Pat, syms: &mut Vec<Atom>) {
+    match pat {
+        Pat::Ident(binding) => syms.push(binding.id.sym.clone()),
+        Pat::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                collect_pat_syms(elem, syms);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    ObjectPatProp::KeyValue(key_value) => collect_pat_syms(&key_value.value, syms),
+                    ObjectPatProp::Assign(assign) => syms.push(assign.key.id.sym.clone()),
+                    ObjectPatProp::Rest(rest) => collect_pat_syms(&rest.arg, syms),
+                }
+            }
+        }
+        Pat::Rest(rest) => collect_pat_syms(&rest.arg, syms),
+        Pat::Assign(assign) => collect_pat_syms(&assign.left, syms),
+        _ => {}
+    }
+}
+
+fn export_name(name: &ModuleExportName) -> Atom {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.clone(),
+        ModuleExportName::Str(str) => str.value.clone(),
+    }
+}
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_' || c == '$')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn js_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+fn export_name_js(name: &str) -> String {
+    if is_ident(name) {
+        name.to_string()
+    } else {
+        js_string(name)
+    }
+}
+
+/// Collects syms of a module's identifiers, its top-level bindings and `import()` specifiers.
+struct CollectSyms {
+    top_level_ctxt: SyntaxContext,
+    syms: HashSet<Atom>,
+    inner_syms: HashSet<Atom>,
+    top_level_syms: Vec<Atom>,
+    dynamic_imports: Vec<String>,
+}
+
+impl Visit for CollectSyms {
+    fn visit_call_expr(&mut self, call_expr: &CallExpr) {
+        if let Callee::Import(_) = call_expr.callee {
+            if let Some(Expr::Lit(Lit::Str(specifier))) =
+                call_expr.args.first().map(|arg| &*arg.expr)
+            {
+                self.dynamic_imports.push(specifier.value.to_string());
+            }
+        }
+        call_expr.visit_children_with(self);
+    }
+
+    fn visit_module_export_name(&mut self, name: &ModuleExportName) {
+        // names of imports and exports are not bindings of the module's scopes
+        self.syms.insert(export_name(name));
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.syms.insert(ident.sym.clone());
+        if ident.ctxt != self.top_level_ctxt {
+            self.inner_syms.insert(ident.sym.clone());
+        } else if !self.top_level_syms.contains(&ident.sym) {
+            self.top_level_syms.push(ident.sym.clone());
+        }
+    }
+}
+
+/// Replaces references to a module's top-level bindings and imports with names in the bundle.
+struct Relink<'a> {
+    top_level_ctxt: SyntaxContext,
+    replacements: &'a HashMap<Atom, Atom>,
+    dynamic_namespaces: &'a HashMap<String, Atom>,
+}
+
+impl Relink<'_> {
+    fn replacement(&self, ident: &Ident) -> Option<&Atom> {
+        if ident.ctxt == self.top_level_ctxt {
+            self.replacements
+                .get(&ident.sym)
+                .filter(|name| **name != ident.sym)
+        } else {
+            None
+        }
+    }
+}
+
+impl VisitMut for Relink<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        if let Expr::Call(CallExpr {
+            callee: Callee::Import(_),
+            args,
+            ..
+        }) = expr
+        {
+            if let Some(Expr::Lit(Lit::Str(specifier))) = args.first().map(|arg| &*arg.expr) {
+                if let Some(namespace) = self.dynamic_namespaces.get(specifier.value.as_str()) {
+                    *expr = Expr::Call(CallExpr {
+                        callee: Callee::Expr(Box::new(Expr::Member(swc_ecma_ast::MemberExpr {
+                            span: DUMMY_SP,
+                            obj: Box::new(Expr::Ident(Ident::new_no_ctxt(
+                                "Promise".into(),
+                                DUMMY_SP,
+                            ))),
+                            prop: swc_ecma_ast::MemberProp::Ident(IdentName::new(
+                                "resolve".into(),
+                                DUMMY_SP,
+                            )),
+                        }))),
+                        args: vec![
+                            Expr::Ident(Ident::new_no_ctxt(namespace.clone(), DUMMY_SP)).into()
+                        ],
+                        ..Default::default()
+                    });
+                    return;
+                }
+            }
+        }
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_ident(&mut self, ident: &mut Ident) {
+        if let Some(name) = self.replacement(ident) {
+            ident.sym = name.clone();
+        }
+    }
+
+    fn visit_mut_import_named_specifier(&mut self, specifier: &mut ImportNamedSpecifier) {
+        if specifier.imported.is_none() && self.replacement(&specifier.local).is_some() {
+            specifier.imported = Some(ModuleExportName::Ident(Ident::new_no_ctxt(
+                specifier.local.sym.clone(),
+                DUMMY_SP,
+            )));
+        }
+        specifier.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_prop(&mut self, prop: &mut Prop) {
+        if let Prop::Shorthand(ident) = prop {
+            if let Some(name) = self.replacement(ident) {
+                *prop = Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(IdentName::new(ident.sym.clone(), ident.span)),
+                    value: Box::new(Expr::Ident(Ident::new_no_ctxt(name.clone(), ident.span))),
+                });
+                return;
+            }
+        }
+        prop.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_pat_prop(&mut self, prop: &mut ObjectPatProp) {
+        if let ObjectPatProp::Assign(AssignPatProp { key, value, span }) = prop {
+            if let Some(name) = self.replacement(&key.id) {
+                let binding = Pat::Ident(Ident::new_no_ctxt(name.clone(), key.id.span).into());
+                let mut value = value.take();
+                if let Some(value) = value.as_mut() {
+                    value.visit_mut_with(self);
+                }
+                *prop = ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(IdentName::new(key.id.sym.clone(), key.id.span)),
+                    value: Box::new(match value {
+                        None => binding,
+                        Some(default) => Pat::Assign(AssignPat {
+                            span: *span,
+                            left: Box::new(binding),
+                            right: default,
+                        }),
+                    }),
+                });
+                return;
+            }
+        }
+        prop.visit_mut_children_with(self);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::swc::bundler::{BundleImport, BundleLoader};
+use crate::swc::compiler::{CompileResult, SwcCompiler};
+use l3_ast_parse::swc::SourceType;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+struct TestLoader(HashMap<PathBuf, &'static str>);
+
+impl BundleLoader for TestLoader {
+    fn load(&self, path: &Path) -> CompileResult<String> {
+        match self.0.get(path) {
+            Some(js) => Ok(js.to_string()),
+            None => Err(io::Error::from(io::ErrorKind::NotFound).into()),
+        }
+    }
+
+    fn resolve(&self, from: &Path, specifier: &str) -> BundleImport {
+        if specifier.starts_with("../") {
+            return BundleImport::Unresolved;
+        }
+        match specifier.strip_prefix("./") {
+            Some(relative) => BundleImport::Module(from.parent().unwrap().join(relative)),
+            None => BundleImport::External,
+        }
+    }
+
+    fn source_type(&self, _path: &Path) -> SourceType {
+        SourceType::EsModule
+    }
+}
+
+fn bundle(modules: Vec<(&str, &'static str)>) -> String {
+    let loader = TestLoader(
+        modules
+            .into_iter()
+            .map(|(path, js)| (PathBuf::from(path), js))
+            .collect(),
+    );
+    SwcCompiler::new()
+        .bundle(Path::new("routes/index.js"), &loader, false)
+        .unwrap()
+        .code
+}
+
+#[test]
+fn test_bundle_hoists_modules_in_evaluation_order() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "import { getData } from './data.js'\nexport const handler = () => getData()",
+        ),
+        (
+            "routes/data.js",
+            "export function getData() { return 'data' }",
+        ),
+    ]);
+    assert_eq!(
+        result,
+        "function getData() {\n    return 'data';\n}\nconst handler = ()=>getData();\nexport { handler as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_renames_colliding_bindings() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "import { label as dataLabel } from './data.js'\nconst label = 'index'\nexport const handler = () => ({ label, dataLabel })",
+        ),
+        ("routes/data.js", "const label = 'data'\nexport { label }"),
+    ]);
+    assert_eq!(
+        result,
+        "const label = 'data';\nconst label$1 = 'index';\nconst handler = ()=>({\n        label: label$1,\n        dataLabel: label\n    });\nexport { handler as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_names_anonymous_default_export() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "import getData from './data.js'\nexport const handler = () => getData()",
+        ),
+        ("routes/data.js", "export default () => 'data'"),
+    ]);
+    assert_eq!(
+        result,
+        "const _default = ()=>'data';\nconst handler = ()=>_default();\nexport { handler as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_namespace_import() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "import * as data from './data.js'\nexport const handler = () => data.getData()",
+        ),
+        ("routes/data.js", "export const getData = () => 'data'"),
+    ]);
+    assert_eq!(
+        result,
+        "const data_ns = Object.freeze({\n    __proto__: null,\n    get \"getData\" () {\n        return getData;\n    }\n});\nconst getData = ()=>'data';\nconst handler = ()=>data_ns.getData();\nexport { handler as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_keeps_external_imports() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "import { format } from 'date-lib'\nimport { getData } from './data.js'\nexport const handler = () => format(getData())",
+        ),
+        (
+            "routes/data.js",
+            "import { format } from 'date-lib'\nexport const getData = () => format('data')",
+        ),
+    ]);
+    assert_eq!(
+        result,
+        "import { format } from 'date-lib';\nimport { format as format$1 } from 'date-lib';\nconst getData = ()=>format('data');\nconst handler = ()=>format$1(getData());\nexport { handler as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_reexports_through_cycle() {
+    let result = bundle(vec![
+        (
+            "routes/index.js",
+            "export { getData as handler } from './data.js'",
+        ),
+        (
+            "routes/data.js",
+            "import { handler } from './index.js'\nexport * from './lib.js'\nexport const unused = () => handler",
+        ),
+        ("routes/lib.js", "export const getData = () => 'data'"),
+    ]);
+    assert_eq!(
+        result,
+        "const getData = ()=>'data';\nconst unused = ()=>getData;\nexport { getData as handler };\n"
+    );
+}
+
+#[test]
+fn test_bundle_errors_for_unresolved_import() {
+    let loader = TestLoader(HashMap::from([(
+        PathBuf::from("routes/index.js"),
+        "import { getData } from '../lib/data.js'\nexport const handler = () => getData()",
+    )]));
+    match SwcCompiler::new().bundle(Path::new("routes/index.js"), &loader, false) {
+        Err(err) => assert!(err
+            .to_string()
+            .contains("could not resolve \"../lib/data.js\" from routes/index.js")),
+        Ok(_) => panic!(),
+    };
+}
@@ -1,123 +1,154 @@
-use std::io;
+use l3_ast_parse::swc::visitors::{RewriteTsImportsVisitor, TsImportRewrites};
+use l3_ast_parse::swc::{is_tsx, ts_syntax, SwcParser};
+use l3_fn_config::NodeVersion;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use swc::config::{Config, IsModule, JscConfig, Options};
-use swc::{BoolConfig, Compiler};
-use swc_common::errors::{Diagnostic, DiagnosticBuilder, Emitter, Handler, HANDLER};
-use swc_common::{SourceFile, SourceMap, GLOBALS};
-use swc_ecma_ast::{noop_pass, EsVersion, Module, Pass, Program};
-use swc_ecma_parser::{EsSyntax, Syntax, TsSyntax};
+use std::sync::Arc;
+use swc::config::{
+    Config, DecoratorVersion, IsModule, JsMinifyOptions, JscConfig, Options, SourceMapsConfig,
+    TerserSourceMapOption, TransformConfig,
+};
+use swc::{BoolConfig, BoolOrDataConfig, Compiler, TransformOutput};
+use swc_common::errors::Handler;
+use swc_common::SourceFile;
+use swc_config::source_map::SourceMapContent;
+use swc_ecma_ast::{noop_pass, EsVersion, Pass};
+use swc_ecma_parser::Syntax;
+use swc_ecma_transforms_react::{Options as ReactOptions, Runtime as ReactRuntime};
 use swc_ecma_visit::fold_pass;
 
-use crate::swc::visitors::RewriteTsImportsVisitor;
+pub use l3_ast_parse::swc::{es_target, CompileError, CompileResult};
 
-#[derive(Clone)]
-struct CapturingEmitter {
-    errors: Arc<Mutex<Vec<Diagnostic>>>,
+use crate::typescript::{TsCompilerOptions, TsJsx};
+
+/// Code of a compile with the source map of the code if a source map was emitted.
+pub struct CompileOutput {
+    pub code: String,
+    pub source_map: Option<String>,
 }
 
-impl CapturingEmitter {
-    pub fn new(errors: Arc<Mutex<Vec<Diagnostic>>>) -> Self {
-        Self { errors }
+impl From<TransformOutput> for CompileOutput {
+    fn from(transform_output: TransformOutput) -> Self {
+        Self {
+            code: transform_output.code,
+            source_map: transform_output.map,
+        }
     }
 }
 
-impl Emitter for CapturingEmitter {
-    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
-        self.errors.lock().unwrap().push((**db).clone());
+/// Options of a project's tsconfig.json and the Node.js version of a build that configure
+/// transpiling TS to JS.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranspileOptions {
+    /// Decorators have the semantics of compilerOptions.experimentalDecorators instead of TC39's.
+    pub legacy_decorators: bool,
+    pub decorator_metadata: bool,
+    /// JSX emit of .tsx sources.
+    pub jsx: TsJsx,
+    /// Emits a source map of the transpiled JS.
+    pub source_map: bool,
+    pub target: EsVersion,
+    pub use_define_for_class_fields: bool,
+    pub verbatim_module_syntax: bool,
+}
+
+impl TranspileOptions {
+    /// Maps compilerOptions to SWC's options, defaulting the ES target to the ES version supported
+    /// by the Node.js version, or the oldest supported Node.js version when not configured.
+    pub fn new(compiler: Option<&TsCompilerOptions>, node_version: Option<&NodeVersion>) -> Self {
+        let default_compiler = TsCompilerOptions::default();
+        let compiler = compiler.unwrap_or(&default_compiler);
+        let target = compiler
+            .target
+            .unwrap_or_else(|| node_es_target(node_version));
+        Self {
+            legacy_decorators: compiler.experimental_decorators,
+            decorator_metadata: compiler.experimental_decorators
+                && compiler.emit_decorator_metadata,
+            jsx: compiler.jsx.unwrap_or(TsJsx::React),
+            source_map: false,
+            target,
+            // tsc defaults useDefineForClassFields by whether the target has class fields
+            use_define_for_class_fields: compiler
+                .use_define_for_class_fields
+                .unwrap_or(target >= EsVersion::Es2022),
+            verbatim_module_syntax: compiler.verbatim_module_syntax,
+        }
+    }
+
+    pub fn with_source_map(mut self, source_map: bool) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    fn react(&self) -> ReactOptions {
+        let (runtime, development) = match self.jsx {
+            TsJsx::Preserve | TsJsx::ReactNative => (ReactRuntime::Preserve, false),
+            TsJsx::React => (ReactRuntime::Classic, false),
+            TsJsx::ReactJsx => (ReactRuntime::Automatic, false),
+            TsJsx::ReactJsxDev => (ReactRuntime::Automatic, true),
+        };
+        ReactOptions {
+            runtime: Some(runtime),
+            development: Some(development),
+            ..Default::default()
+        }
     }
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum CompileError {
-    #[error("compiler produced diagnostic info")]
-    CompilerDiagnostics(Vec<Diagnostic>),
-    #[error("compiler operation produced error: {0}")]
-    OperationError(String),
-    #[error("reading source io error: {0}")]
-    ReadError(#[from] io::Error),
+impl Default for TranspileOptions {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
 }
 
-pub type CompileResult<R> = Result<R, CompileError>;
+/// ES version fully supported by a Node.js version.
+pub fn node_es_target(node_version: Option<&NodeVersion>) -> EsVersion {
+    match node_version {
+        None | Some(NodeVersion::Eighteen) => EsVersion::Es2022,
+        Some(NodeVersion::Twenty) => EsVersion::Es2023,
+        Some(NodeVersion::TwentyTwo) => EsVersion::Es2024,
+        Some(NodeVersion::TwentyFour) => EsVersion::EsNext,
+    }
+}
 
+/// Transpiles, minifies and bundles sources with l3_ast_parse's SwcParser, reporting the
+/// FnDiagnostics of a build's sources the same as parsing them.
 #[derive(Clone)]
 pub struct SwcCompiler {
-    /**
-     * swc::Compiler APIs
-     *
-     * parse_js: (source_file) => program
-     * transform: (program, fold) => program
-     * process_js: (program) => string
-     * process_js_file: (source_file) => string
-     * process_js_with_custom_pass: (source_file, program?, fold) => string
-     * minify: (source_file) => string
-     */
-    compiler: Arc<Compiler>,
-    pub source_map: Arc<SourceMap>,
+    parser: SwcParser,
 }
 
 impl SwcCompiler {
     pub fn new() -> Self {
-        let source_map = Arc::<SourceMap>::default();
-        let compiler = Arc::new(Compiler::new(source_map.clone()));
         Self {
-            compiler,
-            source_map,
+            parser: SwcParser::new(),
         }
     }
 
-    // from string of js, minify
-    pub fn minify_js(self, path: PathBuf, js: String) -> CompileResult<String> {
+    // from string of js, minify with an optional source map chained to the js's source map
+    pub fn minify_js(
+        self,
+        path: PathBuf,
+        js: String,
+        source_map: bool,
+        input_source_map: Option<String>,
+    ) -> CompileResult<CompileOutput> {
+        let opts = JsMinifyOptions {
+            inline_sources_content: true,
+            emit_source_map_columns: true,
+            source_map: match source_map {
+                false => BoolOrDataConfig::default(),
+                true => BoolOrDataConfig::from_obj(TerserSourceMapOption {
+                    content: input_source_map.map(SourceMapContent::Json),
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        };
         self.string_source_with_compiler(path, js, |compiler, handler, source_file| {
             compiler
-                .minify(
-                    source_file,
-                    handler,
-                    &Default::default(),
-                    Default::default(),
-                )
-                .map(|transform_output| transform_output.code)
-        })
-    }
-
-    // from file of ts or js, parse to ast
-    pub fn parse_program_from_fs(self, path: &Path) -> CompileResult<Program> {
-        debug_assert!(path.is_absolute());
-        self.fs_source_with_compiler(path, |compiler, handler, source_file| {
-            compiler
-                .parse_js(
-                    source_file,
-                    handler,
-                    es_target(),
-                    es_or_ts_syntax(path),
-                    IsModule::Bool(true),
-                    None,
-                )
-                .map(|program| {
-                    if let Program::Script(_) = program {
-                        panic!("cjs");
-                    }
-                    program
-                })
-        })
-    }
-
-    // from file of ts or js, parse to ast
-    pub fn parse_module_from_fs(self, path: &Path) -> CompileResult<Module> {
-        debug_assert!(path.is_absolute());
-        self.parse_program_from_fs(path)
-            .map(|program| match program {
-                Program::Module(module) => module,
-                Program::Script(_) => panic!("cjs"),
-            })
-    }
-
-    #[allow(dead_code)]
-    pub fn transform_to_string_from_ast(self, program: Program) -> CompileResult<String> {
-        self.with_compiler(|compiler, handler| {
-            Ok(compiler
-                .process_js(handler, program, &process_opts(false, ts_syntax()))?
-                .code)
+                .minify(source_file, handler, &opts, Default::default())
+                .map(CompileOutput::from)
         })
     }
 
@@ -126,9 +157,10 @@ impl SwcCompiler {
         self,
         path: PathBuf,
         ts: String,
-        rewrite_ts_imports: bool,
-    ) -> CompileResult<String> {
-        self.process_ts(path, ts, false, rewrite_ts_imports)
+        options: &TranspileOptions,
+        rewrites: TsImportRewrites,
+    ) -> CompileResult<CompileOutput> {
+        self.process_ts(path, ts, false, options, rewrites)
     }
 
     // from string of ts code, transpile to js and minify
@@ -136,9 +168,10 @@ impl SwcCompiler {
         self,
         path: PathBuf,
         ts: String,
-        rewrite_ts_imports: bool,
-    ) -> CompileResult<String> {
-        self.process_ts(path, ts, true, rewrite_ts_imports)
+        options: &TranspileOptions,
+        rewrites: TsImportRewrites,
+    ) -> CompileResult<CompileOutput> {
+        self.process_ts(path, ts, true, options, rewrites)
     }
 
     // from string of ts code, transpile to js, optionally minify
@@ -147,111 +180,79 @@ impl SwcCompiler {
         path: PathBuf,
         ts: String,
         minify: bool,
-        rewrite_ts_imports: bool,
-    ) -> CompileResult<String> {
+        options: &TranspileOptions,
+        rewrites: TsImportRewrites,
+    ) -> CompileResult<CompileOutput> {
+        let syntax = ts_syntax(is_tsx(&path));
         self.string_source_with_compiler(path, ts, |compiler, handler, source_file| {
-            let after_pass: Box<dyn Pass> = match rewrite_ts_imports {
-                true => Box::new(fold_pass(RewriteTsImportsVisitor::new())),
-                false => Box::new(noop_pass()),
+            let after_pass: Box<dyn Pass> = match rewrites.is_empty() {
+                false => Box::new(fold_pass(RewriteTsImportsVisitor::new(rewrites))),
+                true => Box::new(noop_pass()),
             };
             compiler
                 .process_js_with_custom_pass(
                     source_file,
                     None,
                     handler,
-                    &process_opts(minify, ts_syntax()),
+                    &process_opts(minify, syntax, options),
                     Default::default(),
                     |_| noop_pass(),
                     |_| after_pass,
                 )
-                .map(|transform_output| transform_output.code)
+                .map(CompileOutput::from)
         })
     }
 }
 
-fn process_opts(minify: bool, syntax: Syntax) -> Options {
+fn process_opts(minify: bool, syntax: Syntax, options: &TranspileOptions) -> Options {
     Options {
         config: Config {
             jsc: JscConfig {
+                // helpers are inlined by the inline-helpers feature of swc_ecma_transforms_base
+                // regardless of compilerOptions.importHelpers, since a build does not ship the
+                // `@swc/helpers` package
+                external_helpers: BoolConfig::new(Some(false)),
                 syntax: Some(syntax),
-                target: Some(es_target()),
+                target: Some(options.target),
+                transform: Some(TransformConfig {
+                    decorator_metadata: BoolConfig::new(Some(options.decorator_metadata)),
+                    decorator_version: Some(if options.legacy_decorators {
+                        DecoratorVersion::V202112
+                    } else {
+                        DecoratorVersion::V202203
+                    }),
+                    legacy_decorator: BoolConfig::new(Some(options.legacy_decorators)),
+                    react: options.react(),
+                    use_define_for_class_fields: BoolConfig::new(Some(
+                        options.use_define_for_class_fields,
+                    )),
+                    verbatim_module_syntax: BoolConfig::new(Some(options.verbatim_module_syntax)),
+                    ..Default::default()
+                })
+                .into(),
                 ..Default::default()
             },
             is_module: Some(IsModule::Bool(true)),
             minify: BoolConfig::new(Some(minify)),
             ..Default::default()
         },
+        source_maps: options.source_map.then_some(SourceMapsConfig::Bool(true)),
         ..Default::default()
     }
 }
 
-fn es_or_ts_syntax(p: &Path) -> Syntax {
-    p.extension()
-        .map(|ext| match ext.to_str().unwrap() {
-            "ts" => ts_syntax(),
-            _ => es_syntax(),
-        })
-        .unwrap()
-}
-
-fn es_syntax() -> Syntax {
-    Syntax::Es(EsSyntax::default())
-}
-
-fn ts_syntax() -> Syntax {
-    Syntax::Typescript(TsSyntax {
-        decorators: false,
-        disallow_ambiguous_jsx_like: true,
-        tsx: false,
-        dts: false,
-        no_early_errors: false,
-    })
-}
-
-fn es_target() -> EsVersion {
-    EsVersion::EsNext
-}
-
 impl SwcCompiler {
-    fn fs_source_with_compiler<F, R>(self, p: &Path, f: F) -> CompileResult<R>
-    where
-        F: FnOnce(&Compiler, &Handler, Arc<SourceFile>) -> Result<R, anyhow::Error>,
-    {
-        let source_file = self.source_map.load_file(p)?;
-        self.with_compiler(|compiler, handler| f(compiler, handler, source_file))
-    }
-
     fn string_source_with_compiler<F, R>(self, p: PathBuf, js: String, f: F) -> CompileResult<R>
     where
         F: FnOnce(&Compiler, &Handler, Arc<SourceFile>) -> Result<R, anyhow::Error>,
     {
-        let source_file = self.source_map.new_source_file(Arc::new(p.into()), js);
-        self.with_compiler(|compiler, handler| f(compiler, handler, source_file))
+        self.parser.string_source_with_compiler(p, js, f)
     }
 
-    fn with_compiler<F, R>(self, f: F) -> CompileResult<R>
+    pub(crate) fn with_compiler<F, R>(self, p: &Path, f: F) -> CompileResult<R>
     where
         F: FnOnce(&Compiler, &Handler) -> Result<R, anyhow::Error>,
     {
-        GLOBALS.set(&Default::default(), || {
-            let errors: Arc<Mutex<Vec<Diagnostic>>> = Default::default();
-            let result = {
-                let emitter = CapturingEmitter::new(errors.clone());
-                let handler = Handler::with_emitter(true, false, Box::new(emitter));
-                HANDLER.set(&handler, || f(&self.compiler, &handler))
-            };
-            let diagnostics: Vec<Diagnostic> = Arc::into_inner(errors)
-                .unwrap_or_default()
-                .into_inner()
-                .unwrap_or_default();
-            if diagnostics.is_empty() {
-                match result {
-                    Ok(result) => Ok(result),
-                    Err(err) => Err(CompileError::OperationError(err.to_string())),
-                }
-            } else {
-                Err(CompileError::CompilerDiagnostics(diagnostics))
-            }
-        })
+        self.parser.with_compiler(p, f)
     }
 }
@@ -0,0 +1,82 @@
+use crate::swc::compiler::{node_es_target, SwcCompiler, TranspileOptions};
+use crate::typescript::{TsCompilerOptions, TsJsx};
+use l3_ast_parse::swc::visitors::TsImportRewrites;
+use l3_fn_config::NodeVersion;
+use std::path::PathBuf;
+use swc_ecma_ast::EsVersion;
+
+fn transpile(path: &str, ts: &str, options: &TranspileOptions) -> String {
+    SwcCompiler::new()
+        .transpile_ts(
+            PathBuf::from(path),
+            ts.to_string(),
+            options,
+            TsImportRewrites::default(),
+        )
+        .unwrap()
+        .code
+}
+
+#[test]
+fn test_transpile_options_target_defaults_to_node_version() {
+    assert_eq!(
+        TranspileOptions::new(None, Some(&NodeVersion::Eighteen)).target,
+        EsVersion::Es2022
+    );
+    assert_eq!(
+        TranspileOptions::new(None, Some(&NodeVersion::TwentyFour)).target,
+        EsVersion::EsNext
+    );
+    assert_eq!(TranspileOptions::default().target, node_es_target(None));
+    let compiler = TsCompilerOptions {
+        target: Some(EsVersion::Es2019),
+        ..Default::default()
+    };
+    let options = TranspileOptions::new(Some(&compiler), Some(&NodeVersion::TwentyFour));
+    assert_eq!(options.target, EsVersion::Es2019);
+    assert!(!options.use_define_for_class_fields);
+}
+
+#[test]
+fn test_transpile_ts_downlevels_to_target() {
+    let compiler = TsCompilerOptions {
+        target: Some(EsVersion::Es2019),
+        ..Default::default()
+    };
+    let js = transpile(
+        "index.ts",
+        "export const value = (v?: string) => v ?? 'default'",
+        &TranspileOptions::new(Some(&compiler), None),
+    );
+    assert!(!js.contains("??"), "{js}");
+}
+
+#[test]
+fn test_transpile_ts_experimental_decorators() {
+    let compiler = TsCompilerOptions {
+        emit_decorator_metadata: true,
+        experimental_decorators: true,
+        ..Default::default()
+    };
+    let js = transpile(
+        "index.ts",
+        "function log(target: any) {}\n@log\nexport class Handler {}",
+        &TranspileOptions::new(Some(&compiler), None),
+    );
+    assert!(js.contains("_ts_decorate"), "{js}");
+    assert!(!js.contains("@log"), "{js}");
+}
+
+#[test]
+fn test_transpile_tsx_react_jsx() {
+    let compiler = TsCompilerOptions {
+        jsx: Some(TsJsx::ReactJsx),
+        ..Default::default()
+    };
+    let js = transpile(
+        "page.tsx",
+        "export const Page = () => <div>hello</div>",
+        &TranspileOptions::new(Some(&compiler), None),
+    );
+    assert!(js.contains("react/jsx-runtime"), "{js}");
+}
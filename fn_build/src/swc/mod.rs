@@ -1,2 +1,8 @@
+pub mod bundler;
 pub mod compiler;
-pub mod visitors;
+
+#[cfg(test)]
+mod bundler_test;
+
+#[cfg(test)]
+mod compiler_test;
@@ -1,11 +0,0 @@
-pub use collect_imports::*;
-pub use rewrite_ts_imports::*;
-
-mod collect_imports;
-mod rewrite_ts_imports;
-
-#[cfg(test)]
-mod collect_imports_test;
-
-#[cfg(test)]
-mod rewrite_ts_imports_test;
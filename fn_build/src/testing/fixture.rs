@@ -71,7 +71,7 @@ impl TestFixtureSpec {
             Language::JavaScript | Language::TypeScript => Runtime::Node(Some(Arc::new(
                 NodeConfig::read_configs(self.fixture_dir.as_path()).unwrap(),
             ))),
-            Language::Python => Runtime::Python,
+            Language::Python => Runtime::Python(None),
         }
     }
 }
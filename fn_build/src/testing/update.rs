@@ -5,6 +5,7 @@ use crate::{parse_fn, FnParseSpec};
 
 pub async fn update_gold(spec: &Arc<TestFixtureSpec>) {
     let parse_result = parse_fn(FnParseSpec {
+        conditions: Vec::new(),
         entrypoint: spec.entrypoint.to_path_buf(),
         project_dir: spec.fixture_dir.clone(),
         runtime: spec.fixture_runtime(),
@@ -16,6 +16,8 @@ pub async fn verify_build(
 ) -> Result<(), FixtureError> {
     let temp_dir = TempDir::new().unwrap();
     let build_manifest = match build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
         entrypoint: spec.entrypoint.to_path_buf(),
         handler_fn_name: spec.handler_fn_name.to_string(),
         mode: mode.clone(),
@@ -25,8 +27,10 @@ pub async fn verify_build(
             dirname: "build-fixture-test".into(),
             use_build_mode: true,
         },
+        previous_build: None,
         project_dir: spec.fixture_dir.clone(),
         runtime: spec.fixture_runtime(),
+        source_maps: None,
     })
     .await
     {
@@ -6,6 +6,7 @@ use crate::{parse_fn, FnParseManifest, FnParseResult, FnParseSpec};
 
 pub async fn verify_parse(spec: &Arc<TestFixtureSpec>) -> Result<(), FixtureError> {
     let parse_result = parse_fn(FnParseSpec {
+        conditions: Vec::new(),
         entrypoint: spec.entrypoint.to_path_buf(),
         project_dir: spec.fixture_dir.clone(),
         runtime: spec.fixture_runtime(),
@@ -49,6 +50,11 @@ fn verify_successful_parse(
             "parse manifest has too many sources".into(),
         ));
     }
+    if parse_manifest.dependencies != expected_manifest.dependencies {
+        return Err(FixtureError::ParseSuccess(
+            "parse manifest dependencies did not match".into(),
+        ));
+    }
     if parse_manifest.entrypoint != expected_manifest.entrypoint {
         return Err(FixtureError::ParseSuccess(
             "parse manifest entrypoint did not match".into(),
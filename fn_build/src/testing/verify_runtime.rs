@@ -1,4 +1,5 @@
 use std::{
+    env,
     path::{Path, PathBuf},
     process::Command,
     sync::Arc,
@@ -33,10 +34,12 @@ pub fn verify_with_runtime(
             &spec.language,
             bin_override,
         ),
-        Some((build_dir, build_manifest)) => {
-            let entrypoint = build_manifest.output.paths.get(&spec.entrypoint).unwrap();
-            run_runtime(&build_dir, entrypoint, &spec.language, bin_override)
-        }
+        Some((build_dir, build_manifest)) => run_runtime(
+            &build_dir,
+            &build_manifest.handler.path,
+            &spec.language,
+            bin_override,
+        ),
     }
 }
 
@@ -60,11 +63,34 @@ fn run_runtime(
         }
         Language::Python => bin_override.unwrap_or_else(|| PathBuf::from(bin::PYTHON)),
     };
-    let output = match Command::new(&bin)
-        .arg(entrypoint)
-        .current_dir(project_dir)
-        .output()
-    {
+    let mut command = Command::new(&bin);
+    command.current_dir(project_dir);
+    match language {
+        Language::JavaScript | Language::TypeScript => _ = command.arg(entrypoint),
+        Language::Python => {
+            // imports the entrypoint as a module of its packages like Lambda with the task root
+            // on sys.path and the entrypoint's dir like a script's dir
+            let module = entrypoint
+                .with_extension("")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            let python_path = env::join_paths([
+                project_dir.to_path_buf(),
+                project_dir.join(entrypoint.parent().unwrap()),
+            ])
+            .unwrap();
+            command
+                .args([
+                    "-c",
+                    "import importlib, sys; importlib.import_module(sys.argv[1])",
+                ])
+                .arg(module)
+                .env("PYTHONPATH", python_path);
+        }
+    }
+    let output = match command.output() {
         Err(err) => panic!("error running {}: {err}", bin.to_string_lossy(),),
         Ok(output) => output,
     };
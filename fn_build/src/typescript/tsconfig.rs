@@ -1,6 +1,15 @@
-use std::{fs::read_to_string, io, path::Path, sync::Arc};
+use std::{
+    collections::HashSet,
+    fs::read_to_string,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use serde_json::Value;
+use serde_json::{Map, Value};
+use swc_ecma_ast::EsVersion;
+
+use crate::paths::{normalize_path, relative_path};
 
 /// Errors that occur when building a TsConfigJson from tsconfig.json.
 #[derive(Debug, thiserror::Error)]
@@ -13,8 +22,16 @@ pub enum TsConfigError {
         json_path: String,
         expected_type: String,
     },
+    #[error("tsconfig.json extends {0} that could not be resolved")]
+    UnresolvedExtends(String),
+    #[error("tsconfig.json extends {0} circularly")]
+    CircularExtends(String),
 }
 
+/// Key of the dir that `compilerOptions.paths` resolve from without a `baseUrl`, which is the
+/// dir of the tsconfig.json declaring `paths`, like tsc's internal `pathsBasePath` option.
+const PATHS_BASE_PATH: &str = "pathsBasePath";
+
 #[allow(unused)]
 #[derive(Debug, Default, PartialEq)]
 pub struct TsConfigJson {
@@ -28,8 +45,46 @@ pub struct TsCompilerOptions {
     pub allow_importing_ts: bool,
     /// Captures compilerOptions.allowJs.
     pub allow_js: bool,
+    /// Captures compilerOptions.baseUrl relative to the project dir.
+    pub base_url: Option<PathBuf>,
+    /// Captures compilerOptions.emitDecoratorMetadata.
+    pub emit_decorator_metadata: bool,
+    /// Captures compilerOptions.experimentalDecorators.
+    pub experimental_decorators: bool,
+    /// Captures compilerOptions.importHelpers.
+    pub import_helpers: bool,
+    /// Captures compilerOptions.jsx.
+    pub jsx: Option<TsJsx>,
+    /// Captures compilerOptions.paths in declaration order.
+    pub paths: Vec<TsPathAlias>,
     /// Captures compilerOptions.rewriteRelativeImportExtensions.
     pub rewrite_relative_imports: bool,
+    /// Captures compilerOptions.target.
+    pub target: Option<EsVersion>,
+    /// Captures compilerOptions.useDefineForClassFields, which defaults by the target when absent.
+    pub use_define_for_class_fields: Option<bool>,
+    /// Captures compilerOptions.verbatimModuleSyntax.
+    pub verbatim_module_syntax: bool,
+}
+
+/// JSX emit of .tsx sources set by compilerOptions.jsx.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TsJsx {
+    Preserve,
+    React,
+    ReactJsx,
+    ReactJsxDev,
+    ReactNative,
+}
+
+/// An import alias of compilerOptions.paths, such as `@lib/*` aliasing `lib/*`.
+#[derive(Debug, PartialEq)]
+pub struct TsPathAlias {
+    /// Alias pattern that may include a `*` wildcard.
+    pub pattern: String,
+    /// Substitutions of the alias relative to the project dir tried in order, where a `*` is
+    /// replaced by the wildcard's match.
+    pub targets: Vec<String>,
 }
 
 impl TsConfigJson {
@@ -39,13 +94,24 @@ impl TsConfigJson {
         )?)?))
     }
 
+    /// Reads a project's tsconfig.json with the compilerOptions of the configs it extends
+    /// merged before its own. Path options of each config are resolved relative to the dir
+    /// of the tsconfig.json read.
     pub fn read_tsconfig_json(path: &Path) -> Result<Arc<Self>, TsConfigError> {
         debug_assert!(path.extension().unwrap() == "json");
-        if path.is_file() {
-            Self::parse_tsconfig_json(read_to_string(path)?.as_str())
-        } else {
-            Ok(Default::default())
+        if !path.is_file() {
+            return Ok(Default::default());
         }
+        let tsconfig_json: Value = serde_json::from_str(read_to_string(path)?.as_str())?;
+        if tsconfig_json.get("extends").is_none() {
+            return Self::parse_tsconfig_json(&tsconfig_json.to_string());
+        }
+        let project_dir = path.parent().unwrap_or(Path::new(""));
+        let compiler_options =
+            read_extended_compiler_options(project_dir, path, tsconfig_json, &mut HashSet::new())?;
+        let mut merged = Map::new();
+        merged.insert("compilerOptions".into(), Value::Object(compiler_options));
+        Ok(Arc::new(Self::try_from(Value::Object(merged))?))
     }
 }
 
@@ -58,35 +124,234 @@ impl TryFrom<Value> for TsConfigJson {
                 .get("compilerOptions")
                 .and_then(|co| co.as_object())
             {
-                Some(co_json) => TsCompilerOptions {
-                    allow_importing_ts: optional_bool(co_json.get("allowImportingTsExtensions"))
-                        .ok_or(TsConfigError::MismatchedType {
-                            json_path: "compilerOptions.allowImportingTsExtensions".into(),
-                            expected_type: "bool".into(),
-                        })?,
-                    allow_js: optional_bool(co_json.get("allowJs")).ok_or(
-                        TsConfigError::MismatchedType {
-                            json_path: "compilerOptions.allowJs".into(),
+                Some(co_json) => {
+                    let base_url = optional_str(co_json, "baseUrl")?.map(|base_url| {
+                        normalize_path(Path::new(base_url.trim_start_matches("./")))
+                    });
+                    let paths_base = match &base_url {
+                        Some(base_url) => base_url.clone(),
+                        None => optional_str(co_json, PATHS_BASE_PATH)?
+                            .map(PathBuf::from)
+                            .unwrap_or_default(),
+                    };
+                    let bool_option = |option: &str| {
+                        optional_bool(co_json.get(option)).ok_or(TsConfigError::MismatchedType {
+                            json_path: format!("compilerOptions.{option}"),
                             expected_type: "bool".into(),
+                        })
+                    };
+                    TsCompilerOptions {
+                        allow_importing_ts: bool_option("allowImportingTsExtensions")?,
+                        allow_js: bool_option("allowJs")?,
+                        emit_decorator_metadata: bool_option("emitDecoratorMetadata")?,
+                        experimental_decorators: bool_option("experimentalDecorators")?,
+                        import_helpers: bool_option("importHelpers")?,
+                        jsx: optional_str(co_json, "jsx")?.map(parse_jsx).transpose()?,
+                        paths: match co_json.get("paths") {
+                            Some(paths) => read_paths(paths, &paths_base)?,
+                            None => Vec::new(),
                         },
-                    )?,
-                    rewrite_relative_imports: optional_bool(
-                        co_json.get("rewriteRelativeImportExtensions"),
-                    )
-                    .ok_or(TsConfigError::MismatchedType {
-                        json_path: "compilerOptions.rewriteRelativeImportExtensions".into(),
-                        expected_type: "bool".into(),
-                    })?,
-                },
+                        base_url,
+                        rewrite_relative_imports: bool_option("rewriteRelativeImportExtensions")?,
+                        target: optional_str(co_json, "target")?
+                            .map(parse_target)
+                            .transpose()?,
+                        use_define_for_class_fields: match co_json.get("useDefineForClassFields") {
+                            None => None,
+                            Some(_) => Some(bool_option("useDefineForClassFields")?),
+                        },
+                        verbatim_module_syntax: bool_option("verbatimModuleSyntax")?,
+                    }
+                }
                 None => Default::default(),
             },
         })
     }
 }
 
+/// Merges the compilerOptions of a tsconfig.json's `extends` configs in order and then its own
+/// compilerOptions, rebasing `baseUrl` and the base dir of `paths` to the project dir.
+fn read_extended_compiler_options(
+    project_dir: &Path,
+    path: &Path,
+    tsconfig_json: Value,
+    extending: &mut HashSet<PathBuf>,
+) -> Result<Map<String, Value>, TsConfigError> {
+    let config_dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = Map::new();
+    let extends: Vec<&str> = match tsconfig_json.get("extends") {
+        None => Vec::new(),
+        Some(Value::String(extends)) => vec![extends],
+        Some(Value::Array(extends)) => extends.iter().filter_map(Value::as_str).collect(),
+        Some(_) => {
+            return Err(TsConfigError::MismatchedType {
+                json_path: "extends".into(),
+                expected_type: "string or array".into(),
+            })
+        }
+    };
+    for extends in extends {
+        let extended_path = resolve_extends(config_dir, extends)
+            .ok_or_else(|| TsConfigError::UnresolvedExtends(extends.to_string()))?;
+        if !extending.insert(extended_path.clone()) {
+            return Err(TsConfigError::CircularExtends(extends.to_string()));
+        }
+        let extended_json: Value = serde_json::from_str(&read_to_string(&extended_path)?)?;
+        merged.extend(read_extended_compiler_options(
+            project_dir,
+            &extended_path,
+            extended_json,
+            extending,
+        )?);
+        extending.remove(&extended_path);
+    }
+    if let Some(compiler_options) = tsconfig_json
+        .get("compilerOptions")
+        .and_then(Value::as_object)
+    {
+        let rebase = |p: &Path| {
+            Value::String(
+                relative_path(project_dir, &normalize_path(&config_dir.join(p)))
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        };
+        for (option, value) in compiler_options {
+            match (option.as_str(), value) {
+                ("baseUrl", Value::String(base_url)) => {
+                    merged.insert(option.clone(), rebase(Path::new(base_url)));
+                }
+                ("paths", _) => {
+                    merged.insert(option.clone(), value.clone());
+                    merged.insert(PATHS_BASE_PATH.into(), rebase(Path::new("")));
+                }
+                _ => {
+                    merged.insert(option.clone(), value.clone());
+                }
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Resolves the path of an `extends` config relative to the extending config's dir or, for a
+/// package specifier such as `@tsconfig/node20`, from the node_modules dirs of its ancestors.
+fn resolve_extends(config_dir: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json_ext = |p: PathBuf| -> [PathBuf; 2] {
+        let json = PathBuf::from(format!("{}.json", p.to_string_lossy()));
+        [p, json]
+    };
+    if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        return with_json_ext(normalize_path(&config_dir.join(extends)))
+            .into_iter()
+            .find(|p| p.is_file());
+    }
+    config_dir.ancestors().find_map(|dir| {
+        let package_path = dir.join("node_modules").join(extends);
+        let [package_path, json] = with_json_ext(package_path);
+        [
+            package_path.clone(),
+            json,
+            package_path.join("tsconfig.json"),
+        ]
+        .into_iter()
+        .find(|p| p.is_file())
+    })
+}
+
+fn read_paths(paths: &Value, paths_base: &Path) -> Result<Vec<TsPathAlias>, TsConfigError> {
+    let mismatched_type = |json_path: &str| TsConfigError::MismatchedType {
+        json_path: format!("compilerOptions.paths{json_path}"),
+        expected_type: "array of strings".into(),
+    };
+    let Some(paths) = paths.as_object() else {
+        return Err(TsConfigError::MismatchedType {
+            json_path: "compilerOptions.paths".into(),
+            expected_type: "object".into(),
+        });
+    };
+    let mut aliases = Vec::with_capacity(paths.len());
+    for (pattern, targets) in paths {
+        let targets = targets
+            .as_array()
+            .ok_or_else(|| mismatched_type(&format!(".{pattern}")))?
+            .iter()
+            .map(|target| {
+                target
+                    .as_str()
+                    .map(|target| {
+                        normalize_path(&paths_base.join(target))
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .ok_or_else(|| mismatched_type(&format!(".{pattern}")))
+            })
+            .collect::<Result<_, _>>()?;
+        aliases.push(TsPathAlias {
+            pattern: pattern.clone(),
+            targets,
+        });
+    }
+    Ok(aliases)
+}
+
+/// Parses compilerOptions.jsx case-insensitively like tsc.
+fn parse_jsx(jsx: &str) -> Result<TsJsx, TsConfigError> {
+    match jsx.to_lowercase().as_str() {
+        "preserve" => Ok(TsJsx::Preserve),
+        "react" => Ok(TsJsx::React),
+        "react-jsx" => Ok(TsJsx::ReactJsx),
+        "react-jsxdev" => Ok(TsJsx::ReactJsxDev),
+        "react-native" => Ok(TsJsx::ReactNative),
+        _ => Err(TsConfigError::MismatchedType {
+            json_path: "compilerOptions.jsx".into(),
+            expected_type: "one of (preserve|react|react-jsx|react-jsxdev|react-native)".into(),
+        }),
+    }
+}
+
+/// Parses compilerOptions.target case-insensitively like tsc.
+fn parse_target(target: &str) -> Result<EsVersion, TsConfigError> {
+    match target.to_lowercase().as_str() {
+        "es3" => Ok(EsVersion::Es3),
+        "es5" => Ok(EsVersion::Es5),
+        "es6" | "es2015" => Ok(EsVersion::Es2015),
+        "es2016" => Ok(EsVersion::Es2016),
+        "es2017" => Ok(EsVersion::Es2017),
+        "es2018" => Ok(EsVersion::Es2018),
+        "es2019" => Ok(EsVersion::Es2019),
+        "es2020" => Ok(EsVersion::Es2020),
+        "es2021" => Ok(EsVersion::Es2021),
+        "es2022" => Ok(EsVersion::Es2022),
+        "es2023" => Ok(EsVersion::Es2023),
+        "es2024" => Ok(EsVersion::Es2024),
+        "esnext" => Ok(EsVersion::EsNext),
+        _ => Err(TsConfigError::MismatchedType {
+            json_path: "compilerOptions.target".into(),
+            expected_type: "ES version".into(),
+        }),
+    }
+}
+
 fn optional_bool(v: Option<&Value>) -> Option<bool> {
     match v {
         None => Some(false),
         Some(v) => v.as_bool(),
     }
 }
+
+fn optional_str<'a>(
+    co_json: &'a Map<String, Value>,
+    option: &str,
+) -> Result<Option<&'a str>, TsConfigError> {
+    match co_json.get(option) {
+        None => Ok(None),
+        Some(v) => v
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| TsConfigError::MismatchedType {
+                json_path: format!("compilerOptions.{option}"),
+                expected_type: "string".into(),
+            }),
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use swc_ecma_ast::EsVersion;
 use temp_dir::TempDir;
 
 use crate::typescript::*;
@@ -29,7 +31,10 @@ fn test_read_tsconfig() {
             compiler: TsCompilerOptions {
                 allow_importing_ts: true,
                 allow_js: true,
+                base_url: None,
+                paths: Vec::new(),
                 rewrite_relative_imports: true,
+                ..Default::default()
             }
         })
     );
@@ -47,7 +52,10 @@ fn test_read_tsconfig_compiler_options_absent_values_defaulted() {
             compiler: TsCompilerOptions {
                 allow_importing_ts: false,
                 allow_js: false,
+                base_url: None,
+                paths: Vec::new(),
                 rewrite_relative_imports: false,
+                ..Default::default()
             }
         })
     );
@@ -65,7 +73,10 @@ fn test_read_tsconfig_compiler_options_empty_values_defaulted() {
             compiler: TsCompilerOptions {
                 allow_importing_ts: false,
                 allow_js: false,
+                base_url: None,
+                paths: Vec::new(),
                 rewrite_relative_imports: false,
+                ..Default::default()
             }
         })
     );
@@ -103,3 +114,167 @@ fn test_read_tsconfig_compiler_options_invalid_fields_error() {
         }
     }
 }
+
+#[test]
+fn test_read_tsconfig_paths_and_base_url() {
+    let temp_dir = TempDir::new().unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(
+        &p,
+        r#"{
+    "compilerOptions": {
+        "baseUrl": "./src",
+        "paths": {
+            "@lib/*": ["lib/*", "vendor/*"],
+            "config": ["./config.ts"]
+        }
+    }
+}
+"#,
+    )
+    .unwrap();
+    let tsconfig = TsConfigJson::read_tsconfig_json(&p).unwrap();
+    assert_eq!(tsconfig.compiler.base_url, Some(PathBuf::from("src")));
+    assert_eq!(
+        tsconfig.compiler.paths,
+        vec![
+            TsPathAlias {
+                pattern: "@lib/*".into(),
+                targets: vec!["src/lib/*".into(), "src/vendor/*".into()],
+            },
+            TsPathAlias {
+                pattern: "config".into(),
+                targets: vec!["src/config.ts".into()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_read_tsconfig_extends_relative_config() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.child("configs")).unwrap();
+    fs::write(
+        temp_dir.child("configs/base.json"),
+        r#"{"compilerOptions": {"allowJs": true, "paths": {"@lib/*": ["../lib/*"]}}}"#,
+    )
+    .unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(
+        &p,
+        r#"{"extends": "./configs/base", "compilerOptions": {"allowImportingTsExtensions": true}}"#,
+    )
+    .unwrap();
+    let tsconfig = TsConfigJson::read_tsconfig_json(&p).unwrap();
+    assert_eq!(
+        tsconfig,
+        Arc::new(TsConfigJson {
+            compiler: TsCompilerOptions {
+                allow_importing_ts: true,
+                allow_js: true,
+                base_url: None,
+                paths: vec![TsPathAlias {
+                    pattern: "@lib/*".into(),
+                    targets: vec!["lib/*".into()],
+                }],
+                rewrite_relative_imports: false,
+                ..Default::default()
+            }
+        })
+    );
+}
+
+#[test]
+fn test_read_tsconfig_extends_package_config_overridden() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.child("node_modules/@tsconfig/base")).unwrap();
+    fs::write(
+        temp_dir.child("node_modules/@tsconfig/base/tsconfig.json"),
+        r#"{"compilerOptions": {"allowJs": true, "baseUrl": "."}}"#,
+    )
+    .unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(
+        &p,
+        r#"{"extends": ["@tsconfig/base"], "compilerOptions": {"allowJs": false}}"#,
+    )
+    .unwrap();
+    let tsconfig = TsConfigJson::read_tsconfig_json(&p).unwrap();
+    assert!(!tsconfig.compiler.allow_js);
+    assert_eq!(
+        tsconfig.compiler.base_url,
+        Some(PathBuf::from("node_modules/@tsconfig/base"))
+    );
+}
+
+#[test]
+fn test_read_tsconfig_extends_unresolved_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(&p, r#"{"extends": "./missing.json"}"#).unwrap();
+    assert!(matches!(
+        TsConfigJson::read_tsconfig_json(&p),
+        Err(TsConfigError::UnresolvedExtends(extends)) if extends == "./missing.json"
+    ));
+}
+
+#[test]
+fn test_read_tsconfig_extends_circular_error() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.child("base.json"), r#"{"extends": "./base.json"}"#).unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(&p, r#"{"extends": "./base.json"}"#).unwrap();
+    assert!(matches!(
+        TsConfigJson::read_tsconfig_json(&p),
+        Err(TsConfigError::CircularExtends(_))
+    ));
+}
+
+#[test]
+fn test_read_tsconfig_transpile_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(
+        &p,
+        r#"{
+    "compilerOptions": {
+        "emitDecoratorMetadata": true,
+        "experimentalDecorators": true,
+        "importHelpers": true,
+        "jsx": "react-jsx",
+        "target": "ES2020",
+        "useDefineForClassFields": false,
+        "verbatimModuleSyntax": true
+    }
+}
+"#,
+    )
+    .unwrap();
+    let tsconfig = TsConfigJson::read_tsconfig_json(&p).unwrap();
+    assert_eq!(
+        tsconfig.compiler,
+        TsCompilerOptions {
+            emit_decorator_metadata: true,
+            experimental_decorators: true,
+            import_helpers: true,
+            jsx: Some(TsJsx::ReactJsx),
+            target: Some(EsVersion::Es2020),
+            use_define_for_class_fields: Some(false),
+            verbatim_module_syntax: true,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_read_tsconfig_invalid_target_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let p = temp_dir.child("tsconfig.json");
+    fs::write(&p, r#"{"compilerOptions": {"target": "es1999"}}"#).unwrap();
+    match TsConfigJson::read_tsconfig_json(&p) {
+        Err(TsConfigError::MismatchedType { json_path, .. }) => {
+            assert_eq!(json_path, "compilerOptions.target")
+        }
+        _ => panic!(),
+    }
+}
@@ -1,4 +1,3 @@
-#![expect(clippy::too_many_arguments, reason = "generated code")]
 wit_bindgen::generate!({
     world: "fn-building"
 });
@@ -8,16 +7,19 @@ use std::sync::Arc;
 
 use l3_fn_build::FnOutputConfig;
 
-use crate::build_fn::l3::fn_build::build_result::{FnBuildOutput, SourceChecksum};
-use crate::build_fn::l3::fn_build::build_spec::{BuildMode, Runtime};
+use crate::build_fn::l3::fn_build::build_result::{FnBuildHandler, FnBuildOutput, SourceChecksum};
+use crate::build_fn::l3::fn_build::build_spec::{BuildMode, FnSourceMaps, Runtime};
+use crate::build_fn::l3::fn_build::parse_error::{
+    FnDiagnostic, FnDiagnosticSeverity, FnDiagnosticSpan, FnSourcePosition,
+};
 use crate::build_fn::l3::fn_build::parse_result::{
-    DependencyImport, FnDependencies, FnSource, ModuleImport,
+    DependencyImport, FnDependencies, FnSource, ModuleImport, PackageDependency,
 };
 
 struct FnBuildingWasm;
 
 impl Guest for FnBuildingWasm {
-    fn build_fn(spec: FnBuildSpec) -> Result<FnBuildManifest, String> {
+    fn build_fn(spec: FnBuildSpec) -> Result<FnBuildManifest, FnBuildError> {
         let result = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -25,23 +27,28 @@ impl Guest for FnBuildingWasm {
             .block_on(l3_fn_build::build_fn(map_in_fn_build_spec(spec)));
         match result {
             Ok(val) => Ok(map_out_fn_build_manifest(val)),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(map_out_fn_build_error(err)),
         }
     }
 }
 
 fn map_in_fn_build_spec(build_spec: FnBuildSpec) -> l3_fn_build::FnBuildSpec {
     l3_fn_build::FnBuildSpec {
+        bundle: build_spec.bundle.map(|bundle| l3_fn_build::FnBundleConfig {
+            inline_packages: bundle.inline_packages,
+        }),
+        conditions: build_spec.conditions,
         entrypoint: PathBuf::from(build_spec.entrypoint),
         handler_fn_name: build_spec.handler_fn_name,
         mode: match build_spec.mode {
             BuildMode::Debug => l3_fn_build::BuildMode::Debug,
             BuildMode::Release => l3_fn_build::BuildMode::Release,
         },
+        previous_build: None,
         project_dir: Arc::new(PathBuf::from(build_spec.project_dir)),
         runtime: match build_spec.runtime {
             Runtime::Node => l3_fn_build::runtime::Runtime::Node(None),
-            Runtime::Python => l3_fn_build::runtime::Runtime::Python,
+            Runtime::Python => l3_fn_build::runtime::Runtime::Python(None),
         },
         output: FnOutputConfig {
             build_root: PathBuf::from(build_spec.output.build_root),
@@ -49,16 +56,38 @@ fn map_in_fn_build_spec(build_spec: FnBuildSpec) -> l3_fn_build::FnBuildSpec {
             dirname: build_spec.output.dirname,
             use_build_mode: build_spec.output.use_build_mode,
         },
+        source_maps: build_spec
+            .source_maps
+            .map(|config| l3_fn_build::FnSourceMapConfig {
+                source_maps: match config.source_maps {
+                    FnSourceMaps::External => l3_fn_build::FnSourceMaps::External,
+                    FnSourceMaps::Inline => l3_fn_build::FnSourceMaps::Inline,
+                },
+                enable_node_source_maps: config.enable_node_source_maps,
+            }),
     }
 }
 
 fn map_out_fn_build_manifest(build_manifest: l3_fn_build::FnBuildManifest) -> FnBuildManifest {
     FnBuildManifest {
         entrypoint: build_manifest.entrypoint.to_string_lossy().to_string(),
+        env_vars: build_manifest.env_vars.into_iter().collect(),
         dependencies: match build_manifest.dependencies {
-            l3_fn_build::FnDependencies::Required => FnDependencies::Required,
+            l3_fn_build::FnDependencies::Packages(packages) => FnDependencies::Packages(
+                packages
+                    .into_iter()
+                    .map(|package| PackageDependency {
+                        name: package.name,
+                        version: package.version,
+                    })
+                    .collect(),
+            ),
             l3_fn_build::FnDependencies::Unused => FnDependencies::Unused,
         },
+        handler: FnBuildHandler {
+            fn_name: build_manifest.handler.fn_name,
+            path: build_manifest.handler.path.to_string_lossy().to_string(),
+        },
         checksums: build_manifest
             .checksums
             .into_iter()
@@ -84,6 +113,17 @@ fn map_out_fn_build_manifest(build_manifest: l3_fn_build::FnBuildManifest) -> Fn
                     )
                 })
                 .collect(),
+            source_maps: build_manifest
+                .output
+                .source_maps
+                .into_iter()
+                .map(|(src_path, source_map_path)| {
+                    (
+                        src_path.to_string_lossy().to_string(),
+                        source_map_path.to_string_lossy().to_string(),
+                    )
+                })
+                .collect(),
             archive_file: build_manifest
                 .output
                 .archive_file
@@ -98,9 +138,15 @@ fn map_out_fn_build_manifest(build_manifest: l3_fn_build::FnBuildManifest) -> Fn
                     .imports
                     .into_iter()
                     .map(|import| match import {
-                        l3_fn_build::ModuleImport::PackageDependency { package, subpath } => {
-                            ModuleImport::PackageDependency(DependencyImport { package, subpath })
-                        }
+                        l3_fn_build::ModuleImport::PackageDependency {
+                            package,
+                            subpath,
+                            path,
+                        } => ModuleImport::PackageDependency(DependencyImport {
+                            package,
+                            subpath,
+                            path: path.map(|path| path.to_string_lossy().to_string()),
+                        }),
                         l3_fn_build::ModuleImport::RelativeSource(path) => {
                             ModuleImport::RelativeSource(path.to_string_lossy().to_string())
                         }
@@ -115,3 +161,40 @@ fn map_out_fn_build_manifest(build_manifest: l3_fn_build::FnBuildManifest) -> Fn
 }
 
 export!(FnBuildingWasm);
+
+fn map_out_fn_build_error(err: l3_fn_build::FnBuildError) -> FnBuildError {
+    match err {
+        l3_fn_build::FnBuildError::ParseError(l3_fn_build::FnParseError::SyntaxError(
+            diagnostics,
+        )) => {
+            FnBuildError::SyntaxError(diagnostics.into_iter().map(map_out_fn_diagnostic).collect())
+        }
+        err => FnBuildError::Error(err.to_string()),
+    }
+}
+
+fn map_out_fn_diagnostic(diagnostic: l3_fn_build::FnDiagnostic) -> FnDiagnostic {
+    FnDiagnostic {
+        code: diagnostic.code,
+        message: diagnostic.message,
+        path: diagnostic.path.to_string_lossy().to_string(),
+        severity: match diagnostic.severity {
+            l3_fn_build::FnDiagnosticSeverity::Error => FnDiagnosticSeverity::Error,
+            l3_fn_build::FnDiagnosticSeverity::Warning => FnDiagnosticSeverity::Warning,
+            l3_fn_build::FnDiagnosticSeverity::Note => FnDiagnosticSeverity::Note,
+            l3_fn_build::FnDiagnosticSeverity::Help => FnDiagnosticSeverity::Help,
+        },
+        span: diagnostic.span.map(|span| FnDiagnosticSpan {
+            start: map_out_fn_source_position(span.start),
+            end: map_out_fn_source_position(span.end),
+        }),
+    }
+}
+
+fn map_out_fn_source_position(position: l3_fn_build::FnSourcePosition) -> FnSourcePosition {
+    FnSourcePosition {
+        line: position.line as u32,
+        column: position.column as u32,
+        offset: position.offset as u32,
+    }
+}
@@ -2,6 +2,7 @@ wit_bindgen::generate!({
     world: "fn-parsing"
 });
 
+use crate::parse_fn::l3::fn_build::parse_error::*;
 use crate::parse_fn::l3::fn_build::parse_result::*;
 use crate::parse_fn::l3::fn_build::parse_spec::*;
 
@@ -11,7 +12,7 @@ use std::sync::Arc;
 struct FnParsingWasm;
 
 impl Guest for FnParsingWasm {
-    fn parse_entrypoint(spec: FnParseSpec) -> Result<FnEntrypoint, String> {
+    fn parse_entrypoint(spec: FnParseSpec) -> Result<FnEntrypoint, FnParseError> {
         let result = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -19,11 +20,11 @@ impl Guest for FnParsingWasm {
             .block_on(l3_fn_build::parse_entrypoint(map_in_fn_parse_spec(spec)));
         match result {
             Ok(val) => Ok(map_out_fn_entrypoint(val)),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(map_out_fn_parse_error(err)),
         }
     }
 
-    fn parse_fn(spec: FnParseSpec) -> Result<FnParseManifest, String> {
+    fn parse_fn(spec: FnParseSpec) -> Result<FnParseManifest, FnParseError> {
         let result = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -31,18 +32,19 @@ impl Guest for FnParsingWasm {
             .block_on(l3_fn_build::parse_fn(map_in_fn_parse_spec(spec)));
         match result {
             Ok(val) => Ok(map_out_fn_parse_manifest(val)),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(map_out_fn_parse_error(err)),
         }
     }
 }
 
 fn map_in_fn_parse_spec(parse_spec: FnParseSpec) -> l3_fn_build::FnParseSpec {
     l3_fn_build::FnParseSpec {
+        conditions: parse_spec.conditions,
         entrypoint: PathBuf::from(parse_spec.entrypoint),
         project_dir: Arc::new(PathBuf::from(parse_spec.project_dir)),
         runtime: match parse_spec.runtime {
             Runtime::Node => l3_fn_build::runtime::Runtime::Node(None),
-            Runtime::Python => l3_fn_build::runtime::Runtime::Python,
+            Runtime::Python => l3_fn_build::runtime::Runtime::Python(None),
         },
     }
 }
@@ -50,7 +52,15 @@ fn map_in_fn_parse_spec(parse_spec: FnParseSpec) -> l3_fn_build::FnParseSpec {
 fn map_out_fn_parse_manifest(parse_manifest: l3_fn_build::FnParseManifest) -> FnParseManifest {
     FnParseManifest {
         dependencies: match parse_manifest.dependencies {
-            l3_fn_build::FnDependencies::Required => FnDependencies::Required,
+            l3_fn_build::FnDependencies::Packages(packages) => FnDependencies::Packages(
+                packages
+                    .into_iter()
+                    .map(|package| PackageDependency {
+                        name: package.name,
+                        version: package.version,
+                    })
+                    .collect(),
+            ),
             l3_fn_build::FnDependencies::Unused => FnDependencies::Unused,
         },
         entrypoint: map_out_fn_entrypoint(parse_manifest.entrypoint),
@@ -68,9 +78,15 @@ fn map_out_fn_source(source: l3_fn_build::FnSource) -> FnSource {
             .imports
             .into_iter()
             .map(|import| match import {
-                l3_fn_build::ModuleImport::PackageDependency { package, subpath } => {
-                    ModuleImport::PackageDependency(DependencyImport { package, subpath })
-                }
+                l3_fn_build::ModuleImport::PackageDependency {
+                    package,
+                    subpath,
+                    path,
+                } => ModuleImport::PackageDependency(DependencyImport {
+                    package,
+                    subpath,
+                    path: path.map(|path| path.to_string_lossy().to_string()),
+                }),
                 l3_fn_build::ModuleImport::RelativeSource(path) => {
                     ModuleImport::RelativeSource(path.to_string_lossy().to_string())
                 }
@@ -83,9 +99,74 @@ fn map_out_fn_source(source: l3_fn_build::FnSource) -> FnSource {
 
 fn map_out_fn_entrypoint(entrypoint: l3_fn_build::FnEntrypoint) -> FnEntrypoint {
     FnEntrypoint {
-        handlers: entrypoint.handlers,
+        handlers: entrypoint
+            .handlers
+            .into_iter()
+            .map(map_out_fn_handler)
+            .collect(),
         path: entrypoint.path.to_string_lossy().to_string(),
     }
 }
 
+fn map_out_fn_handler(handler: l3_fn_build::FnHandler) -> FnHandler {
+    FnHandler {
+        definition: match handler.definition {
+            l3_fn_build::FnHandlerDefinition::Function => FnHandlerDefinition::Function,
+            l3_fn_build::FnHandlerDefinition::FunctionExpression => {
+                FnHandlerDefinition::FunctionExpression
+            }
+            l3_fn_build::FnHandlerDefinition::Wrapped => FnHandlerDefinition::Wrapped,
+            l3_fn_build::FnHandlerDefinition::Alias { local } => FnHandlerDefinition::Alias(local),
+            l3_fn_build::FnHandlerDefinition::ReExport { name, path } => {
+                FnHandlerDefinition::ReExport(HandlerReExport {
+                    name,
+                    path: path.to_string_lossy().to_string(),
+                })
+            }
+        },
+        doc: handler.doc,
+        name: handler.name,
+        signature: handler.signature.map(|signature| FnHandlerSignature {
+            event_type: signature.event_type,
+            is_async: signature.is_async,
+            param_count: signature.param_count as u32,
+        }),
+    }
+}
+
+fn map_out_fn_parse_error(err: l3_fn_build::FnParseError) -> FnParseError {
+    match err {
+        l3_fn_build::FnParseError::SyntaxError(diagnostics) => {
+            FnParseError::SyntaxError(diagnostics.into_iter().map(map_out_fn_diagnostic).collect())
+        }
+        err => FnParseError::Error(err.to_string()),
+    }
+}
+
+fn map_out_fn_diagnostic(diagnostic: l3_fn_build::FnDiagnostic) -> FnDiagnostic {
+    FnDiagnostic {
+        code: diagnostic.code,
+        message: diagnostic.message,
+        path: diagnostic.path.to_string_lossy().to_string(),
+        severity: match diagnostic.severity {
+            l3_fn_build::FnDiagnosticSeverity::Error => FnDiagnosticSeverity::Error,
+            l3_fn_build::FnDiagnosticSeverity::Warning => FnDiagnosticSeverity::Warning,
+            l3_fn_build::FnDiagnosticSeverity::Note => FnDiagnosticSeverity::Note,
+            l3_fn_build::FnDiagnosticSeverity::Help => FnDiagnosticSeverity::Help,
+        },
+        span: diagnostic.span.map(|span| FnDiagnosticSpan {
+            start: map_out_fn_source_position(span.start),
+            end: map_out_fn_source_position(span.end),
+        }),
+    }
+}
+
+fn map_out_fn_source_position(position: l3_fn_build::FnSourcePosition) -> FnSourcePosition {
+    FnSourcePosition {
+        line: position.line as u32,
+        column: position.column as u32,
+        offset: position.offset as u32,
+    }
+}
+
 export!(FnParsingWasm);
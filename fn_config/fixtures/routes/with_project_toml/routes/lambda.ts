@@ -0,0 +1,3 @@
+export function DELETE() {
+    return 'deleted'
+}
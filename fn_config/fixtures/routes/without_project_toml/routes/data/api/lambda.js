@@ -0,0 +1,11 @@
+export function GET() {
+    return formatData('data')
+}
+
+export const POST = async () => {
+    return formatData('created')
+}
+
+export function formatData(data) {
+    return { data }
+}
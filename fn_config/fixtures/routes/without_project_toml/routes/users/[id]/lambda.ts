@@ -0,0 +1,3 @@
+export async function GET(event) {
+    return { id: event.pathParameters.id }
+}
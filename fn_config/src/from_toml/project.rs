@@ -17,13 +17,25 @@ impl TryFrom<&toml::Table> for ProjectConfig {
                             toml::Value::Table(lambda_t) => {
                                 lambdas.push(LambdaConfig::try_from(lambda_t)?)
                             }
-                            _ => panic!(),
+                            _ => {
+                                return Err(ConfigParseError::Misconfigured {
+                                    field: "[[lambda]]".into(),
+                                    expected: "a table".into(),
+                                    actual: lambda_v.type_str().into(),
+                                })
+                            }
                         }
                     }
                     lambdas
                 }
                 toml::Value::Table(lambda_t) => vec![LambdaConfig::try_from(lambda_t)?],
-                _ => panic!("toml key lambda is neither [lambda] nor [[lambda]]"),
+                _ => {
+                    return Err(ConfigParseError::Misconfigured {
+                        field: "lambda".into(),
+                        expected: "[lambda] or [[lambda]] tables".into(),
+                        actual: lambda_v.type_str().into(),
+                    })
+                }
             },
         };
         Ok(Self {
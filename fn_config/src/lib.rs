@@ -10,12 +10,13 @@ mod model_test;
 
 use std::{
     collections::{HashMap, HashSet},
-    fs,
-    path::PathBuf,
+    fs, io, iter,
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
-use l3_fn_env::EnvVarsParseError;
+use l3_ast_parse::FnHandler;
+use l3_fn_env::{merge_env_vars, parse_env_file, EnvFileParseError, EnvVarsParseError};
 
 use configs::ProjectConfig;
 
@@ -23,6 +24,8 @@ pub use model::*;
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum ConfigParseError {
+    #[error("{0}")]
+    EnvFileParseError(#[from] EnvFileParseError),
     #[error("{0}")]
     EnvVarsParseError(#[from] EnvVarsParseError),
     #[error("io error reading {path} env file: {kind}")]
@@ -38,8 +41,20 @@ pub enum ConfigParseError {
         expected: String,
         actual: String,
     },
+    #[error("route {route} from {path} collides with route from {colliding_path}")]
+    HttpRouteCollision {
+        route: String,
+        path: PathBuf,
+        colliding_path: PathBuf,
+    },
+    #[error("invalid http route {path}: {cause}")]
+    InvalidHttpRoute { path: PathBuf, cause: String },
+    #[error("error parsing route source {path}: {cause}")]
+    RouteSourceParseError { path: PathBuf, cause: String },
     #[error("{0}")]
     UnresolvedLanguage(#[from] UnresolvedLanguage),
+    #[error("{0} is not l3.toml or a route source")]
+    UnsupportedConfigPath(PathBuf),
     // #[error("error parsing {file_name} env vars: {cause}")]
     // SyntaxError {
     //     cause: String,
@@ -65,12 +80,29 @@ impl ConfigUpdate {
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub enum UpdateMutation {
     ProjectEnv,
+    /// A lambda was configured by l3.toml or discovered from a route source.
+    AddedLambda(String),
+    /// A lambda's l3.toml [[lambda]] table or route source handler was removed.
+    RemovedLambda(String),
+    /// A lambda's l3.toml [[lambda]] table changed its handler, source or runtime.
+    UpdatedLambda(String),
 }
 
+/// Directory of the project containing HTTP route lambda sources.
+const ROUTES_DIR: &str = "routes";
+
+/// File stem of the route source of a ./routes directory, such as `routes/data/lambda.js`.
+const ROUTE_SOURCE_STEM: &str = "lambda";
+
 pub struct LLLConfigs {
     lambdas: HashMap<String, Arc<LambdaSpec>>,
     project: Option<ProjectConfig>,
     project_dir: Arc<PathBuf>,
+    /// Sources of handlers re-exported by each route source.
+    route_re_exports: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Route lambdas discovered from each route source, including routes losing a collision.
+    route_sources: HashMap<PathBuf, Vec<Arc<LambdaSpec>>>,
+    routes: HashMap<String, Arc<LambdaSpec>>,
 }
 
 impl LLLConfigs {
@@ -79,12 +111,27 @@ impl LLLConfigs {
             project_dir,
             lambdas: HashMap::new(),
             project: None,
+            route_re_exports: HashMap::new(),
+            route_sources: HashMap::new(),
+            routes: HashMap::new(),
         }
     }
 
     // lookup any discrete or routing configured lambda by name
     pub fn lambda_by_name(&self, name: &str) -> Option<Arc<LambdaSpec>> {
-        self.lambdas.get(name).cloned()
+        self.lambdas
+            .get(name)
+            .or_else(|| self.routes.get(name))
+            .cloned()
+    }
+
+    // discrete and routing lambdas
+    pub fn lambdas(&self) -> Vec<Arc<LambdaSpec>> {
+        self.lambdas
+            .values()
+            .chain(self.routes.values())
+            .cloned()
+            .collect()
     }
 
     // lambdas configured via l3.toml [[lambdas]]
@@ -92,50 +139,142 @@ impl LLLConfigs {
         self.lambdas.values().cloned().collect()
     }
 
-    // env file paths configured via l3.toml [env_files]
-    // fn project_env_files(&self) -> Option<&Vec<Arc<PathBuf>>> {
-    //     self.project
-    //         .as_ref()
-    //         .and_then(|p| p.env.as_ref())
-    //         .and_then(|e| e.env_files.as_ref())
-    // }
+    // lambdas discovered from exported http method handlers of sources in ./routes
+    pub fn routed_lambdas(&self) -> Vec<Arc<LambdaSpec>> {
+        self.routes.values().cloned().collect()
+    }
 
-    // env vars configured via l3.toml [env]
-    // fn project_env_vars(&self) -> Option<&HashMap<String, String>> {
-    //     self.project
-    //         .as_ref()
-    //         .and_then(|p| p.env.as_ref())
-    //         .and_then(|e| e.env_vars.as_ref())
-    // }
+    /// Env vars of a lambda merged from l3.toml's project and [[lambda]] `env_files` and
+    /// `env_vars`, where a lambda's env takes precedence over the project's and env vars take
+    /// precedence over env files.
+    pub fn lambda_env_vars(&self, name: &str) -> Result<HashMap<String, String>, ConfigParseError> {
+        let project_env = self.project.as_ref().and_then(|p| p.env.as_ref());
+        let lambda_env = self
+            .project
+            .as_ref()
+            .and_then(|p| p.lambdas.iter().find(|l| l.name.as_deref() == Some(name)))
+            .and_then(|l| l.env.as_ref());
+        let mut env_vars = Vec::new();
+        for env in [project_env, lambda_env].into_iter().flatten() {
+            for env_file in env.env_files.iter().flatten() {
+                env_vars.push(Some(parse_env_file(
+                    &self.project_dir.join(env_file.as_path()),
+                )?));
+            }
+            env_vars.push(env.env_vars.clone());
+        }
+        Ok(merge_env_vars(env_vars))
+    }
+
+    /// Node.js version of a lambda configured by l3.toml, where a [[lambda]]'s `node_version`
+    /// takes precedence over the project's.
+    pub fn node_version(&self, name: &str) -> Option<NodeVersion> {
+        let node_version = |runtime: Option<&configs::LambdaRuntimeConfig>| {
+            runtime
+                .and_then(|runtime| runtime.node.as_ref())
+                .and_then(|node| node.version.clone())
+        };
+        let project = self.project.as_ref()?;
+        project
+            .lambdas
+            .iter()
+            .find(|l| l.name.as_deref() == Some(name))
+            .and_then(|l| node_version(l.runtime.as_ref()))
+            .or_else(|| node_version(project.runtime.as_ref()))
+    }
+
+    /// Python version of a lambda configured by l3.toml, where a [[lambda]]'s `python_version`
+    /// takes precedence over the project's.
+    pub fn python_version(&self, name: &str) -> Option<PythonVersion> {
+        let python_version = |runtime: Option<&configs::LambdaRuntimeConfig>| {
+            runtime
+                .and_then(|runtime| runtime.python.as_ref())
+                .and_then(|python| python.version.clone())
+        };
+        let project = self.project.as_ref()?;
+        project
+            .lambdas
+            .iter()
+            .find(|l| l.name.as_deref() == Some(name))
+            .and_then(|l| python_version(l.runtime.as_ref()))
+            .or_else(|| python_version(project.runtime.as_ref()))
+    }
 
     pub fn update_all_configs(&mut self) -> ConfigUpdate {
-        let paths = ["l3.toml"].iter().map(PathBuf::from).collect();
+        let mut paths: Vec<PathBuf> = vec![PathBuf::from("l3.toml")];
+        // previously discovered route sources are included to remove routes of deleted sources
+        for route_source in self
+            .collect_route_sources()
+            .into_iter()
+            .chain(self.route_sources.keys().cloned())
+        {
+            if !paths.contains(&route_source) {
+                paths.push(route_source);
+            }
+        }
         self.update_configs(&paths)
     }
 
     pub fn update_configs(&mut self, paths: &Vec<PathBuf>) -> ConfigUpdate {
         let mut result = ConfigUpdate::default();
+        let mut route_sources: Vec<PathBuf> = Vec::new();
         for path in paths {
             match path.to_str() {
                 Some("l3.toml") => {
                     result.extend(self.update_project_config());
                 }
-                _ => panic!(),
+                _ if path.starts_with(ROUTES_DIR) || self.is_route_handler_source(path) => {
+                    if path.starts_with(ROUTES_DIR) && !route_sources.contains(path) {
+                        route_sources.push(path.clone());
+                    }
+                    // routes are rediscovered from the route sources re-exporting a changed source
+                    for (route_source, re_exports) in &self.route_re_exports {
+                        if re_exports.contains(path) && !route_sources.contains(route_source) {
+                            route_sources.push(route_source.clone());
+                        }
+                    }
+                }
+                _ => result
+                    .config_errs
+                    .push(ConfigParseError::UnsupportedConfigPath(path.clone())),
+            }
+        }
+        if !route_sources.is_empty() {
+            for route_source in &route_sources {
+                result.extend(self.update_route_source(route_source));
             }
+            let updated: Vec<&Path> = route_sources.iter().map(PathBuf::as_path).collect();
+            result.extend(self.resolve_routes(&updated));
         }
         result
     }
 
+    /// Whether a source defines handlers re-exported by a route source.
+    pub fn is_route_handler_source(&self, path: &Path) -> bool {
+        self.route_re_exports
+            .values()
+            .any(|re_exports| re_exports.iter().any(|p| p == path))
+    }
+
     fn update_project_config(&mut self) -> ConfigUpdate {
         let mut result = ConfigUpdate::default();
-        let pc = match self.read_from_project_dir(&"l3.toml".into()) {
-            Ok(pc) => ProjectConfig::try_from(&pc.parse::<toml::Table>().unwrap()).unwrap(),
+        let pc = match self
+            .read_from_project_dir(&"l3.toml".into())
+            .and_then(|pc| ProjectConfig::try_from(&pc.parse::<toml::Table>()?))
+        {
+            Ok(pc) => pc,
+            // a project of only routes does not require an l3.toml
+            Err(ConfigParseError::IoError {
+                kind: io::ErrorKind::NotFound,
+                ..
+            }) => ProjectConfig::default(),
             Err(err) => {
                 result.config_errs.push(err);
                 return result;
             }
         };
 
+        let mut lambdas = HashMap::new();
         for lambda in &pc.lambdas {
             if let (Some(language), Some(name), Some(handler), Some(source)) = (
                 &lambda.language,
@@ -143,28 +282,210 @@ impl LLLConfigs {
                 lambda.handler.clone(),
                 lambda.source.clone(),
             ) {
-                let runtime = match language {
-                    Language::JavaScript | Language::TypeScript => LambdaRuntimeSpec::Node,
-                    Language::Python => LambdaRuntimeSpec::Python,
-                };
-                self.lambdas.insert(
-                    lambda.name.clone().unwrap(),
-                    Arc::new(LambdaSpec {
-                        name,
-                        handler,
-                        source,
-                        runtime,
-                    }),
-                );
+                let lambda_spec = Arc::new(LambdaSpec {
+                    name: name.clone(),
+                    handler,
+                    source,
+                    runtime: LambdaRuntimeSpec::from(language),
+                    routing: None,
+                });
+                match self.lambdas.get(&name) {
+                    None => {
+                        result
+                            .mutations
+                            .insert(UpdateMutation::AddedLambda(name.clone()));
+                    }
+                    Some(previous) if *previous != lambda_spec => {
+                        result
+                            .mutations
+                            .insert(UpdateMutation::UpdatedLambda(name.clone()));
+                    }
+                    Some(_) => {}
+                }
+                lambdas.insert(name, lambda_spec);
             } else {
-                panic!();
+                let missing = [
+                    ("name", lambda.name.is_none()),
+                    ("source", lambda.source.is_none()),
+                    ("handler", lambda.handler.is_none()),
+                ]
+                .into_iter()
+                .filter_map(|(field, missing)| missing.then_some(field))
+                .collect::<Vec<&str>>();
+                result.config_errs.push(ConfigParseError::Misconfigured {
+                    field: match &lambda.name {
+                        Some(name) => format!("[[lambda]] {name}"),
+                        None => "[[lambda]]".into(),
+                    },
+                    expected: "configured with name, source and handler".into(),
+                    actual: format!("missing {}", missing.join(", ")),
+                });
+            }
+        }
+        // an invalid l3.toml does not reconfigure the previous l3.toml's lambdas
+        if !result.config_errs.is_empty() {
+            result.mutations.clear();
+            return result;
+        }
+        for name in self.lambdas.keys() {
+            if !lambdas.contains_key(name) {
+                result
+                    .mutations
+                    .insert(UpdateMutation::RemovedLambda(name.clone()));
             }
         }
 
+        self.lambdas = lambdas;
         self.project = Some(pc);
         result
     }
 
+    // reconfigures the routes discovered from a source in ./routes for its exported http method
+    // handlers, including handlers re-exported from other sources, which are routed by
+    // resolve_routes with the route source as the lambda's source
+    fn update_route_source(&mut self, path: &Path) -> ConfigUpdate {
+        debug_assert!(path.is_relative());
+        let mut result = ConfigUpdate::default();
+        let mut route_lambdas: Vec<Arc<LambdaSpec>> = Vec::new();
+        let mut re_exports: Vec<PathBuf> = Vec::new();
+        let abs_path = self.project_dir.join(path);
+        if abs_path.is_file() && is_route_source(path) {
+            if let Ok(language) = Language::try_from(path) {
+                let collected = match language {
+                    Language::Python => l3_ast_parse::collect_exported_fns(&abs_path),
+                    Language::JavaScript | Language::TypeScript => {
+                        let project_dir = self.project_dir.clone();
+                        l3_ast_parse::ecmascript::collect_exported_fns_with_re_exports(
+                            &project_dir,
+                            path,
+                            &mut |from, specifier| {
+                                let resolved = resolve_re_export(&project_dir, from, specifier)?;
+                                if !re_exports.contains(&resolved) {
+                                    re_exports.push(resolved.clone());
+                                }
+                                Some(resolved)
+                            },
+                        )
+                    }
+                };
+                match collected {
+                    Ok(handlers) => {
+                        for FnHandler { name: handler, .. } in handlers {
+                            // exported fns that are not http method handlers are not routed
+                            if HttpMethod::try_from(handler.as_str()).is_err() {
+                                continue;
+                            }
+                            let route = match HttpRoute::new(path, &handler) {
+                                Ok(route) => route,
+                                Err(err) => {
+                                    result.config_errs.push(ConfigParseError::InvalidHttpRoute {
+                                        path: path.to_path_buf(),
+                                        cause: err.to_string(),
+                                    });
+                                    continue;
+                                }
+                            };
+                            route_lambdas.push(Arc::new(LambdaSpec {
+                                name: route.to_lambda_name(),
+                                handler,
+                                source: path.to_path_buf(),
+                                runtime: LambdaRuntimeSpec::from(&language),
+                                routing: Some(LambdaRouting::HttpRoute(route)),
+                            }));
+                        }
+                    }
+                    Err(err) => result
+                        .config_errs
+                        .push(ConfigParseError::RouteSourceParseError {
+                            path: path.to_path_buf(),
+                            cause: err.to_string(),
+                        }),
+                }
+            }
+        }
+        if route_lambdas.is_empty() {
+            self.route_sources.remove(path);
+        } else {
+            self.route_sources.insert(path.to_path_buf(), route_lambdas);
+        }
+        if re_exports.is_empty() {
+            self.route_re_exports.remove(path);
+        } else {
+            self.route_re_exports.insert(path.to_path_buf(), re_exports);
+        }
+        result
+    }
+
+    // routes lambdas of all route sources in order of their source paths so that the route of
+    // the first source wins a collision, reporting collisions with a route of an updated source
+    fn resolve_routes(&mut self, updated: &[&Path]) -> ConfigUpdate {
+        let mut result = ConfigUpdate::default();
+        let mut sources: Vec<&PathBuf> = self.route_sources.keys().collect();
+        sources.sort();
+        let mut routes: HashMap<String, Arc<LambdaSpec>> = HashMap::new();
+        for lambda in sources
+            .into_iter()
+            .flat_map(|source| self.route_sources.get(source).unwrap())
+        {
+            let Some(LambdaRouting::HttpRoute(route)) = &lambda.routing else {
+                continue;
+            };
+            if let Some(colliding) = find_colliding_route(&routes, &lambda.name, route) {
+                if updated.contains(&lambda.source.as_path())
+                    || updated.contains(&colliding.source.as_path())
+                {
+                    result
+                        .config_errs
+                        .push(ConfigParseError::HttpRouteCollision {
+                            route: format!("{} /{}", route.method, route.path),
+                            path: lambda.source.clone(),
+                            colliding_path: colliding.source.clone(),
+                        });
+                }
+                continue;
+            }
+            routes.insert(lambda.name.clone(), lambda.clone());
+        }
+        for (name, lambda) in &routes {
+            // a route now won by another source is re-added to be rebuilt from its source
+            if self
+                .routes
+                .get(name)
+                .is_none_or(|previous| previous.source != lambda.source)
+            {
+                result
+                    .mutations
+                    .insert(UpdateMutation::AddedLambda(name.clone()));
+            }
+        }
+        for name in self.routes.keys() {
+            if !routes.contains_key(name) {
+                result
+                    .mutations
+                    .insert(UpdateMutation::RemovedLambda(name.clone()));
+            }
+        }
+        self.routes = routes;
+        result
+    }
+
+    // relative paths of all route sources in ./routes
+    fn collect_route_sources(&self) -> Vec<PathBuf> {
+        let routes_dir = self.project_dir.join(ROUTES_DIR);
+        if !routes_dir.is_dir() {
+            return Vec::new();
+        }
+        l3_api_base::collect_files(&routes_dir)
+            .into_iter()
+            .map(|p| {
+                p.strip_prefix(self.project_dir.as_path())
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .filter(|p| is_route_source(p))
+            .collect()
+    }
+
     // read path relative to project dir
     fn read_from_project_dir(&self, p: &PathBuf) -> Result<String, ConfigParseError> {
         debug_assert!(p.is_relative());
@@ -175,6 +496,56 @@ impl LLLConfigs {
     }
 }
 
+// whether a path is the lambda.{js,mjs,ts,py} route source of its dir, excluding the helper
+// modules of a route
+fn is_route_source(path: &Path) -> bool {
+    path.file_stem()
+        .is_some_and(|stem| stem == ROUTE_SOURCE_STEM)
+        && Language::try_from(path).is_ok()
+}
+
+// resolves a relative specifier re-exported by a route source to a project source, including a TS
+// source re-exported by the .js path of its build output
+fn resolve_re_export(project_dir: &Path, from: &Path, specifier: &str) -> Option<PathBuf> {
+    if !specifier.starts_with('.') {
+        return None;
+    }
+    let mut path = PathBuf::new();
+    for component in from.parent()?.join(specifier).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !path.pop() {
+                    return None;
+                }
+            }
+            component => path.push(component),
+        }
+    }
+    let ts_paths = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("js") => vec![path.with_extension("ts"), path.with_extension("tsx")],
+        _ => Vec::new(),
+    };
+    iter::once(path)
+        .chain(ts_paths)
+        .find(|path| project_dir.join(path).is_file())
+}
+
+// a routed lambda matching the same requests or using the same lambda name as route
+fn find_colliding_route(
+    routes: &HashMap<String, Arc<LambdaSpec>>,
+    name: &str,
+    route: &HttpRoute,
+) -> Option<Arc<LambdaSpec>> {
+    routes
+        .values()
+        .find(|l| match &l.routing {
+            Some(LambdaRouting::HttpRoute(other)) => l.name == name || route.collides_with(other),
+            None => false,
+        })
+        .cloned()
+}
+
 pub trait OptionallyParsable<T>: Sized {
     fn has_value(value: &T) -> bool;
 
@@ -1,12 +1,15 @@
 use std::{
-    fs::{copy, read_dir, read_to_string},
+    collections::{HashMap, HashSet},
+    fs::{copy, create_dir_all, read_dir, read_to_string, remove_file, write},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use temp_dir::TempDir;
 
-use crate::LLLConfigs;
+use crate::{
+    ConfigParseError, LLLConfigs, LambdaRouting, NodeVersion, PythonVersion, UpdateMutation,
+};
 
 #[test]
 fn test_fn_config_fixtures() {
@@ -15,6 +18,422 @@ fn test_fn_config_fixtures() {
     }
 }
 
+#[test]
+fn test_update_configs_for_route_source_mutations() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/data")).unwrap();
+    write(
+        temp_dir.child("routes/data/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert!(update.config_errs.is_empty());
+    assert!(update
+        .mutations
+        .contains(&UpdateMutation::AddedLambda("get-data".into())));
+    assert!(configs.lambda_by_name("get-data").is_some());
+
+    write(
+        temp_dir.child("routes/data/lambda.js"),
+        "export function PUT() {}",
+    )
+    .unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("routes/data/lambda.js")]);
+    assert_eq!(
+        update.mutations,
+        HashSet::from([
+            UpdateMutation::AddedLambda("put-data".into()),
+            UpdateMutation::RemovedLambda("get-data".into()),
+        ])
+    );
+
+    remove_file(temp_dir.child("routes/data/lambda.js")).unwrap();
+    let update = configs.update_all_configs();
+    assert_eq!(
+        update.mutations,
+        HashSet::from([UpdateMutation::RemovedLambda("put-data".into())])
+    );
+    assert!(configs.lambdas().is_empty());
+}
+
+#[test]
+fn test_update_configs_routes_handlers_re_exported_from_lib_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/a")).unwrap();
+    create_dir_all(temp_dir.child("lib")).unwrap();
+    write(
+        temp_dir.child("routes/a/lambda.js"),
+        "export { DELETE } from '../../lib/impl.js'",
+    )
+    .unwrap();
+    write(
+        temp_dir.child("lib/impl.js"),
+        "export const DELETE = async () => {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert!(update.config_errs.is_empty());
+    assert_eq!(
+        update.mutations,
+        HashSet::from([UpdateMutation::AddedLambda("delete-a".into())])
+    );
+    let lambda = configs.lambda_by_name("delete-a").unwrap();
+    assert_eq!(lambda.source, PathBuf::from("routes/a/lambda.js"));
+    assert_eq!(lambda.handler, "DELETE");
+    assert!(configs.is_route_handler_source(Path::new("lib/impl.js")));
+
+    write(
+        temp_dir.child("lib/impl.js"),
+        "export const PATCH = async () => {}",
+    )
+    .unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("lib/impl.js")]);
+    assert_eq!(
+        update.mutations,
+        HashSet::from([UpdateMutation::RemovedLambda("delete-a".into())])
+    );
+}
+
+#[test]
+fn test_update_configs_routes_handlers_re_exported_from_route_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/a")).unwrap();
+    write(
+        temp_dir.child("routes/a/lambda.js"),
+        "export { DELETE } from './impl.js'",
+    )
+    .unwrap();
+    write(
+        temp_dir.child("routes/a/impl.js"),
+        "export const DELETE = async () => {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert!(update.config_errs.is_empty());
+    assert_eq!(
+        update.mutations,
+        HashSet::from([UpdateMutation::AddedLambda("delete-a".into())])
+    );
+    let lambda = configs.lambda_by_name("delete-a").unwrap();
+    assert_eq!(lambda.source, PathBuf::from("routes/a/lambda.js"));
+    assert_eq!(lambda.handler, "DELETE");
+}
+
+#[test]
+fn test_update_configs_only_routes_lambda_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/data")).unwrap();
+    write(
+        temp_dir.child("routes/data/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    write(
+        temp_dir.child("routes/data/impl.js"),
+        "export function GET() {}\nexport function DELETE() {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert!(update.config_errs.is_empty());
+    assert_eq!(
+        update.mutations,
+        HashSet::from([UpdateMutation::AddedLambda("get-data".into())])
+    );
+    assert_eq!(
+        configs.lambda_by_name("get-data").unwrap().source,
+        PathBuf::from("routes/data/lambda.js")
+    );
+
+    let update = configs.update_configs(&vec![PathBuf::from("routes/data/impl.js")]);
+    assert!(update.config_errs.is_empty());
+    assert!(update.mutations.is_empty());
+}
+
+#[test]
+fn test_update_configs_errors_for_colliding_routes() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/users/[id]")).unwrap();
+    create_dir_all(temp_dir.child("routes/users/[user_id]")).unwrap();
+    write(
+        temp_dir.child("routes/users/[id]/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    write(
+        temp_dir.child("routes/users/[user_id]/lambda.js"),
+        "export function GET() {}\nexport function DELETE() {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert_eq!(update.config_errs.len(), 1);
+    assert!(matches!(
+        update.config_errs.first().unwrap(),
+        ConfigParseError::HttpRouteCollision { .. }
+    ));
+    assert_eq!(configs.routed_lambdas().len(), 2);
+    assert!(configs.lambda_by_name("delete-users-user_id").is_some());
+}
+
+#[test]
+fn test_update_configs_routes_collision_by_source_path_order() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/users/[id]")).unwrap();
+    create_dir_all(temp_dir.child("routes/users/[user_id]")).unwrap();
+    write(
+        temp_dir.child("routes/users/[user_id]/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+
+    write(
+        temp_dir.child("routes/users/[id]/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("routes/users/[id]/lambda.js")]);
+    assert_eq!(
+        update.config_errs,
+        vec![ConfigParseError::HttpRouteCollision {
+            route: "GET /users/{user_id}".into(),
+            path: PathBuf::from("routes/users/[user_id]/lambda.js"),
+            colliding_path: PathBuf::from("routes/users/[id]/lambda.js"),
+        }]
+    );
+    assert_eq!(
+        update.mutations,
+        HashSet::from([
+            UpdateMutation::AddedLambda("get-users-id".into()),
+            UpdateMutation::RemovedLambda("get-users-user_id".into()),
+        ])
+    );
+
+    remove_file(temp_dir.child("routes/users/[id]/lambda.js")).unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("routes/users/[id]/lambda.js")]);
+    assert!(update.config_errs.is_empty());
+    assert_eq!(
+        update.mutations,
+        HashSet::from([
+            UpdateMutation::AddedLambda("get-users-user_id".into()),
+            UpdateMutation::RemovedLambda("get-users-id".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_update_configs_errors_for_invalid_route_path_param() {
+    let temp_dir = TempDir::new().unwrap();
+    create_dir_all(temp_dir.child("routes/files/[...path]/[id]")).unwrap();
+    write(
+        temp_dir.child("routes/files/[...path]/[id]/lambda.js"),
+        "export function GET() {}",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_all_configs();
+    assert_eq!(
+        update.config_errs,
+        vec![ConfigParseError::InvalidHttpRoute {
+            path: PathBuf::from("routes/files/[...path]/[id]/lambda.js"),
+            cause: "catch-all path param path must be the last path segment".into(),
+        }]
+    );
+    assert!(configs.routed_lambdas().is_empty());
+}
+
+#[test]
+fn test_update_configs_errors_for_invalid_project_config() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.child("data.js"), "export function handler() {}").unwrap();
+    write(
+        temp_dir.child("l3.toml"),
+        "[[lambda]]\nname = \"data\"\nsource = \"data.js\"\nhandler = \"handler\"\n",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+
+    write(
+        temp_dir.child("l3.toml"),
+        "[[lambda]]\nname = \"data\"\nsource = \"data.js\"\n",
+    )
+    .unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("l3.toml")]);
+    assert_eq!(
+        update.config_errs,
+        vec![ConfigParseError::Misconfigured {
+            field: "[[lambda]] data".into(),
+            expected: "configured with name, source and handler".into(),
+            actual: "missing handler".into(),
+        }]
+    );
+    assert!(update.mutations.is_empty());
+    assert!(configs.lambda_by_name("data").is_some());
+
+    write(temp_dir.child("l3.toml"), "[[lambda]\n").unwrap();
+    let update = configs.update_configs(&vec![PathBuf::from("l3.toml")]);
+    assert!(matches!(
+        update.config_errs.as_slice(),
+        [ConfigParseError::TomlParseError(_)]
+    ));
+    assert!(configs.lambda_by_name("data").is_some());
+}
+
+#[test]
+fn test_update_configs_for_updated_project_lambda() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.child("data.js"), "export function handler() {}").unwrap();
+    write(temp_dir.child("data.py"), "def handler():\n    pass\n").unwrap();
+    write(
+        temp_dir.child("l3.toml"),
+        "[[lambda]]\nname = \"data\"\nsource = \"data.js\"\nhandler = \"handler\"\n",
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+
+    let update = configs.update_configs(&vec![PathBuf::from("l3.toml")]);
+    assert!(update.mutations.is_empty());
+
+    for (source, handler) in [("data.js", "GET"), ("data.py", "GET")] {
+        write(
+            temp_dir.child("l3.toml"),
+            format!(
+                "[[lambda]]\nname = \"data\"\nsource = \"{source}\"\nhandler = \"{handler}\"\n"
+            ),
+        )
+        .unwrap();
+        let update = configs.update_configs(&vec![PathBuf::from("l3.toml")]);
+        assert!(update.config_errs.is_empty());
+        assert_eq!(
+            update.mutations,
+            HashSet::from([UpdateMutation::UpdatedLambda("data".into())])
+        );
+        let lambda = configs.lambda_by_name("data").unwrap();
+        assert_eq!(lambda.source, PathBuf::from(source));
+        assert_eq!(lambda.handler, handler);
+    }
+}
+
+#[test]
+fn test_update_configs_errors_for_unsupported_config_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    let update = configs.update_configs(&vec![PathBuf::from("lib/data.js")]);
+    assert_eq!(
+        update.config_errs,
+        vec![ConfigParseError::UnsupportedConfigPath(PathBuf::from(
+            "lib/data.js"
+        ))]
+    );
+    assert!(update.mutations.is_empty());
+}
+
+#[test]
+fn test_lambda_env_vars_merges_project_and_lambda_env() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.child(".env"), "FROM_FILE=project\nOVERRIDDEN=file").unwrap();
+    write(temp_dir.child("data.env"), "LAMBDA_FILE=data").unwrap();
+    write(temp_dir.child("data.js"), "export function handler() {}").unwrap();
+    write(
+        temp_dir.child("l3.toml"),
+        r#"
+env_files = [".env"]
+env_vars = { OVERRIDDEN = "project", PROJECT = "project" }
+
+[[lambda]]
+name = "data"
+source = "data.js"
+handler = "handler"
+env_files = ["data.env"]
+env_vars = ["PROJECT=data"]
+"#,
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+    assert_eq!(
+        configs.lambda_env_vars("data").unwrap(),
+        HashMap::from([
+            ("FROM_FILE".into(), "project".into()),
+            ("LAMBDA_FILE".into(), "data".into()),
+            ("OVERRIDDEN".into(), "project".into()),
+            ("PROJECT".into(), "data".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_python_version_of_lambda_overrides_project() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.child("data.py"), "def handler(): pass").unwrap();
+    write(temp_dir.child("other.py"), "def handler(): pass").unwrap();
+    write(
+        temp_dir.child("l3.toml"),
+        r#"
+python_version = 3.12
+
+[[lambda]]
+name = "data"
+source = "data.py"
+handler = "handler"
+python_version = 3.13
+
+[[lambda]]
+name = "other"
+source = "other.py"
+handler = "handler"
+"#,
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+    assert_eq!(
+        configs.python_version("data"),
+        Some(PythonVersion::ThreeThirteen)
+    );
+    assert_eq!(
+        configs.python_version("other"),
+        Some(PythonVersion::ThreeTwelve)
+    );
+}
+
+#[test]
+fn test_node_version_of_lambda_overrides_project() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.child("data.js"), "export function handler() {}").unwrap();
+    write(temp_dir.child("other.js"), "export function handler() {}").unwrap();
+    write(
+        temp_dir.child("l3.toml"),
+        r#"
+node_version = 20
+
+[[lambda]]
+name = "data"
+source = "data.js"
+handler = "handler"
+node_version = 18
+
+[[lambda]]
+name = "other"
+source = "other.js"
+handler = "handler"
+"#,
+    )
+    .unwrap();
+    let mut configs = LLLConfigs::new(Arc::new(temp_dir.path().to_path_buf()));
+    assert!(configs.update_all_configs().config_errs.is_empty());
+    assert_eq!(configs.node_version("data"), Some(NodeVersion::Eighteen));
+    assert_eq!(configs.node_version("other"), Some(NodeVersion::Twenty));
+}
+
 fn collect_fixture_dirs(p: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
     let mut fixture_dirs = Vec::new();
     for dir_entry_result in read_dir(p)? {
@@ -58,10 +477,12 @@ impl ConfigFixture {
             if from.is_file() {
                 let to = self
                     .temp_dir
-                    .child(from.file_name().unwrap().to_string_lossy());
+                    .path()
+                    .join(from.strip_prefix(&self.fixture_dir).unwrap());
+                create_dir_all(to.parent().unwrap()).unwrap();
                 copy(&from, &to).unwrap();
             } else if from.is_dir() && !from.ends_with(".fixture") {
-                self.copy_to_temp_dir(&path.join(from));
+                self.copy_to_temp_dir(&from);
             }
         }
     }
@@ -71,7 +492,7 @@ impl ConfigFixture {
         let mut configs = LLLConfigs::new(Arc::new(self.temp_dir.path().to_path_buf()));
         let update = configs.update_all_configs();
         assert!(update.config_errs.is_empty());
-        let mut lambdas = configs.discrete_lambdas();
+        let mut lambdas = configs.lambdas();
         let expected_lambdas: serde_json::Value = serde_json::from_str(
             &read_to_string(self.fixture_dir.join(".fixture/lambdas.json")).unwrap(),
         )
@@ -91,6 +512,19 @@ impl ConfigFixture {
                         expected_lambda.get("handler").unwrap().as_str().unwrap(),
                         lambda.handler
                     );
+                    assert_eq!(
+                        expected_lambda.get("route").map(|route| {
+                            (
+                                route.get("method").unwrap().as_str().unwrap().to_string(),
+                                route.get("path").unwrap().as_str().unwrap().to_string(),
+                            )
+                        }),
+                        lambda.routing.as_ref().map(|routing| match routing {
+                            LambdaRouting::HttpRoute(route) =>
+                                (route.method.to_string(), route.path.clone()),
+                        }),
+                        "routing of lambda {expected_lambda_name}"
+                    );
                 }
             }
         }
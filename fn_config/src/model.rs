@@ -57,6 +57,22 @@ pub enum HttpMethod {
     Put,
 }
 
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                HttpMethod::Get => "GET",
+                HttpMethod::Delete => "DELETE",
+                HttpMethod::Patch => "PATCH",
+                HttpMethod::Post => "POST",
+                HttpMethod::Put => "PUT",
+            }
+        )
+    }
+}
+
 impl<'a> TryFrom<&'a str> for HttpMethod {
     type Error = anyhow::Error;
 
@@ -75,35 +91,145 @@ impl<'a> TryFrom<&'a str> for HttpMethod {
     }
 }
 
+/// A path parameter of an HttpRoute resolved from a bracketed directory name of a route source.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HttpPathParam {
+    /// Captures a single path segment with a directory such as `routes/users/[id]`.
+    Segment(String),
+    /// Captures the remaining path segments with a directory such as `routes/files/[...path]`.
+    CatchAll(String),
+}
+
+impl HttpPathParam {
+    pub fn name(&self) -> &str {
+        match self {
+            HttpPathParam::Segment(name) | HttpPathParam::CatchAll(name) => name.as_str(),
+        }
+    }
+
+    fn from_dirname(dirname: &str) -> Result<Option<Self>, anyhow::Error> {
+        let Some(param) = dirname
+            .strip_prefix('[')
+            .and_then(|param| param.strip_suffix(']'))
+        else {
+            return Ok(None);
+        };
+        let (name, catch_all) = match param.strip_prefix("...") {
+            Some(name) => (name, true),
+            None => (param, false),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(anyhow!(
+                "path param directory {dirname} has an invalid name"
+            ));
+        }
+        Ok(Some(if catch_all {
+            HttpPathParam::CatchAll(name.to_string())
+        } else {
+            HttpPathParam::Segment(name.to_string())
+        }))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HttpRoute {
     pub method: HttpMethod,
+    /// Route path in API Gateway format such as `users/{id}` or `files/{path+}`.
     pub path: String,
+    /// Path params in the order they appear in the route's path.
+    pub params: Vec<HttpPathParam>,
 }
 
 impl HttpRoute {
     pub fn new(path: &Path, handler_fn_name: &str) -> Result<Self, anyhow::Error> {
         debug_assert!(path.is_relative());
-        match Self::extract_http_path(path) {
-            None => Err(anyhow!("not an http route source path")),
-            Some(path) => match HttpMethod::try_from(handler_fn_name) {
-                Err(err) => Err(err),
-                Ok(method) => Ok(Self { method, path }),
-            },
+        let (path, params) = Self::extract_http_path(path)?;
+        let method = HttpMethod::try_from(handler_fn_name)?;
+        Ok(Self {
+            method,
+            path,
+            params,
+        })
+    }
+
+    /// Creates a lambda name unique to the route's method and path, such as `get-data-api`
+    /// for `GET /data/api`.
+    pub fn to_lambda_name(&self) -> String {
+        let method = self.method.to_string().to_lowercase();
+        if self.path.is_empty() {
+            method
+        } else {
+            format!(
+                "{method}-{}",
+                self.path.replace('/', "-").replace(['{', '}', '+'], "")
+            )
         }
     }
 
-    fn extract_http_path(path: &Path) -> Option<String> {
+    /// Route path with param names erased for comparing whether two routes match the same
+    /// requests, such as `users/{}` for `users/{id}` and `files/{+}` for `files/{path+}`.
+    pub fn normalized_path(&self) -> String {
+        let mut path = self.path.clone();
+        for param in &self.params {
+            path = match param {
+                HttpPathParam::Segment(name) => path.replace(&format!("{{{name}}}"), "{}"),
+                HttpPathParam::CatchAll(name) => path.replace(&format!("{{{name}+}}"), "{+}"),
+            };
+        }
+        path
+    }
+
+    /// Whether both routes would handle the same method and path.
+    pub fn collides_with(&self, other: &HttpRoute) -> bool {
+        self.method == other.method && self.normalized_path() == other.normalized_path()
+    }
+
+    fn extract_http_path(path: &Path) -> Result<(String, Vec<HttpPathParam>), anyhow::Error> {
         debug_assert!(path.is_relative());
-        let mut parts: Vec<String> = Vec::new();
-        for p in path.parent()?.components().rev() {
-            if p.as_os_str().to_string_lossy().as_ref() == "routes" {
-                return Some(parts.join("/"));
+        let mut dirnames: Vec<String> = Vec::new();
+        let mut found_routes_dir = false;
+        for p in path
+            .parent()
+            .map(|parent| parent.components().rev())
+            .into_iter()
+            .flatten()
+        {
+            let dirname = p.as_os_str().to_string_lossy().to_string();
+            if dirname == "routes" {
+                found_routes_dir = true;
+                break;
             } else {
-                parts.insert(0, p.as_os_str().to_string_lossy().to_string());
+                dirnames.insert(0, dirname);
+            }
+        }
+        if !found_routes_dir {
+            return Err(anyhow!("not an http route source path"));
+        }
+        let mut parts: Vec<String> = Vec::new();
+        let mut params: Vec<HttpPathParam> = Vec::new();
+        for (i, dirname) in dirnames.iter().enumerate() {
+            match HttpPathParam::from_dirname(dirname)? {
+                None => parts.push(dirname.clone()),
+                Some(param) => {
+                    if params.iter().any(|p| p.name() == param.name()) {
+                        return Err(anyhow!("path param {} is used twice", param.name()));
+                    }
+                    parts.push(match &param {
+                        HttpPathParam::Segment(name) => format!("{{{name}}}"),
+                        HttpPathParam::CatchAll(name) => {
+                            if i != dirnames.len() - 1 {
+                                return Err(anyhow!(
+                                    "catch-all path param {name} must be the last path segment"
+                                ));
+                            }
+                            format!("{{{name}+}}")
+                        }
+                    });
+                    params.push(param);
+                }
             }
         }
-        None
+        Ok((parts.join("/"), params))
     }
 }
 
@@ -113,6 +239,9 @@ pub struct LambdaSpec {
     pub source: PathBuf,
     pub handler: String,
     pub runtime: LambdaRuntimeSpec,
+    /// Routing of a lambda discovered from the project's `routes` directory. Discrete lambdas
+    /// configured with l3.toml [[lambda]] tables are not routed.
+    pub routing: Option<LambdaRouting>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -121,6 +250,15 @@ pub enum LambdaRuntimeSpec {
     Python,
 }
 
+impl From<&Language> for LambdaRuntimeSpec {
+    fn from(language: &Language) -> Self {
+        match language {
+            Language::JavaScript | Language::TypeScript => LambdaRuntimeSpec::Node,
+            Language::Python => LambdaRuntimeSpec::Python,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum NodeVersion {
     TwentyFour,
@@ -1,4 +1,4 @@
-use crate::{HttpMethod, HttpRoute};
+use crate::{HttpMethod, HttpPathParam, HttpRoute};
 use std::path::PathBuf;
 
 #[test]
@@ -52,6 +52,7 @@ fn test_http_route_new_ok() {
             HttpRoute {
                 method: http_method,
                 path: String::from(http_path),
+                params: Vec::new(),
             }
         );
     }
@@ -73,3 +74,102 @@ fn test_http_route_new_err() {
         Some("not an http route source path".to_string())
     );
 }
+
+#[test]
+fn test_http_route_to_lambda_name() {
+    let test_data = [
+        ("GET", "routes/data/api/lambda.js", "get-data-api"),
+        ("DELETE", "routes/data/lambda.js", "delete-data"),
+        ("GET", "routes/users/[id]/lambda.js", "get-users-id"),
+        ("GET", "routes/files/[...path]/lambda.js", "get-files-path"),
+        ("post", "routes/lambda.py", "post"),
+    ];
+    for (handler_fn_name, source_path, lambda_name) in test_data {
+        assert_eq!(
+            HttpRoute::new(&PathBuf::from(source_path), handler_fn_name)
+                .unwrap()
+                .to_lambda_name(),
+            lambda_name
+        );
+    }
+}
+
+#[test]
+fn test_http_route_new_with_path_params() {
+    assert_eq!(
+        HttpRoute::new(
+            &PathBuf::from("routes/users/[id]/posts/[post_id]/lambda.ts"),
+            "GET"
+        )
+        .unwrap(),
+        HttpRoute {
+            method: HttpMethod::Get,
+            path: "users/{id}/posts/{post_id}".into(),
+            params: vec![
+                HttpPathParam::Segment("id".into()),
+                HttpPathParam::Segment("post_id".into()),
+            ],
+        }
+    );
+    assert_eq!(
+        HttpRoute::new(&PathBuf::from("routes/files/[...path]/lambda.py"), "get").unwrap(),
+        HttpRoute {
+            method: HttpMethod::Get,
+            path: "files/{path+}".into(),
+            params: vec![HttpPathParam::CatchAll("path".into())],
+        }
+    );
+}
+
+#[test]
+fn test_http_route_new_with_path_params_err() {
+    let test_data = [
+        (
+            "routes/files/[...path]/meta/lambda.js",
+            "catch-all path param path must be the last path segment",
+        ),
+        (
+            "routes/[id]/users/[id]/lambda.js",
+            "path param id is used twice",
+        ),
+        (
+            "routes/users/[]/lambda.js",
+            "path param directory [] has an invalid name",
+        ),
+        (
+            "routes/users/[user-id]/lambda.js",
+            "path param directory [user-id] has an invalid name",
+        ),
+    ];
+    for (source_path, err_msg) in test_data {
+        assert_eq!(
+            HttpRoute::new(&PathBuf::from(source_path), "GET")
+                .err()
+                .map(|err| err.to_string()),
+            Some(err_msg.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_http_route_collides_with() {
+    let route = |source_path: &str, method: &str| {
+        HttpRoute::new(&PathBuf::from(source_path), method).unwrap()
+    };
+    assert_eq!(
+        route("routes/users/[id]/lambda.js", "GET").normalized_path(),
+        "users/{}"
+    );
+    assert_eq!(
+        route("routes/files/[...path]/lambda.js", "GET").normalized_path(),
+        "files/{+}"
+    );
+    assert!(route("routes/users/[id]/lambda.js", "GET")
+        .collides_with(&route("routes/users/[user_id]/lambda.py", "get")));
+    assert!(!route("routes/users/[id]/lambda.js", "GET")
+        .collides_with(&route("routes/users/[id]/lambda.py", "post")));
+    assert!(!route("routes/users/[id]/lambda.js", "GET")
+        .collides_with(&route("routes/users/[...path]/lambda.js", "GET")));
+    assert!(!route("routes/users/[id]/lambda.js", "GET")
+        .collides_with(&route("routes/users/me/lambda.js", "GET")));
+}
@@ -8,5 +8,6 @@ mod merge_test;
 #[cfg(test)]
 mod parse_test;
 
+pub use merge::*;
 pub use parse_file::*;
 pub use parse_vars::*;
@@ -1,10 +1,8 @@
 use std::collections::HashMap;
 
-#[allow(unused)]
-type EnvVarsCollection = Vec<Option<HashMap<String, String>>>;
+pub type EnvVarsCollection = Vec<Option<HashMap<String, String>>>;
 
 /// Merge env vars ordered from lowest to highest precedence
-#[allow(unused)]
 pub fn merge_env_vars(env_vars: EnvVarsCollection) -> HashMap<String, String> {
     let env_vars = env_vars
         .into_iter()
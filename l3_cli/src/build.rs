@@ -1,11 +1,15 @@
+use crate::run_fn::fn_runtime;
 use crate::{LLLCommandRun, LLLCommandRunError, LLLCommandRunResult};
-use clap::Parser;
-use l3_fn_build::runtime::{node::NodeConfig, Runtime};
-use l3_fn_build::{BuildMode, FnBuildManifest, FnBuildResult, FnBuildSpec, FnOutputConfig};
+use clap::{Parser, ValueEnum};
+use l3_fn_build::runtime::node::NodeConfig;
+use l3_fn_build::{
+    BuildMode, FnBuildManifest, FnBuildResult, FnBuildSpec, FnBundleConfig, FnOutputConfig,
+    FnParseSpec, FnSourceMapConfig, FnSourceMaps,
+};
 use l3_fn_config::{LLLConfigs, LambdaRuntimeSpec, LambdaSpec};
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{env, fs, process};
 use tokio::task::JoinSet;
@@ -18,6 +22,48 @@ pub struct BuildCommand {
         long_help = "Create a release build of Lambda functions"
     )]
     release: bool,
+    #[clap(
+        long,
+        default_value = "false",
+        long_help = "Bundle each Node.js Lambda function into a single ES module"
+    )]
+    bundle: bool,
+    #[clap(
+        long,
+        default_value = "false",
+        requires = "bundle",
+        long_help = "Inline npm packages distributed as ES modules into bundles instead of copying them"
+    )]
+    inline_packages: bool,
+    #[clap(
+        long,
+        value_enum,
+        long_help = "Write source maps of Node.js Lambda functions' transformed sources"
+    )]
+    source_maps: Option<SourceMapsOutput>,
+    #[clap(
+        long,
+        default_value = "false",
+        requires = "source_maps",
+        long_help = "Add NODE_OPTIONS=--enable-source-maps to the env vars of built Node.js Lambda functions"
+    )]
+    enable_source_maps: bool,
+    #[clap(
+        long = "condition",
+        short = 'C',
+        value_name = "CONDITION",
+        long_help = "Resolve Node.js package exports and imports with a custom condition, repeatable for multiple conditions"
+    )]
+    conditions: Vec<String>,
+}
+
+/// Where `l3 build --source-maps` writes source maps.
+#[derive(Clone, Debug, ValueEnum)]
+pub enum SourceMapsOutput {
+    /// .map files next to each built source
+    External,
+    /// Base64 data URLs appended to each built source
+    Inline,
 }
 
 impl LLLCommandRun for BuildCommand {
@@ -29,7 +75,6 @@ impl LLLCommandRun for BuildCommand {
         };
         let project_dir = Arc::new(env::current_dir().expect("cwd"));
         let build_root = project_dir.join(".l3");
-        _ = fs::remove_dir_all(&build_root);
         let mut configs = LLLConfigs::new(project_dir.clone());
         let update_result = configs.update_all_configs();
         if !update_result.config_errs.is_empty() {
@@ -38,23 +83,43 @@ impl LLLCommandRun for BuildCommand {
             }
             process::exit(1);
         }
-        let lambda_specs = configs.discrete_lambdas();
+        let lambda_specs = configs.lambdas();
         if lambda_specs.is_empty() {
             return Err(LLLCommandRunError::LambdasNotFound);
         }
         let node_config = Arc::new(NodeConfig::read_configs(&project_dir).unwrap());
+        let handler_errs = validate_configured_handlers(
+            &configs,
+            &lambda_specs,
+            &project_dir,
+            &node_config,
+            &self.conditions,
+        )
+        .await;
+        if !handler_errs.is_empty() {
+            for err in handler_errs {
+                println!("\x1b[31m✗\x1b[0m config error: {err}");
+            }
+            process::exit(1);
+        }
+        let mut previous_builds = read_build_manifest_json(&build_root);
         let mut join_set: JoinSet<(Arc<LambdaSpec>, FnBuildResult<FnBuildManifest>)> =
             JoinSet::new();
         for lambda_spec in lambda_specs {
-            let runtime = match &lambda_spec.runtime {
-                LambdaRuntimeSpec::Node => Runtime::Node(Some(node_config.clone())),
-                LambdaRuntimeSpec::Python => Runtime::Python,
+            let runtime = fn_runtime(&configs, &lambda_spec, &node_config);
+            let bundle = match &lambda_spec.runtime {
+                LambdaRuntimeSpec::Node => self.bundle.then_some(FnBundleConfig {
+                    inline_packages: self.inline_packages,
+                }),
+                LambdaRuntimeSpec::Python => None,
             };
             join_set.spawn(build_fn(
                 lambda_spec.clone(),
                 FnBuildSpec {
+                    bundle,
                     project_dir: project_dir.clone(),
                     runtime,
+                    conditions: self.conditions.clone(),
                     entrypoint: lambda_spec.source.clone(),
                     mode: build_mode.clone(),
                     handler_fn_name: lambda_spec.handler.clone(),
@@ -64,6 +129,17 @@ impl LLLCommandRun for BuildCommand {
                         dirname: lambda_spec.name.clone(),
                         use_build_mode: true,
                     },
+                    previous_build: previous_builds.remove(&lambda_spec.name),
+                    source_maps: self
+                        .source_maps
+                        .as_ref()
+                        .map(|source_maps| FnSourceMapConfig {
+                            source_maps: match source_maps {
+                                SourceMapsOutput::External => FnSourceMaps::External,
+                                SourceMapsOutput::Inline => FnSourceMaps::Inline,
+                            },
+                            enable_node_source_maps: self.enable_source_maps,
+                        }),
                 },
             ));
         }
@@ -79,6 +155,13 @@ impl LLLCommandRun for BuildCommand {
         }
 
         let build_count = result.len();
+        remove_stale_builds(
+            &build_root.join(build_mode.to_label()),
+            result
+                .iter()
+                .map(|(lambda_spec, _)| lambda_spec.name.as_str())
+                .collect(),
+        );
         write_build_manifest_json(&build_root, result);
 
         println!(
@@ -92,6 +175,60 @@ impl LLLCommandRun for BuildCommand {
     }
 }
 
+/// Checks that the entrypoint of each l3.toml `[[lambda]]` exports its configured handler,
+/// suggesting the closest exported handler for a mistyped handler. Entrypoints that fail to
+/// parse are left to report their error from building the lambda.
+async fn validate_configured_handlers(
+    configs: &LLLConfigs,
+    lambda_specs: &[Arc<LambdaSpec>],
+    project_dir: &Arc<PathBuf>,
+    node_config: &Arc<NodeConfig>,
+    conditions: &[String],
+) -> Vec<String> {
+    let mut errs = Vec::new();
+    for lambda_spec in lambda_specs.iter().filter(|l| l.routing.is_none()) {
+        let Ok(entrypoint) = l3_fn_build::parse_entrypoint(FnParseSpec {
+            conditions: conditions.to_vec(),
+            entrypoint: lambda_spec.source.clone(),
+            project_dir: project_dir.clone(),
+            runtime: fn_runtime(configs, lambda_spec, node_config),
+        })
+        .await
+        else {
+            continue;
+        };
+        if entrypoint.handler(&lambda_spec.handler).is_some() {
+            continue;
+        }
+        let mut err = format!(
+            "lambda {} handler {} is not exported by {}",
+            lambda_spec.name,
+            lambda_spec.handler,
+            lambda_spec.source.to_string_lossy()
+        );
+        if let Some(suggestion) = closest_handler(&lambda_spec.handler, &entrypoint.handler_names())
+        {
+            err.push_str(&format!(", did you mean {suggestion}?"));
+        }
+        errs.push(err);
+    }
+    errs
+}
+
+/// Exported handler with the smallest edit distance from a handler name, if close enough to
+/// be a typo of the handler.
+pub(crate) fn closest_handler<'a>(handler: &str, exported: &[&'a str]) -> Option<&'a str> {
+    exported
+        .iter()
+        .map(|name| {
+            let distance = strsim::levenshtein(&handler.to_lowercase(), &name.to_lowercase());
+            (name, distance)
+        })
+        .filter(|(name, distance)| *distance <= name.chars().count().max(3) / 3)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(name, _)| *name)
+}
+
 async fn build_fn(
     lambda_spec: Arc<LambdaSpec>,
     build_spec: FnBuildSpec,
@@ -99,6 +236,44 @@ async fn build_fn(
     (lambda_spec, l3_fn_build::build_fn(build_spec).await)
 }
 
+/// Reads manifests of successful builds from the previous `l3_build.json` by lambda name.
+fn read_build_manifest_json(build_root: &Path) -> HashMap<String, FnBuildManifest> {
+    let Ok(build_json) = fs::read_to_string(build_root.join("l3_build.json")) else {
+        return HashMap::new();
+    };
+    let Ok(Value::Array(lambda_builds)) = serde_json::from_str(&build_json) else {
+        return HashMap::new();
+    };
+    lambda_builds
+        .into_iter()
+        .filter_map(|mut lambda_build| {
+            let name = lambda_build.get("name")?.as_str()?.to_string();
+            let manifest = serde_json::from_value(lambda_build["build"]["manifest"].take()).ok()?;
+            Some((name, manifest))
+        })
+        .collect()
+}
+
+/// Removes build dirs and archives of lambdas that are no longer in the project.
+fn remove_stale_builds(build_mode_root: &Path, lambda_names: HashSet<&str>) {
+    let Ok(read_dir) = fs::read_dir(build_mode_root) else {
+        return;
+    };
+    for entry in read_dir.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !lambda_names.contains(file_name.as_str()) {
+                _ = fs::remove_dir_all(&path);
+            }
+        } else if let Some(lambda_name) = file_name.strip_suffix(".zip") {
+            if !lambda_names.contains(lambda_name) {
+                _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
 // todo `JSON.stringify(data, null, 4)` style output
 fn write_build_manifest_json(
     build_root: &Path,
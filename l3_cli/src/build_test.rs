@@ -0,0 +1,16 @@
+use crate::build::closest_handler;
+
+#[test]
+fn test_closest_handler_suggests_mistyped_handler() {
+    let exported = ["handler", "GET"];
+    assert_eq!(closest_handler("handlr", &exported), Some("handler"));
+    assert_eq!(closest_handler("get", &exported), Some("GET"));
+}
+
+#[test]
+fn test_closest_handler_does_not_suggest_unrelated_handler() {
+    let exported = ["handler", "GET"];
+    assert_eq!(closest_handler("process_event", &exported), None);
+    assert_eq!(closest_handler("PUT", &exported), None);
+    assert_eq!(closest_handler("handler", &[]), None);
+}
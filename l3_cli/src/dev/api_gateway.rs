@@ -0,0 +1,203 @@
+use crate::dev::routing::{percent_decode, RouteMatch};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::http::request::Parts;
+use hyper::{Response, StatusCode};
+use serde_json::{json, Map, Value};
+use std::net::SocketAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Creates an API Gateway HTTP API payload format version 2.0 event for a request.
+pub fn create_http_event(
+    parts: &Parts,
+    body: &[u8],
+    route_match: &RouteMatch,
+    remote_addr: &SocketAddr,
+    request_id: &str,
+) -> Value {
+    let route_key = format!("{} /{}", route_match.route.method, route_match.route.path);
+    let raw_path = parts.uri.path();
+    let raw_query_string = parts.uri.query().unwrap_or_default();
+
+    let mut cookies: Vec<Value> = Vec::new();
+    let mut headers = Map::new();
+    for (name, value) in &parts.headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+        if name == hyper::header::COOKIE {
+            cookies.extend(
+                value
+                    .split(';')
+                    .map(|c| Value::String(c.trim().to_string())),
+            );
+            continue;
+        }
+        match headers.get_mut(name.as_str()) {
+            Some(Value::String(joined)) => {
+                joined.push(',');
+                joined.push_str(&value);
+            }
+            _ => {
+                headers.insert(name.to_string(), Value::String(value));
+            }
+        }
+    }
+
+    let mut query_string_parameters = Map::new();
+    for pair in raw_query_string.split('&').filter(|p| !p.is_empty()) {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        let k = percent_decode(&k.replace('+', " "));
+        let v = percent_decode(&v.replace('+', " "));
+        match query_string_parameters.get_mut(&k) {
+            Some(Value::String(joined)) => {
+                joined.push(',');
+                joined.push_str(&v);
+            }
+            _ => {
+                query_string_parameters.insert(k, Value::String(v));
+            }
+        }
+    }
+
+    let domain_name = headers
+        .get("host")
+        .cloned()
+        .unwrap_or(Value::from("localhost"));
+    let user_agent = headers.get("user-agent").cloned().unwrap_or_default();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let mut event = json!({
+        "version": "2.0",
+        "routeKey": route_key,
+        "rawPath": raw_path,
+        "rawQueryString": raw_query_string,
+        "headers": headers,
+        "requestContext": {
+            "accountId": "000000000000",
+            "apiId": "l3dev",
+            "domainName": domain_name,
+            "domainPrefix": "l3dev",
+            "http": {
+                "method": parts.method.as_str(),
+                "path": raw_path,
+                "protocol": format!("{:?}", parts.version),
+                "sourceIp": remote_addr.ip().to_string(),
+                "userAgent": user_agent,
+            },
+            "requestId": request_id,
+            "routeKey": route_key,
+            "stage": "$default",
+            "time": format_request_time(now.as_secs()),
+            "timeEpoch": now.as_millis() as u64,
+        },
+        "isBase64Encoded": false,
+    });
+    let fields = event.as_object_mut().unwrap();
+    if !cookies.is_empty() {
+        fields.insert("cookies".into(), Value::Array(cookies));
+    }
+    if !query_string_parameters.is_empty() {
+        fields.insert(
+            "queryStringParameters".into(),
+            Value::Object(query_string_parameters),
+        );
+    }
+    if !route_match.path_params.is_empty() {
+        fields.insert("pathParameters".into(), json!(route_match.path_params));
+    }
+    if !body.is_empty() {
+        match std::str::from_utf8(body) {
+            Ok(body) => {
+                fields.insert("body".into(), Value::from(body));
+            }
+            Err(_) => {
+                fields.insert("body".into(), Value::from(BASE64.encode(body)));
+                fields.insert("isBase64Encoded".into(), Value::Bool(true));
+            }
+        }
+    }
+    event
+}
+
+/// Creates an http response from a handler's result the way API Gateway interprets payload
+/// format version 2.0 responses, where a result without a `statusCode` is a JSON body.
+pub fn create_http_response(result: Value) -> Response<Vec<u8>> {
+    let Some(status_code) = result.get("statusCode").and_then(|s| s.as_u64()) else {
+        return json_response(StatusCode::OK, result.to_string());
+    };
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() =
+        StatusCode::from_u16(status_code as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let headers = response.headers_mut();
+    if let Some(Value::Object(result_headers)) = result.get("headers") {
+        for (name, value) in result_headers {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            };
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+    }
+    if let Some(Value::Array(cookies)) = result.get("cookies") {
+        for cookie in cookies.iter().filter_map(|c| c.as_str()) {
+            if let Ok(value) = HeaderValue::try_from(cookie) {
+                headers.append(hyper::header::SET_COOKIE, value);
+            }
+        }
+    }
+    let body = match result.get("body") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(body)) => {
+            if result.get("isBase64Encoded") == Some(&Value::Bool(true)) {
+                BASE64.decode(body).unwrap_or_default()
+            } else {
+                body.as_bytes().to_vec()
+            }
+        }
+        Some(body) => body.to_string().into_bytes(),
+    };
+    *response.body_mut() = body;
+    response
+}
+
+pub fn json_response(status: StatusCode, body: String) -> Response<Vec<u8>> {
+    let mut response = Response::new(body.into_bytes());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+// formats as `18/Oct/2026:19:03:58 +0000` like API Gateway's requestContext.time
+fn format_request_time(epoch_secs: u64) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = (epoch_secs / 86400) as i64;
+    let secs = epoch_secs % 86400;
+    // civil from days algorithm by Howard Hinnant
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:02}/{}/{}:{:02}:{:02}:{:02} +0000",
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,213 @@
+mod api_gateway;
+mod routing;
+
+#[cfg(test)]
+mod routing_test;
+
+use crate::dev::api_gateway::{create_http_event, create_http_response, json_response};
+use crate::dev::routing::{DevRoutes, RouteLookup};
+use crate::run_fn::{build_local_fn, create_request_id, fn_runtime, invoke_fn};
+use crate::{LLLCommandRun, LLLCommandRunError, LLLCommandRunResult};
+use clap::Parser;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use l3_fn_build::runtime::node::NodeConfig;
+use l3_fn_build::FnBuildManifest;
+use l3_fn_config::LLLConfigs;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::{env, process};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+#[derive(Parser, Debug)]
+pub struct DevCommand {
+    #[clap(
+        long,
+        default_value = "3000",
+        long_help = "Port for the local HTTP server"
+    )]
+    port: u16,
+}
+
+/// State shared by connections of the dev server.
+struct DevServer {
+    configs: LLLConfigs,
+    node_config: Arc<NodeConfig>,
+    project_dir: Arc<PathBuf>,
+    routes: DevRoutes,
+    /// Serializes builds of a lambda when concurrent requests are routed to it and holds its
+    /// last build to reuse outputs of unchanged sources.
+    build_locks: HashMap<String, Mutex<Option<FnBuildManifest>>>,
+}
+
+impl LLLCommandRun for DevCommand {
+    async fn run(&self) -> LLLCommandRunResult {
+        let project_dir = Arc::new(env::current_dir().expect("cwd"));
+        let mut configs = LLLConfigs::new(project_dir.clone());
+        let update_result = configs.update_all_configs();
+        if !update_result.config_errs.is_empty() {
+            for err in update_result.config_errs {
+                println!("\x1b[31m✗\x1b[0m config error: {err}");
+            }
+            process::exit(1);
+        }
+        let routes = DevRoutes::new(configs.routed_lambdas());
+        if routes.is_empty() {
+            return Err(LLLCommandRunError::LambdasNotFound);
+        }
+        let node_config = Arc::new(NodeConfig::read_configs(&project_dir).unwrap());
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], self.port))).await?;
+        println!(
+            "\x1b[32m✔\x1b[0m serving {} routes at http://{}",
+            routes.routes().len(),
+            listener.local_addr()?
+        );
+        for (lambda_spec, route) in routes.routes() {
+            println!(
+                "  {:<6} /{} → {}",
+                route.method.to_string(),
+                route.path,
+                lambda_spec.source.to_string_lossy()
+            );
+        }
+        let server = Arc::new(DevServer {
+            build_locks: routes
+                .routes()
+                .iter()
+                .map(|(lambda_spec, _)| (lambda_spec.name.clone(), Mutex::new(None)))
+                .collect(),
+            configs,
+            node_config,
+            project_dir,
+            routes,
+        });
+        loop {
+            let (stream, remote_addr) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(request, remote_addr).await) }
+                });
+                if let Err(err) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    println!("\x1b[31m✗\x1b[0m connection error: {err}");
+                }
+            });
+        }
+    }
+}
+
+impl DevServer {
+    async fn handle(
+        &self,
+        request: Request<Incoming>,
+        remote_addr: SocketAddr,
+    ) -> Response<Full<Bytes>> {
+        let (parts, body) = request.into_parts();
+        let response = match self.routes.lookup(parts.method.as_str(), parts.uri.path()) {
+            RouteLookup::NotFound => json_response(
+                StatusCode::NOT_FOUND,
+                r#"{"message":"Not Found"}"#.to_string(),
+            ),
+            RouteLookup::MethodNotAllowed => json_response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                r#"{"message":"Method Not Allowed"}"#.to_string(),
+            ),
+            RouteLookup::Found(route_match) => {
+                let body = match body.collect().await {
+                    Ok(body) => body.to_bytes(),
+                    Err(err) => {
+                        println!("\x1b[31m✗\x1b[0m request error: {err}");
+                        return internal_server_error().map(Full::from);
+                    }
+                };
+                let lambda_spec = &route_match.lambda;
+                let build_result = {
+                    let mut previous_build = self.build_locks[&lambda_spec.name].lock().await;
+                    let build_result = build_local_fn(
+                        &self.project_dir,
+                        lambda_spec,
+                        fn_runtime(&self.configs, lambda_spec, &self.node_config),
+                        previous_build.take(),
+                    )
+                    .await;
+                    if let Ok(build_manifest) = &build_result {
+                        *previous_build = Some(build_manifest.clone());
+                    }
+                    build_result
+                };
+                let request_id = create_request_id();
+                let event =
+                    create_http_event(&parts, &body, &route_match, &remote_addr, &request_id);
+                let invoke_result = match build_result {
+                    Ok(build_manifest) => match self.configs.lambda_env_vars(&lambda_spec.name) {
+                        Ok(env_vars) => {
+                            invoke_fn(lambda_spec, &build_manifest, env_vars, &event).await
+                        }
+                        Err(err) => Err(err.into()),
+                    },
+                    Err(err) => Err(err),
+                };
+                match invoke_result {
+                    Ok(invocation) => {
+                        print!("{}", invocation.logs);
+                        match invocation.result {
+                            Ok(result) => {
+                                let response = create_http_response(result);
+                                println!(
+                                    "{} {} → {} {} ({}ms)",
+                                    parts.method,
+                                    parts.uri.path(),
+                                    lambda_spec.name,
+                                    response.status().as_u16(),
+                                    invocation.duration.as_millis()
+                                );
+                                response
+                            }
+                            Err(err) => {
+                                println!(
+                                    "\x1b[31m✗\x1b[0m {} {} → {} error: {err}",
+                                    parts.method,
+                                    parts.uri.path(),
+                                    lambda_spec.name
+                                );
+                                for line in &err.stack_trace {
+                                    println!("    {line}");
+                                }
+                                internal_server_error()
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        println!(
+                            "\x1b[31m✗\x1b[0m {} {} → {} error: {err}",
+                            parts.method,
+                            parts.uri.path(),
+                            lambda_spec.name
+                        );
+                        internal_server_error()
+                    }
+                }
+            }
+        };
+        response.map(Full::from)
+    }
+}
+
+fn internal_server_error() -> Response<Vec<u8>> {
+    json_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        r#"{"message":"Internal Server Error"}"#.to_string(),
+    )
+}
@@ -0,0 +1,179 @@
+use l3_fn_config::{HttpMethod, HttpRoute, LambdaRouting, LambdaSpec};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A lambda's HttpRoute matched to a request path.
+pub struct RouteMatch {
+    pub lambda: Arc<LambdaSpec>,
+    pub route: HttpRoute,
+    pub path_params: HashMap<String, String>,
+}
+
+pub enum RouteLookup {
+    Found(RouteMatch),
+    MethodNotAllowed,
+    NotFound,
+}
+
+/// Matches request paths to lambdas routed by HttpRoute.
+pub struct DevRoutes {
+    routes: Vec<(Arc<LambdaSpec>, HttpRoute)>,
+}
+
+/// Ranks how specific a route path segment is where a lower rank is more specific.
+enum RoutePathSegment<'a> {
+    Static(&'a str),
+    Param(&'a str),
+    CatchAll(&'a str),
+}
+
+impl RoutePathSegment<'_> {
+    fn rank(&self) -> u8 {
+        match self {
+            RoutePathSegment::Static(_) => 0,
+            RoutePathSegment::Param(_) => 1,
+            RoutePathSegment::CatchAll(_) => 2,
+        }
+    }
+}
+
+impl DevRoutes {
+    pub fn new(lambdas: Vec<Arc<LambdaSpec>>) -> Self {
+        let mut routes: Vec<(Arc<LambdaSpec>, HttpRoute)> = lambdas
+            .into_iter()
+            .filter_map(|lambda| match &lambda.routing {
+                Some(LambdaRouting::HttpRoute(route)) => {
+                    let route = route.clone();
+                    Some((lambda, route))
+                }
+                None => None,
+            })
+            .collect();
+        routes.sort_by(|(_, a), (_, b)| {
+            a.path
+                .cmp(&b.path)
+                .then(a.method.to_string().cmp(&b.method.to_string()))
+        });
+        Self { routes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn routes(&self) -> &Vec<(Arc<LambdaSpec>, HttpRoute)> {
+        &self.routes
+    }
+
+    /// Finds the most specific route for a request where static path segments are preferred
+    /// over path params and path params are preferred over catch-all path params.
+    pub fn lookup(&self, method: &str, path: &str) -> RouteLookup {
+        let mut path_matched = false;
+        let mut best: Option<(Vec<u8>, RouteMatch)> = None;
+        for (lambda, route) in &self.routes {
+            let Some((ranks, path_params)) = match_path(route, path) else {
+                continue;
+            };
+            path_matched = true;
+            if HttpMethod::try_from(method).ok().as_ref() != Some(&route.method) {
+                continue;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(best_ranks, _)| &ranks < best_ranks)
+            {
+                best = Some((
+                    ranks,
+                    RouteMatch {
+                        lambda: lambda.clone(),
+                        route: route.clone(),
+                        path_params,
+                    },
+                ));
+            }
+        }
+        match best {
+            Some((_, route_match)) => RouteLookup::Found(route_match),
+            None if path_matched => RouteLookup::MethodNotAllowed,
+            None => RouteLookup::NotFound,
+        }
+    }
+}
+
+fn route_segments(route: &HttpRoute) -> Vec<RoutePathSegment<'_>> {
+    route
+        .path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(
+            |s| match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                None => RoutePathSegment::Static(s),
+                Some(param) => match param.strip_suffix('+') {
+                    Some(name) => RoutePathSegment::CatchAll(name),
+                    None => RoutePathSegment::Param(param),
+                },
+            },
+        )
+        .collect()
+}
+
+/// Matches a request path to a route returning the specificity ranks of the route's path
+/// segments and the captured path params.
+fn match_path(route: &HttpRoute, path: &str) -> Option<(Vec<u8>, HashMap<String, String>)> {
+    let route_segments = route_segments(route);
+    let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut path_params = HashMap::new();
+    let mut i = 0;
+    for route_segment in &route_segments {
+        match route_segment {
+            RoutePathSegment::Static(s) => {
+                if path_segments.get(i) != Some(s) {
+                    return None;
+                }
+            }
+            RoutePathSegment::Param(name) => {
+                path_params.insert(name.to_string(), percent_decode(path_segments.get(i)?));
+            }
+            RoutePathSegment::CatchAll(name) => {
+                if i >= path_segments.len() {
+                    return None;
+                }
+                path_params.insert(
+                    name.to_string(),
+                    percent_decode(&path_segments[i..].join("/")),
+                );
+                i = path_segments.len();
+                break;
+            }
+        }
+        i += 1;
+    }
+    if i == path_segments.len() {
+        Some((
+            route_segments.iter().map(|s| s.rank()).collect(),
+            path_params,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Decodes `%XX` escapes of a url path or query string component.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
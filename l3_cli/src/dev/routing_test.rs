@@ -0,0 +1,103 @@
+use crate::dev::routing::{percent_decode, DevRoutes, RouteLookup};
+use l3_fn_config::{HttpRoute, LambdaRouting, LambdaRuntimeSpec, LambdaSpec};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn dev_routes(route_sources: &[(&str, &str)]) -> DevRoutes {
+    DevRoutes::new(
+        route_sources
+            .iter()
+            .map(|(handler, source)| {
+                let source = PathBuf::from(source);
+                let route = HttpRoute::new(&source, handler).unwrap();
+                Arc::new(LambdaSpec {
+                    name: route.to_lambda_name(),
+                    source,
+                    handler: handler.to_string(),
+                    runtime: LambdaRuntimeSpec::Node,
+                    routing: Some(LambdaRouting::HttpRoute(route)),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn assert_found(routes: &DevRoutes, method: &str, path: &str, expected: (&str, &[(&str, &str)])) {
+    match routes.lookup(method, path) {
+        RouteLookup::Found(route_match) => {
+            assert_eq!(route_match.route.path, expected.0);
+            assert_eq!(
+                route_match.path_params,
+                expected
+                    .1
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<String, String>>()
+            );
+        }
+        _ => panic!("{method} {path} did not match {}", expected.0),
+    }
+}
+
+#[test]
+fn test_dev_routes_lookup_static_path() {
+    let routes = dev_routes(&[("GET", "routes/data/api/lambda.js")]);
+    assert_found(&routes, "GET", "/data/api", ("data/api", &[]));
+    assert_found(&routes, "get", "/data/api/", ("data/api", &[]));
+    assert!(matches!(
+        routes.lookup("GET", "/data"),
+        RouteLookup::NotFound
+    ));
+    assert!(matches!(
+        routes.lookup("POST", "/data/api"),
+        RouteLookup::MethodNotAllowed
+    ));
+}
+
+#[test]
+fn test_dev_routes_lookup_path_params() {
+    let routes = dev_routes(&[
+        ("GET", "routes/users/[id]/lambda.ts"),
+        ("GET", "routes/files/[...path]/lambda.ts"),
+    ]);
+    assert_found(&routes, "GET", "/users/32", ("users/{id}", &[("id", "32")]));
+    assert_found(
+        &routes,
+        "GET",
+        "/files/docs/read%20me.md",
+        ("files/{path+}", &[("path", "docs/read me.md")]),
+    );
+    assert!(matches!(
+        routes.lookup("GET", "/users/32/posts"),
+        RouteLookup::NotFound
+    ));
+    assert!(matches!(
+        routes.lookup("GET", "/files"),
+        RouteLookup::NotFound
+    ));
+}
+
+#[test]
+fn test_dev_routes_lookup_prefers_specific_route() {
+    let routes = dev_routes(&[
+        ("GET", "routes/users/[...path]/lambda.ts"),
+        ("GET", "routes/users/[id]/lambda.ts"),
+        ("GET", "routes/users/me/lambda.ts"),
+    ]);
+    assert_found(&routes, "GET", "/users/me", ("users/me", &[]));
+    assert_found(&routes, "GET", "/users/32", ("users/{id}", &[("id", "32")]));
+    assert_found(
+        &routes,
+        "GET",
+        "/users/32/posts",
+        ("users/{path+}", &[("path", "32/posts")]),
+    );
+}
+
+#[test]
+fn test_percent_decode() {
+    assert_eq!(percent_decode("a%20b%2Fc"), "a b/c");
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz%é"), "%zz%é");
+}
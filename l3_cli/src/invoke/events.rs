@@ -0,0 +1,162 @@
+use clap::ValueEnum;
+use l3_fn_config::{HttpRoute, LambdaRouting, LambdaSpec};
+use serde_json::{json, Map, Value};
+
+/// Built-in sample events for invoking a lambda without writing an event payload.
+#[derive(Clone, Debug, ValueEnum)]
+pub enum EventTemplate {
+    /// API Gateway HTTP API payload format version 2.0 request
+    #[value(name = "apigw-v2")]
+    ApiGatewayV2,
+    /// EventBridge Scheduler or scheduled rule event
+    #[value(name = "schedule")]
+    EventBridgeSchedule,
+    /// S3 ObjectCreated:Put notification
+    #[value(name = "s3")]
+    S3,
+    /// SQS message batch with a single record
+    #[value(name = "sqs")]
+    Sqs,
+}
+
+impl EventTemplate {
+    pub fn create_event(&self, lambda_spec: &LambdaSpec) -> Value {
+        match self {
+            EventTemplate::ApiGatewayV2 => api_gateway_v2_event(match &lambda_spec.routing {
+                Some(LambdaRouting::HttpRoute(route)) => Some(route),
+                None => None,
+            }),
+            EventTemplate::EventBridgeSchedule => schedule_event(),
+            EventTemplate::S3 => s3_event(),
+            EventTemplate::Sqs => sqs_event(),
+        }
+    }
+}
+
+// uses a routed lambda's method and path with path params set to their param name
+fn api_gateway_v2_event(route: Option<&HttpRoute>) -> Value {
+    let method = route.map(|r| r.method.to_string()).unwrap_or("GET".into());
+    let route_path = route.map(|r| r.path.as_str()).unwrap_or_default();
+    let mut path_params = Map::new();
+    let mut raw_path = String::new();
+    for segment in route_path.split('/').filter(|s| !s.is_empty()) {
+        raw_path.push('/');
+        match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(param) => {
+                let name = param.trim_end_matches('+');
+                raw_path.push_str(name);
+                path_params.insert(name.to_string(), Value::from(name));
+            }
+            None => raw_path.push_str(segment),
+        }
+    }
+    if raw_path.is_empty() {
+        raw_path.push('/');
+    }
+    let route_key = format!("{method} /{route_path}");
+    let mut event = json!({
+        "version": "2.0",
+        "routeKey": route_key,
+        "rawPath": raw_path,
+        "rawQueryString": "",
+        "headers": {
+            "accept": "*/*",
+            "host": "localhost",
+            "user-agent": "l3",
+        },
+        "requestContext": {
+            "accountId": "000000000000",
+            "apiId": "l3invoke",
+            "domainName": "localhost",
+            "domainPrefix": "l3invoke",
+            "http": {
+                "method": method,
+                "path": raw_path,
+                "protocol": "HTTP/1.1",
+                "sourceIp": "127.0.0.1",
+                "userAgent": "l3",
+            },
+            "requestId": "l3-invoke",
+            "routeKey": route_key,
+            "stage": "$default",
+            "time": "01/Jan/2025:00:00:00 +0000",
+            "timeEpoch": 1735689600000u64,
+        },
+        "isBase64Encoded": false,
+    });
+    if !path_params.is_empty() {
+        event
+            .as_object_mut()
+            .unwrap()
+            .insert("pathParameters".into(), Value::Object(path_params));
+    }
+    event
+}
+
+fn schedule_event() -> Value {
+    json!({
+        "version": "0",
+        "id": "53dc4d37-cffa-4f76-80c9-8b7d4a4d2eaa",
+        "detail-type": "Scheduled Event",
+        "source": "aws.events",
+        "account": "000000000000",
+        "time": "2025-01-01T00:00:00Z",
+        "region": "us-east-1",
+        "resources": ["arn:aws:events:us-east-1:000000000000:rule/l3-schedule"],
+        "detail": {},
+    })
+}
+
+fn s3_event() -> Value {
+    json!({
+        "Records": [{
+            "eventVersion": "2.1",
+            "eventSource": "aws:s3",
+            "awsRegion": "us-east-1",
+            "eventTime": "2025-01-01T00:00:00.000Z",
+            "eventName": "ObjectCreated:Put",
+            "userIdentity": {"principalId": "AWS:EXAMPLE"},
+            "requestParameters": {"sourceIPAddress": "127.0.0.1"},
+            "responseElements": {
+                "x-amz-request-id": "EXAMPLE123456789",
+                "x-amz-id-2": "EXAMPLE123/5678abcdefghijklambdaisawesome/mnopqrstuvwxyzABCDEFGH",
+            },
+            "s3": {
+                "s3SchemaVersion": "1.0",
+                "configurationId": "l3-notification",
+                "bucket": {
+                    "name": "l3-bucket",
+                    "ownerIdentity": {"principalId": "EXAMPLE"},
+                    "arn": "arn:aws:s3:::l3-bucket",
+                },
+                "object": {
+                    "key": "data/object.json",
+                    "size": 1024,
+                    "eTag": "0123456789abcdef0123456789abcdef",
+                    "sequencer": "0A1B2C3D4E5F678901",
+                },
+            },
+        }],
+    })
+}
+
+fn sqs_event() -> Value {
+    json!({
+        "Records": [{
+            "messageId": "059f36b4-87a3-44ab-83d2-661975830a7d",
+            "receiptHandle": "AQEBwJnKyrHigUMZj6rYigCgxlaS3SLy0a",
+            "body": "{\"message\":\"hello\"}",
+            "attributes": {
+                "ApproximateReceiveCount": "1",
+                "SentTimestamp": "1735689600000",
+                "SenderId": "AIDAIENQZJOLO23YVJ4VO",
+                "ApproximateFirstReceiveTimestamp": "1735689600001",
+            },
+            "messageAttributes": {},
+            "md5OfBody": "e4e68fb7bd0e697a0ae8f1bb342846b3",
+            "eventSource": "aws:sqs",
+            "eventSourceARN": "arn:aws:sqs:us-east-1:000000000000:l3-queue",
+            "awsRegion": "us-east-1",
+        }],
+    })
+}
@@ -0,0 +1,37 @@
+use crate::invoke::events::EventTemplate;
+use l3_fn_config::{HttpRoute, LambdaRouting, LambdaRuntimeSpec, LambdaSpec};
+use serde_json::json;
+use std::path::PathBuf;
+
+#[test]
+fn test_api_gateway_v2_template_uses_lambda_route() {
+    let source = PathBuf::from("routes/users/[id]/files/[...path]/lambda.js");
+    let route = HttpRoute::new(&source, "PUT").unwrap();
+    let lambda_spec = LambdaSpec {
+        name: route.to_lambda_name(),
+        source,
+        handler: "PUT".into(),
+        runtime: LambdaRuntimeSpec::Node,
+        routing: Some(LambdaRouting::HttpRoute(route)),
+    };
+    let event = EventTemplate::ApiGatewayV2.create_event(&lambda_spec);
+    assert_eq!(event["routeKey"], "PUT /users/{id}/files/{path+}");
+    assert_eq!(event["rawPath"], "/users/id/files/path");
+    assert_eq!(event["requestContext"]["http"]["method"], "PUT");
+    assert_eq!(event["pathParameters"], json!({"id": "id", "path": "path"}));
+}
+
+#[test]
+fn test_api_gateway_v2_template_for_discrete_lambda() {
+    let lambda_spec = LambdaSpec {
+        name: "data".into(),
+        source: PathBuf::from("data.js"),
+        handler: "handler".into(),
+        runtime: LambdaRuntimeSpec::Node,
+        routing: None,
+    };
+    let event = EventTemplate::ApiGatewayV2.create_event(&lambda_spec);
+    assert_eq!(event["routeKey"], "GET /");
+    assert_eq!(event["rawPath"], "/");
+    assert!(event.get("pathParameters").is_none());
+}
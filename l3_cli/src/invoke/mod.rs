@@ -0,0 +1,109 @@
+mod events;
+
+#[cfg(test)]
+mod events_test;
+
+use crate::invoke::events::EventTemplate;
+use crate::run_fn::{build_local_fn, fn_runtime, invoke_fn};
+use crate::{LLLCommandRun, LLLCommandRunError, LLLCommandRunResult};
+use clap::Parser;
+use l3_fn_build::runtime::node::NodeConfig;
+use l3_fn_config::LLLConfigs;
+use serde_json::Value;
+use std::io::{self, IsTerminal, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::{env, fs, process};
+
+#[derive(Parser, Debug)]
+pub struct InvokeCommand {
+    #[clap(help = "Name of the Lambda function")]
+    lambda_name: String,
+    #[clap(
+        long,
+        conflicts_with = "template",
+        long_help = "Path to a JSON event file or - to read the event from stdin"
+    )]
+    event: Option<PathBuf>,
+    #[clap(long, value_enum, long_help = "Invoke with a built-in sample event")]
+    template: Option<EventTemplate>,
+}
+
+impl LLLCommandRun for InvokeCommand {
+    async fn run(&self) -> LLLCommandRunResult {
+        let project_dir = Arc::new(env::current_dir().expect("cwd"));
+        let mut configs = LLLConfigs::new(project_dir.clone());
+        let update_result = configs.update_all_configs();
+        if !update_result.config_errs.is_empty() {
+            for err in update_result.config_errs {
+                println!("\x1b[31m✗\x1b[0m config error: {err}");
+            }
+            process::exit(1);
+        }
+        let lambda_spec = configs
+            .lambda_by_name(&self.lambda_name)
+            .ok_or_else(|| LLLCommandRunError::LambdaNotFound(self.lambda_name.clone()))?;
+        let env_vars = configs.lambda_env_vars(&lambda_spec.name)?;
+        let event = match &self.template {
+            Some(template) => template.create_event(&lambda_spec),
+            None => self.read_event()?,
+        };
+        let node_config = Arc::new(NodeConfig::read_configs(&project_dir).unwrap());
+        let build_manifest = build_local_fn(
+            &project_dir,
+            &lambda_spec,
+            fn_runtime(&configs, &lambda_spec, &node_config),
+            None,
+        )
+        .await?;
+        let invocation = invoke_fn(&lambda_spec, &build_manifest, env_vars, &event).await?;
+        print!("{}", invocation.logs);
+        match invocation.result {
+            Ok(result) => {
+                println!(
+                    "\x1b[32m✔\x1b[0m invoked {} in {}ms",
+                    lambda_spec.name,
+                    invocation.duration.as_millis()
+                );
+                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                Ok(())
+            }
+            Err(err) => {
+                println!(
+                    "\x1b[31m✗\x1b[0m invoked {} in {}ms with error {err}",
+                    lambda_spec.name,
+                    invocation.duration.as_millis()
+                );
+                for line in &err.stack_trace {
+                    println!("    {line}");
+                }
+                process::exit(1);
+            }
+        }
+    }
+}
+
+impl InvokeCommand {
+    // reads the --event file or stdin when piped and defaults to an empty json object
+    fn read_event(&self) -> Result<Value, LLLCommandRunError> {
+        let (event_source, event_json) = match &self.event {
+            Some(p) if p.as_os_str() == "-" => ("stdin".to_string(), read_stdin()?),
+            Some(p) => (p.to_string_lossy().to_string(), fs::read_to_string(p)?),
+            None if !io::stdin().is_terminal() => ("stdin".to_string(), read_stdin()?),
+            None => (String::new(), String::new()),
+        };
+        if event_json.trim().is_empty() && self.event.is_none() {
+            return Ok(Value::Object(Default::default()));
+        }
+        serde_json::from_str(&event_json).map_err(|err| LLLCommandRunError::InvalidEvent {
+            event_source,
+            cause: err.to_string(),
+        })
+    }
+}
+
+fn read_stdin() -> io::Result<String> {
+    let mut event_json = String::new();
+    io::stdin().read_to_string(&mut event_json)?;
+    Ok(event_json)
+}
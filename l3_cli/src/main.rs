@@ -1,13 +1,36 @@
 mod build;
+mod dev;
+mod invoke;
+mod run_fn;
+mod runtime_api;
+mod watch;
+
+#[cfg(test)]
+mod build_test;
 
 use build::BuildCommand;
 use clap::{Parser, Subcommand};
+use dev::DevCommand;
+use invoke::InvokeCommand;
+use l3_fn_config::ConfigParseError;
+use run_fn::FnInvokeError;
 use std::process::exit;
+use watch::WatchCommand;
 use LLLCommand::*;
 
 #[derive(Debug, thiserror::Error)]
 enum LLLCommandRunError {
-    #[error("current directory does not have a ./routes directory with Lambda functions")]
+    #[error("{0}")]
+    ConfigError(#[from] ConfigParseError),
+    #[error("{0}")]
+    FnInvokeError(#[from] FnInvokeError),
+    #[error("invalid event json from {event_source}: {cause}")]
+    InvalidEvent { event_source: String, cause: String },
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Lambda function {0} not found")]
+    LambdaNotFound(String),
+    #[error("current directory does not have Lambda functions in ./routes or l3.toml")]
     LambdasNotFound,
 }
 
@@ -28,12 +51,21 @@ struct LLLCli {
 enum LLLCommand {
     #[clap(about = "Build Lambda functions")]
     Build(BuildCommand),
+    #[clap(about = "Serve HTTP routes with a local dev server")]
+    Dev(DevCommand),
+    #[clap(about = "Invoke a Lambda function locally")]
+    Invoke(InvokeCommand),
+    #[clap(about = "Rebuild Lambda functions on source and config changes")]
+    Watch(WatchCommand),
 }
 
 #[tokio::main]
 async fn main() {
     let result = match LLLCli::parse().command {
         Build(build) => build.run().await,
+        Dev(dev) => dev.run().await,
+        Invoke(invoke) => invoke.run().await,
+        Watch(watch) => watch.run().await,
     };
     if let Err(err) = result {
         println!("\x1b[0;31;1merror:\x1b[0m {err}");
@@ -0,0 +1,254 @@
+use crate::runtime_api::{InvocationReceivers, RuntimeApi};
+use l3_fn_build::runtime::{node::NodeConfig, Runtime};
+use l3_fn_build::{BuildMode, FnBuildError, FnBuildManifest, FnBuildSpec, FnOutputConfig};
+use l3_fn_config::{ConfigParseError, LLLConfigs, LambdaRuntimeSpec, LambdaSpec};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{fmt, io};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::task::JoinHandle;
+
+/// Lambda's default timeout of an invocation from when the runtime fetches it.
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Lambda's limit for a runtime's init phase before its first invocation.
+const INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum FnInvokeError {
+    #[error("build error: {0}")]
+    Build(#[from] FnBuildError),
+    #[error("{0}")]
+    Config(#[from] ConfigParseError),
+    #[error("{0}")]
+    IoError(#[from] io::Error),
+    #[error("{runtime} runtime exited with {status}\n{logs}")]
+    RuntimeExit {
+        runtime: String,
+        status: ExitStatus,
+        logs: String,
+    },
+}
+
+/// Error thrown or raised by a lambda's handler fn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandlerError {
+    pub error_type: String,
+    pub error_message: String,
+    pub stack_trace: Vec<String>,
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error_type, self.error_message)
+    }
+}
+
+/// Result of running a lambda's handler fn in a local runtime subprocess.
+pub struct FnInvocation {
+    pub duration: Duration,
+    /// Output written to stdout and stderr by the handler.
+    pub logs: String,
+    pub result: Result<Value, HandlerError>,
+}
+
+pub fn fn_runtime(
+    configs: &LLLConfigs,
+    lambda_spec: &LambdaSpec,
+    node_config: &Arc<NodeConfig>,
+) -> Runtime {
+    match &lambda_spec.runtime {
+        LambdaRuntimeSpec::Node => {
+            Runtime::Node(Some(match configs.node_version(&lambda_spec.name) {
+                Some(version) => {
+                    Arc::new(NodeConfig::clone(node_config).with_version(Some(version)))
+                }
+                None => node_config.clone(),
+            }))
+        }
+        LambdaRuntimeSpec::Python => Runtime::Python(configs.python_version(&lambda_spec.name)),
+    }
+}
+
+/// Debug builds a lambda for local invocations to `.l3/local/{lambda_name}`, reusing outputs of
+/// a previous build's unchanged sources. Local builds are kept apart from `l3 build` outputs in
+/// `.l3/debug` because they do not update the `l3_build.json` checksums of those outputs.
+pub async fn build_local_fn(
+    project_dir: &Arc<PathBuf>,
+    lambda_spec: &LambdaSpec,
+    runtime: Runtime,
+    previous_build: Option<FnBuildManifest>,
+) -> Result<FnBuildManifest, FnInvokeError> {
+    Ok(l3_fn_build::build_fn(FnBuildSpec {
+        bundle: None,
+        conditions: Vec::new(),
+        entrypoint: lambda_spec.source.clone(),
+        handler_fn_name: lambda_spec.handler.clone(),
+        mode: BuildMode::Debug,
+        output: FnOutputConfig {
+            build_root: project_dir.join(".l3").join("local"),
+            create_archive: false,
+            dirname: lambda_spec.name.clone(),
+            use_build_mode: false,
+        },
+        previous_build,
+        project_dir: project_dir.clone(),
+        runtime,
+        source_maps: None,
+    })
+    .await?)
+}
+
+/// Runs a lambda's handler fn from its build output by booting a `node` or `python3` runtime
+/// against a local Lambda Runtime API.
+pub async fn invoke_fn(
+    lambda_spec: &LambdaSpec,
+    build_manifest: &FnBuildManifest,
+    env_vars: impl IntoIterator<Item = (String, String)>,
+    event: &Value,
+) -> Result<FnInvocation, FnInvokeError> {
+    let runtime_api = RuntimeApi::start(&lambda_spec.name).await?;
+    let InvocationReceivers {
+        mut started,
+        result: mut invocation,
+    } = runtime_api.invoke(create_request_id(), event, TIMEOUT);
+    let start = Instant::now();
+    // the lambda's configured env vars take precedence over the env vars of its build
+    let env_vars = build_manifest.env_vars.clone().into_iter().chain(env_vars);
+    let mut child = runtime_api.boot_runtime(lambda_spec, build_manifest, env_vars)?;
+    let logs = Arc::new(Mutex::new(String::new()));
+    let log_readers = [
+        read_logs(child.stdout.take().unwrap(), logs.clone()),
+        read_logs(child.stderr.take().unwrap(), logs.clone()),
+    ];
+    // INIT_TIMEOUT limits the runtime's init until it fetches the invocation, which starts the
+    // invocation's TIMEOUT
+    let init_outcome = tokio::select! {
+        biased;
+        result = &mut invocation => {
+            Some(RuntimeOutcome::Completed(result.expect("runtime api invocation")))
+        }
+        status = child.wait() => Some(RuntimeOutcome::Exited(status?)),
+        // a dropped sender of an init error is completed by the invocation's result
+        _ = &mut started => None,
+        _ = tokio::time::sleep(INIT_TIMEOUT) => Some(RuntimeOutcome::InitTimedOut),
+    };
+    let outcome = match init_outcome {
+        Some(outcome) => outcome,
+        None => tokio::select! {
+            biased;
+            result = invocation => {
+                RuntimeOutcome::Completed(result.expect("runtime api invocation"))
+            }
+            status = child.wait() => RuntimeOutcome::Exited(status?),
+            _ = tokio::time::sleep(TIMEOUT) => RuntimeOutcome::TimedOut,
+        },
+    };
+    let duration = start.elapsed();
+    _ = child.kill().await;
+    for log_reader in log_readers {
+        _ = log_reader.await;
+    }
+    let logs = Arc::try_unwrap(logs)
+        .map(|logs| logs.into_inner().unwrap())
+        .unwrap_or_default();
+    let result = match outcome {
+        RuntimeOutcome::Completed(result) => result,
+        RuntimeOutcome::Exited(status) => {
+            return Err(FnInvokeError::RuntimeExit {
+                runtime: match &lambda_spec.runtime {
+                    LambdaRuntimeSpec::Node => "node",
+                    LambdaRuntimeSpec::Python => "python",
+                }
+                .to_string(),
+                status,
+                logs,
+            })
+        }
+        RuntimeOutcome::InitTimedOut => Err(HandlerError {
+            error_type: "Sandbox.Timedout".to_string(),
+            error_message: format!(
+                "Init timed out after {:.2} seconds",
+                INIT_TIMEOUT.as_secs_f32()
+            ),
+            stack_trace: Vec::new(),
+        }),
+        RuntimeOutcome::TimedOut => Err(HandlerError {
+            error_type: "Sandbox.Timedout".to_string(),
+            error_message: format!("Task timed out after {:.2} seconds", TIMEOUT.as_secs_f32()),
+            stack_trace: Vec::new(),
+        }),
+    };
+    Ok(FnInvocation {
+        duration,
+        logs,
+        result,
+    })
+}
+
+enum RuntimeOutcome {
+    Completed(Result<Value, HandlerError>),
+    Exited(ExitStatus),
+    /// The runtime did not fetch the invocation within INIT_TIMEOUT.
+    InitTimedOut,
+    /// The handler did not complete within TIMEOUT of the runtime fetching the invocation.
+    TimedOut,
+}
+
+// appends lines of a runtime's stdout or stderr to the invocation's logs
+fn read_logs(
+    output: impl AsyncRead + Unpin + Send + 'static,
+    logs: Arc<Mutex<String>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(output).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut logs = logs.lock().unwrap();
+            logs.push_str(&line);
+            logs.push('\n');
+        }
+    })
+}
+
+impl From<Value> for HandlerError {
+    fn from(error: Value) -> Self {
+        let str_field = |field: &str| {
+            error
+                .get(field)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        Self {
+            error_type: str_field("errorType"),
+            error_message: str_field("errorMessage"),
+            stack_trace: error
+                .get("stackTrace")
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// unique enough for identifying local invocations
+pub fn create_request_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!(
+        "{:016x}-{:04x}",
+        nanos as u64,
+        COUNTER.fetch_add(1, Ordering::Relaxed) & 0xffff
+    )
+}
@@ -0,0 +1,102 @@
+// Bootstraps a built Node.js lambda against the Lambda Runtime API at AWS_LAMBDA_RUNTIME_API.
+// `_HANDLER` is a LAMBDA_TASK_ROOT relative module path without its file extension and the
+// handler fn name, such as `routes/data/lambda.GET`.
+
+import { existsSync } from 'node:fs'
+import { join } from 'node:path'
+import { pathToFileURL } from 'node:url'
+
+const {
+    AWS_LAMBDA_FUNCTION_MEMORY_SIZE: memoryLimitInMB,
+    AWS_LAMBDA_FUNCTION_NAME: functionName,
+    AWS_LAMBDA_FUNCTION_VERSION: functionVersion,
+    AWS_LAMBDA_RUNTIME_API: runtimeApi,
+    LAMBDA_TASK_ROOT: taskRoot,
+    _HANDLER: handlerSetting,
+} = process.env
+
+const runtimeUrl = `http://${runtimeApi}/2018-06-01/runtime`
+
+function errorBody(e) {
+    return JSON.stringify({
+        errorType: e?.name ?? 'Error',
+        errorMessage: e?.message ?? String(e),
+        stackTrace: e?.stack?.split('\n') ?? [],
+    })
+}
+
+async function postError(path, e) {
+    await fetch(`${runtimeUrl}/${path}`, {
+        method: 'POST',
+        headers: { 'Lambda-Runtime-Function-Error-Type': e?.name ?? 'Error' },
+        body: errorBody(e),
+    })
+}
+
+async function loadHandler() {
+    const separator = handlerSetting.lastIndexOf('.')
+    const modulePath = handlerSetting.substring(0, separator)
+    const handlerFn = handlerSetting.substring(separator + 1)
+    const file = ['.js', '.mjs', '.cjs']
+        .map(extension => join(taskRoot, modulePath + extension))
+        .find(existsSync)
+    if (!file) {
+        const err = new Error(`Cannot find module '${modulePath}'`)
+        err.name = 'Runtime.ImportModuleError'
+        throw err
+    }
+    const module = await import(pathToFileURL(file).href)
+    const handler = module[handlerFn]
+    if (typeof handler !== 'function') {
+        const err = new Error(`${modulePath}.${handlerFn} is undefined or not exported`)
+        err.name = 'Runtime.HandlerNotFound'
+        throw err
+    }
+    if (handler.length < 3) {
+        return handler
+    }
+    return (event, context) =>
+        new Promise((resolve, reject) => {
+            const result = handler(event, context, (err, result) => (err ? reject(err) : resolve(result)))
+            if (typeof result?.then === 'function') {
+                result.then(resolve, reject)
+            }
+        })
+}
+
+let handler
+try {
+    handler = await loadHandler()
+} catch (e) {
+    console.error(e)
+    await postError('init/error', e)
+    process.exit(1)
+}
+
+while (true) {
+    const next = await fetch(`${runtimeUrl}/invocation/next`)
+    const awsRequestId = next.headers.get('lambda-runtime-aws-request-id')
+    const deadline = parseInt(next.headers.get('lambda-runtime-deadline-ms'), 10)
+    const event = await next.json()
+    const context = {
+        awsRequestId,
+        callbackWaitsForEmptyEventLoop: true,
+        functionName,
+        functionVersion,
+        invokedFunctionArn: next.headers.get('lambda-runtime-invoked-function-arn'),
+        logGroupName: `/aws/lambda/${functionName}`,
+        logStreamName: 'l3',
+        memoryLimitInMB,
+        getRemainingTimeInMillis: () => Math.max(0, deadline - Date.now()),
+    }
+    try {
+        const result = await handler(event, context)
+        await fetch(`${runtimeUrl}/invocation/${awsRequestId}/response`, {
+            method: 'POST',
+            body: JSON.stringify(result ?? null),
+        })
+    } catch (e) {
+        console.error(e)
+        await postError(`invocation/${awsRequestId}/error`, e)
+    }
+}
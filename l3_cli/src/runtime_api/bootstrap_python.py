@@ -0,0 +1,80 @@
+# Bootstraps a built Python lambda against the Lambda Runtime API at AWS_LAMBDA_RUNTIME_API.
+# `_HANDLER` is a LAMBDA_TASK_ROOT relative module path without its file extension and the
+# handler fn name, such as `routes/data/lambda.get`.
+
+import importlib
+import json
+import os
+import sys
+import time
+import traceback
+import urllib.request
+
+runtime_url = f"http://{os.environ['AWS_LAMBDA_RUNTIME_API']}/2018-06-01/runtime"
+
+
+class LambdaContext:
+    def __init__(self, request_id, deadline_ms, invoked_function_arn):
+        self.aws_request_id = request_id
+        self.function_name = os.environ['AWS_LAMBDA_FUNCTION_NAME']
+        self.function_version = os.environ['AWS_LAMBDA_FUNCTION_VERSION']
+        self.invoked_function_arn = invoked_function_arn
+        self.log_group_name = f'/aws/lambda/{self.function_name}'
+        self.log_stream_name = 'l3'
+        self.memory_limit_in_mb = os.environ['AWS_LAMBDA_FUNCTION_MEMORY_SIZE']
+        self._deadline_ms = deadline_ms
+
+    def get_remaining_time_in_millis(self):
+        return max(0, self._deadline_ms - int(time.time() * 1000))
+
+
+def post(path, body, headers=None):
+    request = urllib.request.Request(f'{runtime_url}/{path}', data=body, headers=headers or {}, method='POST')
+    urllib.request.urlopen(request).read()
+
+
+def post_error(path, e, error_type=None):
+    error_type = error_type or type(e).__name__
+    body = json.dumps({
+        'errorType': error_type,
+        'errorMessage': str(e),
+        'stackTrace': traceback.format_tb(e.__traceback__),
+    }).encode('utf-8')
+    post(path, body, {'Lambda-Runtime-Function-Error-Type': error_type})
+
+
+def load_handler():
+    module_path, handler_fn = os.environ['_HANDLER'].rsplit('.', 1)
+    sys.path.insert(0, os.environ['LAMBDA_TASK_ROOT'])
+    try:
+        module = importlib.import_module(module_path.replace('/', '.'))
+    except Exception as e:
+        traceback.print_exc()
+        post_error('init/error', e, 'Runtime.ImportModuleError')
+        sys.exit(1)
+    handler = getattr(module, handler_fn, None)
+    if not callable(handler):
+        e = AttributeError(f'{module_path}.{handler_fn} is undefined or not exported')
+        print(e, file=sys.stderr)
+        post_error('init/error', e, 'Runtime.HandlerNotFound')
+        sys.exit(1)
+    return handler
+
+
+handler = load_handler()
+
+while True:
+    with urllib.request.urlopen(f'{runtime_url}/invocation/next') as next_invocation:
+        request_id = next_invocation.headers['Lambda-Runtime-Aws-Request-Id']
+        context = LambdaContext(
+            request_id,
+            int(next_invocation.headers['Lambda-Runtime-Deadline-Ms']),
+            next_invocation.headers['Lambda-Runtime-Invoked-Function-Arn'],
+        )
+        event = json.loads(next_invocation.read() or 'null')
+    try:
+        result = handler(event, context)
+        post(f'invocation/{request_id}/response', json.dumps(result, default=str).encode('utf-8'))
+    except Exception as e:
+        traceback.print_exc()
+        post_error(f'invocation/{request_id}/error', e)
@@ -0,0 +1,142 @@
+use crate::run_fn::HandlerError;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{oneshot, Notify};
+
+pub type InvocationResult = Result<Value, HandlerError>;
+
+/// An invocation delivered to a runtime by `/runtime/invocation/next`.
+pub struct NextInvocation {
+    pub request_id: String,
+    pub deadline_ms: u64,
+    pub event: Vec<u8>,
+}
+
+/// Receivers of a queued invocation's start and result.
+pub struct InvocationReceivers {
+    /// Receives when a runtime fetches the invocation from `/runtime/invocation/next`.
+    pub started: oneshot::Receiver<()>,
+    pub result: oneshot::Receiver<InvocationResult>,
+}
+
+struct QueuedInvocation {
+    request_id: String,
+    event: Vec<u8>,
+    started: oneshot::Sender<()>,
+    timeout: Duration,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum InvocationStateError {
+    #[error("runtime already reported an init error")]
+    AlreadyInitErrored,
+    #[error("invocation {0} is not in progress")]
+    InvalidRequestId(String),
+}
+
+#[derive(Default)]
+struct InvocationQueues {
+    init_error: Option<HandlerError>,
+    in_progress: HashMap<String, oneshot::Sender<InvocationResult>>,
+    queued: VecDeque<(QueuedInvocation, oneshot::Sender<InvocationResult>)>,
+}
+
+/// Tracks invocations from being queued for a runtime until the runtime posts a response or error.
+#[derive(Default)]
+pub struct Invocations {
+    queues: Mutex<InvocationQueues>,
+    queued: Notify,
+}
+
+impl Invocations {
+    /// Queues an event for the runtime and returns receivers for the invocation's start and result.
+    pub fn queue(
+        &self,
+        request_id: String,
+        event: &Value,
+        timeout: Duration,
+    ) -> InvocationReceivers {
+        let (started_tx, started_rx) = oneshot::channel();
+        let (tx, rx) = oneshot::channel();
+        let mut queues = self.queues.lock().unwrap();
+        match &queues.init_error {
+            Some(init_error) => {
+                _ = tx.send(Err(init_error.clone()));
+            }
+            None => {
+                queues.queued.push_back((
+                    QueuedInvocation {
+                        request_id,
+                        event: event.to_string().into_bytes(),
+                        started: started_tx,
+                        timeout,
+                    },
+                    tx,
+                ));
+                self.queued.notify_one();
+            }
+        }
+        InvocationReceivers {
+            started: started_rx,
+            result: rx,
+        }
+    }
+
+    /// Waits for a queued invocation and marks it in progress with a deadline of its timeout.
+    pub async fn next(&self) -> NextInvocation {
+        loop {
+            if let Some(next) = self.try_next() {
+                return next;
+            }
+            self.queued.notified().await;
+        }
+    }
+
+    fn try_next(&self) -> Option<NextInvocation> {
+        let mut queues = self.queues.lock().unwrap();
+        let (invocation, tx) = queues.queued.pop_front()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        queues.in_progress.insert(invocation.request_id.clone(), tx);
+        _ = invocation.started.send(());
+        Some(NextInvocation {
+            deadline_ms: (now + invocation.timeout).as_millis() as u64,
+            event: invocation.event,
+            request_id: invocation.request_id,
+        })
+    }
+
+    /// Completes an in progress invocation with the runtime's response or error.
+    pub fn complete(
+        &self,
+        request_id: &str,
+        result: InvocationResult,
+    ) -> Result<(), InvocationStateError> {
+        match self.queues.lock().unwrap().in_progress.remove(request_id) {
+            Some(tx) => {
+                _ = tx.send(result);
+                Ok(())
+            }
+            None => Err(InvocationStateError::InvalidRequestId(
+                request_id.to_string(),
+            )),
+        }
+    }
+
+    /// Fails queued and in progress invocations with an error the runtime posted during init.
+    pub fn init_error(&self, error: HandlerError) -> Result<(), InvocationStateError> {
+        let mut queues = self.queues.lock().unwrap();
+        if queues.init_error.is_some() {
+            return Err(InvocationStateError::AlreadyInitErrored);
+        }
+        for (_, tx) in queues.in_progress.drain() {
+            _ = tx.send(Err(error.clone()));
+        }
+        for (_, tx) in queues.queued.drain(..) {
+            _ = tx.send(Err(error.clone()));
+        }
+        queues.init_error = Some(error);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::run_fn::HandlerError;
+use crate::runtime_api::invocations::{InvocationStateError, Invocations};
+use serde_json::json;
+use std::time::Duration;
+
+fn handler_error(error_type: &str) -> HandlerError {
+    HandlerError {
+        error_type: error_type.to_string(),
+        error_message: "oops".to_string(),
+        stack_trace: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_invocations_next_and_complete() {
+    let invocations = Invocations::default();
+    let rx = invocations.queue("1".into(), &json!({"a": 1}), Duration::from_secs(3));
+    let next = invocations.next().await;
+    assert_eq!(next.request_id, "1");
+    assert_eq!(next.event, br#"{"a":1}"#);
+    invocations.complete("1", Ok(json!("done"))).unwrap();
+    assert_eq!(rx.result.await.unwrap(), Ok(json!("done")));
+}
+
+#[tokio::test]
+async fn test_invocations_next_starts_invocation() {
+    let invocations = Invocations::default();
+    let mut rx = invocations.queue("1".into(), &json!(null), Duration::from_secs(3));
+    assert!(rx.started.try_recv().is_err());
+    invocations.next().await;
+    assert_eq!(rx.started.await, Ok(()));
+}
+
+#[tokio::test]
+async fn test_invocations_next_waits_for_queued_invocation() {
+    let invocations = std::sync::Arc::new(Invocations::default());
+    let next = tokio::spawn({
+        let invocations = invocations.clone();
+        async move { invocations.next().await }
+    });
+    tokio::task::yield_now().await;
+    let rx = invocations.queue("1".into(), &json!(null), Duration::from_secs(3));
+    assert_eq!(next.await.unwrap().request_id, "1");
+    invocations
+        .complete("1", Err(handler_error("TypeError")))
+        .unwrap();
+    assert_eq!(rx.result.await.unwrap(), Err(handler_error("TypeError")));
+}
+
+#[tokio::test]
+async fn test_invocations_complete_errors_for_invocation_not_in_progress() {
+    let invocations = Invocations::default();
+    let _rx = invocations.queue("1".into(), &json!(null), Duration::from_secs(3));
+    assert_eq!(
+        invocations.complete("1", Ok(json!(null))),
+        Err(InvocationStateError::InvalidRequestId("1".into()))
+    );
+}
+
+#[tokio::test]
+async fn test_invocations_init_error_fails_invocations() {
+    let invocations = Invocations::default();
+    let queued_rx = invocations.queue("1".into(), &json!(null), Duration::from_secs(3));
+    invocations
+        .init_error(handler_error("Runtime.HandlerNotFound"))
+        .unwrap();
+    assert_eq!(
+        queued_rx.result.await.unwrap(),
+        Err(handler_error("Runtime.HandlerNotFound"))
+    );
+    let later_rx = invocations.queue("2".into(), &json!(null), Duration::from_secs(3));
+    assert_eq!(
+        later_rx.result.await.unwrap(),
+        Err(handler_error("Runtime.HandlerNotFound"))
+    );
+    assert_eq!(
+        invocations.init_error(handler_error("Runtime.HandlerNotFound")),
+        Err(InvocationStateError::AlreadyInitErrored)
+    );
+}
@@ -0,0 +1,166 @@
+mod invocations;
+mod server;
+
+#[cfg(test)]
+mod invocations_test;
+pub use invocations::InvocationReceivers;
+
+use crate::runtime_api::invocations::Invocations;
+use crate::runtime_api::server::handle_runtime_request;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use l3_fn_build::{FnBuildHandler, FnBuildManifest};
+use l3_fn_config::{LambdaRuntimeSpec, LambdaSpec};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+
+#[cfg(target_os = "windows")]
+mod bin {
+    pub const NODE: &str = "node.exe";
+    pub const PYTHON: &str = "python.exe";
+}
+
+#[cfg(not(target_os = "windows"))]
+mod bin {
+    pub const NODE: &str = "node";
+    pub const PYTHON: &str = "python3";
+}
+
+const BOOTSTRAP_NODE_SCRIPT: &str = include_str!("bootstrap_node.mjs");
+
+const BOOTSTRAP_PYTHON_SCRIPT: &str = include_str!("bootstrap_python.py");
+
+/// Emulates the AWS Lambda Runtime API on localhost for a runtime process booted from a
+/// lambda's build output.
+pub struct RuntimeApi {
+    addr: SocketAddr,
+    function_name: String,
+    invocations: Arc<Invocations>,
+    server: JoinHandle<()>,
+}
+
+impl RuntimeApi {
+    /// Starts serving the Runtime API on an ephemeral port of 127.0.0.1.
+    pub async fn start(function_name: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let addr = listener.local_addr()?;
+        let invocations = Arc::new(Invocations::default());
+        let function_arn = Arc::new(format!(
+            "arn:aws:lambda:local:000000000000:function:{function_name}"
+        ));
+        let server = tokio::spawn({
+            let invocations = invocations.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let invocations = invocations.clone();
+                    let function_arn = function_arn.clone();
+                    tokio::spawn(async move {
+                        let service = service_fn(move |request| {
+                            let invocations = invocations.clone();
+                            let function_arn = function_arn.clone();
+                            async move {
+                                Ok::<_, Infallible>(
+                                    handle_runtime_request(invocations, function_arn, request)
+                                        .await,
+                                )
+                            }
+                        });
+                        _ = http1::Builder::new()
+                            .serve_connection(TokioIo::new(stream), service)
+                            .await;
+                    });
+                }
+            }
+        });
+        Ok(Self {
+            addr,
+            function_name: function_name.to_string(),
+            invocations,
+            server,
+        })
+    }
+
+    /// Value of `AWS_LAMBDA_RUNTIME_API` for a runtime process.
+    pub fn runtime_api_host(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// Queues an invocation that a runtime receives from `/runtime/invocation/next`.
+    pub fn invoke(
+        &self,
+        request_id: String,
+        event: &Value,
+        timeout: Duration,
+    ) -> InvocationReceivers {
+        self.invocations.queue(request_id, event, timeout)
+    }
+
+    /// Boots a `node` or `python3` bootstrap process that runs the lambda's handler fn from its
+    /// build output against this Runtime API.
+    pub fn boot_runtime(
+        &self,
+        lambda_spec: &LambdaSpec,
+        build_manifest: &FnBuildManifest,
+        env_vars: impl IntoIterator<Item = (String, String)>,
+    ) -> io::Result<Child> {
+        let build_dir = &build_manifest.output.build_dir;
+        let mut command = match &lambda_spec.runtime {
+            LambdaRuntimeSpec::Node => {
+                let mut command = Command::new(bin::NODE);
+                command.args(["--input-type=module", "-e", BOOTSTRAP_NODE_SCRIPT]);
+                command
+            }
+            LambdaRuntimeSpec::Python => {
+                let mut command = Command::new(bin::PYTHON);
+                command
+                    .args(["-c", BOOTSTRAP_PYTHON_SCRIPT])
+                    .env("PYTHONUNBUFFERED", "1");
+                command
+            }
+        };
+        command
+            .current_dir(build_dir)
+            .envs(env_vars)
+            .env("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "128")
+            .env("AWS_LAMBDA_FUNCTION_NAME", &self.function_name)
+            .env("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST")
+            .env("AWS_LAMBDA_RUNTIME_API", self.runtime_api_host())
+            .env("LAMBDA_TASK_ROOT", build_dir)
+            .env("_HANDLER", handler_setting(&build_manifest.handler))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+    }
+}
+
+impl Drop for RuntimeApi {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+// `_HANDLER` setting of a build dir relative source path and handler fn such as `routes/data/lambda.GET`
+fn handler_setting(handler: &FnBuildHandler) -> String {
+    format!(
+        "{}.{}",
+        handler
+            .path
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join("/"),
+        handler.fn_name
+    )
+}
@@ -0,0 +1,135 @@
+use crate::run_fn::HandlerError;
+use crate::runtime_api::invocations::Invocations;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::CONTENT_TYPE;
+use hyper::{Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const RUNTIME_PATH_PREFIX: &str = "/2018-06-01/runtime/";
+
+const FUNCTION_ERROR_TYPE_HEADER: &str = "lambda-runtime-function-error-type";
+
+/// Handles a request from a runtime to the Lambda Runtime API.
+pub async fn handle_runtime_request(
+    invocations: Arc<Invocations>,
+    function_arn: Arc<String>,
+    request: Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    let Some(path) = request.uri().path().strip_prefix(RUNTIME_PATH_PREFIX) else {
+        return error_response(StatusCode::NOT_FOUND, "NotFound", "unknown path");
+    };
+    let segments: Vec<&str> = path.split('/').collect();
+    match (request.method(), segments.as_slice()) {
+        (&Method::GET, ["invocation", "next"]) => {
+            let next = invocations.next().await;
+            Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .header("lambda-runtime-aws-request-id", &next.request_id)
+                .header("lambda-runtime-deadline-ms", next.deadline_ms.to_string())
+                .header("lambda-runtime-invoked-function-arn", function_arn.as_str())
+                .header(
+                    "lambda-runtime-trace-id",
+                    format!("Root=1-{}", next.request_id),
+                )
+                .body(Full::from(next.event))
+                .unwrap()
+        }
+        (&Method::POST, ["invocation", request_id, "response"]) => {
+            let request_id = request_id.to_string();
+            let body = match read_body(request).await {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            let result = match serde_json::from_slice::<Value>(&body) {
+                Ok(result) => result,
+                Err(_) if body.is_empty() => Value::Null,
+                Err(_) => Value::String(String::from_utf8_lossy(&body).to_string()),
+            };
+            accepted_or_invalid(invocations.complete(&request_id, Ok(result)))
+        }
+        (&Method::POST, ["invocation", request_id, "error"]) => {
+            let request_id = request_id.to_string();
+            let error = match read_handler_error(request).await {
+                Ok(error) => error,
+                Err(response) => return response,
+            };
+            accepted_or_invalid(invocations.complete(&request_id, Err(error)))
+        }
+        (&Method::POST, ["init", "error"]) => {
+            let error = match read_handler_error(request).await {
+                Ok(error) => error,
+                Err(response) => return response,
+            };
+            match invocations.init_error(error) {
+                Ok(()) => accepted(),
+                Err(err) => error_response(
+                    StatusCode::FORBIDDEN,
+                    "Runtime.AlreadyInitErrored",
+                    &err.to_string(),
+                ),
+            }
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "NotFound", "unknown path"),
+    }
+}
+
+async fn read_body(request: Request<Incoming>) -> Result<Bytes, Response<Full<Bytes>>> {
+    match request.into_body().collect().await {
+        Ok(body) => Ok(body.to_bytes()),
+        Err(err) => Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "InvalidRequestBody",
+            &err.to_string(),
+        )),
+    }
+}
+
+// error types default to the Lambda-Runtime-Function-Error-Type header of error requests
+async fn read_handler_error(
+    request: Request<Incoming>,
+) -> Result<HandlerError, Response<Full<Bytes>>> {
+    let error_type_header = request
+        .headers()
+        .get(FUNCTION_ERROR_TYPE_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+    let body = read_body(request).await?;
+    let mut error =
+        HandlerError::from(serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null));
+    if error.error_type.is_empty() {
+        error.error_type = error_type_header.unwrap_or_else(|| "Runtime.Unknown".to_string());
+    }
+    Ok(error)
+}
+
+fn accepted_or_invalid<E: ToString>(result: Result<(), E>) -> Response<Full<Bytes>> {
+    match result {
+        Ok(()) => accepted(),
+        Err(err) => error_response(
+            StatusCode::BAD_REQUEST,
+            "InvalidRequestID",
+            &err.to_string(),
+        ),
+    }
+}
+
+fn accepted() -> Response<Full<Bytes>> {
+    json_response(StatusCode::ACCEPTED, json!({"status": "OK"}))
+}
+
+fn error_response(status: StatusCode, error_type: &str, message: &str) -> Response<Full<Bytes>> {
+    json_response(
+        status,
+        json!({"errorType": error_type, "errorMessage": message}),
+    )
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::from(body.to_string()))
+        .unwrap()
+}
@@ -0,0 +1,74 @@
+use l3_fn_build::FnBuildManifest;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Directories of a project that do not contain sources of lambdas.
+const IGNORED_DIRS: [&str; 4] = [".git", ".l3", "__pycache__", "node_modules"];
+
+/// Node.js configs that are read for every Node.js lambda's build.
+const NODE_CONFIG_FILES: [&str; 2] = ["package.json", "tsconfig.json"];
+
+/// Python dependency configs that are read for every Python lambda's build.
+const PYTHON_CONFIG_FILES: [&str; 4] = [
+    "poetry.lock",
+    "pyproject.toml",
+    "requirements.txt",
+    "uv.lock",
+];
+
+/// Project relative paths of a batch of file changes sorted by how they affect lambdas.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectChanges {
+    /// Changes to l3.toml and route sources that reconfigure lambdas.
+    pub configs: Vec<PathBuf>,
+    /// Changes to package.json or tsconfig.json.
+    pub node_configs: bool,
+    /// Changes to requirements.txt, pyproject.toml, uv.lock or poetry.lock.
+    pub python_configs: bool,
+    /// Changes to files that may be a source of a lambda's build.
+    pub sources: HashSet<PathBuf>,
+}
+
+impl ProjectChanges {
+    pub fn new(project_dir: &Path, changed: HashSet<PathBuf>) -> Self {
+        let mut changes = Self::default();
+        for path in changed {
+            let Ok(path) = path.strip_prefix(project_dir) else {
+                continue;
+            };
+            if path
+                .components()
+                .any(|c| IGNORED_DIRS.iter().any(|ignored| c.as_os_str() == *ignored))
+            {
+                continue;
+            }
+            if path == Path::new("l3.toml") || path.starts_with("routes") {
+                changes.configs.push(path.to_path_buf());
+            }
+            if NODE_CONFIG_FILES.iter().any(|f| path == Path::new(f)) {
+                changes.node_configs = true;
+            } else if PYTHON_CONFIG_FILES.iter().any(|f| path == Path::new(f)) {
+                changes.python_configs = true;
+            } else if path != Path::new("l3.toml") {
+                changes.sources.insert(path.to_path_buf());
+            }
+        }
+        changes.configs.sort();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+            && !self.node_configs
+            && !self.python_configs
+            && self.sources.is_empty()
+    }
+
+    /// Whether a lambda's build includes any of the changed sources.
+    pub fn affects_build(&self, build_manifest: &FnBuildManifest) -> bool {
+        build_manifest
+            .sources
+            .iter()
+            .any(|source| self.sources.contains(&source.path))
+    }
+}
@@ -0,0 +1,93 @@
+use crate::watch::changes::ProjectChanges;
+use l3_fn_build::{
+    Checksum, FnBuildHandler, FnBuildManifest, FnBuildOutput, FnDependencies, FnSource,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+fn project_changes(paths: &[&str]) -> ProjectChanges {
+    let project_dir = Path::new("/project");
+    ProjectChanges::new(
+        project_dir,
+        paths.iter().map(|p| project_dir.join(p)).collect(),
+    )
+}
+
+#[test]
+fn test_project_changes_sorts_configs_and_sources() {
+    let changes = project_changes(&["l3.toml", "routes/data/lambda.js", "lib/data.js"]);
+    assert_eq!(
+        changes.configs,
+        vec![
+            PathBuf::from("l3.toml"),
+            PathBuf::from("routes/data/lambda.js")
+        ]
+    );
+    assert!(!changes.node_configs);
+    assert_eq!(
+        changes.sources,
+        HashSet::from([
+            PathBuf::from("lib/data.js"),
+            PathBuf::from("routes/data/lambda.js")
+        ])
+    );
+}
+
+#[test]
+fn test_project_changes_ignores_build_and_dependency_dirs() {
+    let changes = project_changes(&[
+        ".l3/debug/get-data/routes/data/lambda.js",
+        "node_modules/pkg/index.js",
+        "lib/__pycache__/data.cpython-313.pyc",
+        ".git/index",
+    ]);
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn test_project_changes_node_configs() {
+    let changes = project_changes(&["package.json", "tsconfig.json"]);
+    assert!(changes.node_configs);
+    assert!(changes.sources.is_empty());
+}
+
+#[test]
+fn test_project_changes_python_configs() {
+    let changes = project_changes(&["requirements.txt", "pyproject.toml", "uv.lock"]);
+    assert!(changes.python_configs);
+    assert!(!changes.node_configs);
+    assert!(changes.sources.is_empty());
+}
+
+#[test]
+fn test_project_changes_affects_build() {
+    let build_manifest = FnBuildManifest {
+        build_options: Checksum::try_from("debug").unwrap(),
+        checksums: HashMap::new(),
+        dependencies: FnDependencies::Unused,
+        entrypoint: PathBuf::from("routes/data/lambda.js"),
+        env_vars: HashMap::new(),
+        handler: FnBuildHandler {
+            fn_name: "GET".into(),
+            path: PathBuf::from("routes/data/lambda.js"),
+        },
+        output: FnBuildOutput {
+            archive_file: None,
+            build_dir: PathBuf::from("/project/.l3/debug/get-data"),
+            paths: HashMap::new(),
+            source_maps: HashMap::new(),
+        },
+        sources: vec![
+            FnSource {
+                imports: Vec::new(),
+                path: PathBuf::from("routes/data/lambda.js"),
+            },
+            FnSource {
+                imports: Vec::new(),
+                path: PathBuf::from("lib/data.js"),
+            },
+        ],
+    };
+    assert!(project_changes(&["lib/data.js"]).affects_build(&build_manifest));
+    assert!(!project_changes(&["lib/other.js"]).affects_build(&build_manifest));
+}
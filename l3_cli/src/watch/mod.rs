@@ -0,0 +1,209 @@
+mod changes;
+
+#[cfg(test)]
+mod changes_test;
+
+use crate::run_fn::{build_local_fn, fn_runtime, FnInvokeError};
+use crate::watch::changes::ProjectChanges;
+use crate::{LLLCommandRun, LLLCommandRunResult};
+use clap::Parser;
+use l3_fn_build::runtime::node::NodeConfig;
+use l3_fn_build::FnBuildManifest;
+use l3_fn_config::{LLLConfigs, LambdaRuntimeSpec, LambdaSpec, UpdateMutation};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{env, io};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// Delay for collecting a batch of file changes, such as an editor's save or a git checkout.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+#[derive(Parser, Debug)]
+pub struct WatchCommand {}
+
+type LambdaBuilds = HashMap<String, Result<FnBuildManifest, FnInvokeError>>;
+
+impl LLLCommandRun for WatchCommand {
+    async fn run(&self) -> LLLCommandRunResult {
+        let project_dir = Arc::new(env::current_dir().expect("cwd"));
+        let mut configs = LLLConfigs::new(project_dir.clone());
+        let update_result = configs.update_all_configs();
+        for err in update_result.config_errs {
+            println!("\x1b[31m✗\x1b[0m config error: {err}");
+        }
+        let mut node_config = Arc::new(NodeConfig::read_configs(&project_dir).unwrap());
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    if !matches!(event.kind, EventKind::Access(_)) {
+                        for path in event.paths {
+                            _ = tx.send(path);
+                        }
+                    }
+                }
+            })
+            .map_err(io::Error::other)?;
+        watcher
+            .watch(&project_dir, RecursiveMode::Recursive)
+            .map_err(io::Error::other)?;
+
+        let mut builds: LambdaBuilds = HashMap::new();
+        build_lambdas(
+            &project_dir,
+            &configs,
+            &node_config,
+            configs.lambdas(),
+            &mut builds,
+        )
+        .await;
+        println!(
+            "watching for changes to {} lambdas",
+            configs.lambdas().len()
+        );
+
+        loop {
+            let changed = tokio::select! {
+                changed = rx.recv() => changed,
+                _ = tokio::signal::ctrl_c() => None,
+            };
+            let Some(changed) = changed else {
+                break;
+            };
+            let mut changed = HashSet::from([changed]);
+            while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                changed.insert(path);
+            }
+            let changes = ProjectChanges::new(&project_dir, changed);
+            if changes.is_empty() {
+                continue;
+            }
+
+            let mut rebuilding: HashSet<String> = HashSet::new();
+            // sources of handlers re-exported by route sources outside ./routes reconfigure routes
+            let mut config_changes = changes.configs.clone();
+            for path in &changes.sources {
+                if !path.starts_with("routes") && configs.is_route_handler_source(path) {
+                    config_changes.push(path.clone());
+                }
+            }
+            if !config_changes.is_empty() {
+                // a moved or removed routes dir only notifies for the dir and requires a full update
+                let update_result = if config_changes
+                    .iter()
+                    .any(|p| p.starts_with("routes") && !project_dir.join(p).is_file())
+                {
+                    configs.update_all_configs()
+                } else {
+                    configs.update_configs(&config_changes)
+                };
+                for err in update_result.config_errs {
+                    println!("\x1b[31m✗\x1b[0m config error: {err}");
+                }
+                for mutation in update_result.mutations {
+                    match mutation {
+                        UpdateMutation::AddedLambda(name) => {
+                            println!("\x1b[32m+\x1b[0m added {name}");
+                            rebuilding.insert(name);
+                        }
+                        UpdateMutation::RemovedLambda(name) => {
+                            println!("\x1b[31m-\x1b[0m removed {name}");
+                            builds.remove(&name);
+                        }
+                        UpdateMutation::UpdatedLambda(name) => {
+                            println!("\x1b[33m~\x1b[0m updated {name}");
+                            // the previous build is of the lambda's previous source or handler
+                            builds.remove(&name);
+                            rebuilding.insert(name);
+                        }
+                        UpdateMutation::ProjectEnv => println!("project env updated"),
+                    }
+                }
+            }
+            if changes.node_configs {
+                match NodeConfig::read_configs(&project_dir) {
+                    Ok(updated) => {
+                        node_config = Arc::new(updated);
+                        for lambda_spec in configs.lambdas() {
+                            if matches!(lambda_spec.runtime, LambdaRuntimeSpec::Node) {
+                                rebuilding.insert(lambda_spec.name.clone());
+                            }
+                        }
+                    }
+                    Err(err) => println!("\x1b[31m✗\x1b[0m node config error: {err}"),
+                }
+            }
+            if changes.python_configs {
+                for lambda_spec in configs.lambdas() {
+                    if matches!(lambda_spec.runtime, LambdaRuntimeSpec::Python) {
+                        rebuilding.insert(lambda_spec.name.clone());
+                    }
+                }
+            }
+            for lambda_spec in configs.lambdas() {
+                let affected = match builds.get(&lambda_spec.name) {
+                    Some(Ok(build_manifest)) => changes.affects_build(build_manifest),
+                    // without a manifest the sources of a failed build are only known by its entrypoint
+                    Some(Err(_)) | None => changes.sources.contains(&lambda_spec.source),
+                };
+                if affected {
+                    rebuilding.insert(lambda_spec.name.clone());
+                }
+            }
+            let lambda_specs: Vec<Arc<LambdaSpec>> = rebuilding
+                .iter()
+                .filter_map(|name| configs.lambda_by_name(name))
+                .collect();
+            build_lambdas(
+                &project_dir,
+                &configs,
+                &node_config,
+                lambda_specs,
+                &mut builds,
+            )
+            .await;
+        }
+        Ok(())
+    }
+}
+
+async fn build_lambdas(
+    project_dir: &Arc<PathBuf>,
+    configs: &LLLConfigs,
+    node_config: &Arc<NodeConfig>,
+    lambda_specs: Vec<Arc<LambdaSpec>>,
+    builds: &mut LambdaBuilds,
+) {
+    let mut join_set = JoinSet::new();
+    for lambda_spec in lambda_specs {
+        let project_dir = project_dir.clone();
+        let runtime = fn_runtime(configs, &lambda_spec, node_config);
+        let previous_build = match builds.get(&lambda_spec.name) {
+            Some(Ok(build_manifest)) => Some(build_manifest.clone()),
+            _ => None,
+        };
+        join_set.spawn(async move {
+            let start = Instant::now();
+            let build_result =
+                build_local_fn(&project_dir, &lambda_spec, runtime, previous_build).await;
+            (lambda_spec, build_result, start.elapsed())
+        });
+    }
+    while let Some(join_result) = join_set.join_next().await {
+        let (lambda_spec, build_result, duration) = join_result.unwrap();
+        match &build_result {
+            Ok(_) => println!(
+                "\x1b[32m✔\x1b[0m built {} in {}ms",
+                lambda_spec.name,
+                duration.as_millis()
+            ),
+            Err(err) => println!("\x1b[31m✗\x1b[0m {}: {err}", lambda_spec.name),
+        }
+        builds.insert(lambda_spec.name.clone(), build_result);
+    }
+}